@@ -0,0 +1,191 @@
+use crate::data::PSP22Event;
+use crate::errors::AccessControlError;
+use crate::events::{RoleGranted, RoleRevoked};
+use ink::{
+    prelude::{vec, vec::Vec},
+    primitives::AccountId,
+    storage::Mapping,
+};
+
+/// Identifier of a role, chosen by the contract (e.g. `const MINTER: RoleType = 1;`).
+pub type RoleType = u32;
+
+/// The role allowed to grant and revoke all roles, including itself.
+pub const DEFAULT_ADMIN_ROLE: RoleType = 0;
+
+/// A class implementing the internal logic of the `AccessControl` trait: accounts granted
+/// roles allowed to call privileged messages, like `OwnableData` with a single owner.
+///
+/// The contract calls `ensure_role(role, caller)` at the start of each privileged message.
+/// All roles are administered by `DEFAULT_ADMIN_ROLE`, granted to the initial admin
+/// in `new`. An admin can renounce its own role, leaving the roles without an admin.
+#[ink::storage_item]
+#[derive(Debug, Default)]
+pub struct AccessControlData {
+    members: Mapping<(RoleType, AccountId), ()>,
+}
+
+impl AccessControlData {
+    /// Creates a new state with `DEFAULT_ADMIN_ROLE` granted to `admin`.
+    /// Returns the resulting `RoleGranted` event, with `admin` as the sender.
+    pub fn new(admin: AccountId) -> (AccessControlData, Vec<PSP22Event>) {
+        let mut data = AccessControlData::default();
+        let events = data.grant(DEFAULT_ADMIN_ROLE, admin, admin);
+        (data, events)
+    }
+
+    /// Returns `true` if `account` has `role`.
+    pub fn has_role(&self, role: RoleType, account: AccountId) -> bool {
+        self.members.contains((role, account))
+    }
+
+    /// Returns `MissingRole` error if `caller` does not have `role`.
+    pub fn ensure_role(&self, role: RoleType, caller: AccountId) -> Result<(), AccessControlError> {
+        if !self.has_role(role, caller) {
+            return Err(AccessControlError::MissingRole);
+        }
+        Ok(())
+    }
+
+    /// Grants `role` to `account`, on behalf of `caller`. Granting a role the account
+    /// already has emits no event.
+    ///
+    /// Returns `MissingRole` error if `caller` is not an admin.
+    pub fn grant_role(
+        &mut self,
+        caller: AccountId,
+        role: RoleType,
+        account: AccountId,
+    ) -> Result<Vec<PSP22Event>, AccessControlError> {
+        self.ensure_role(DEFAULT_ADMIN_ROLE, caller)?;
+        Ok(self.grant(role, account, caller))
+    }
+
+    /// Revokes `role` from `account`, on behalf of `caller`. Revoking a role the account
+    /// does not have emits no event.
+    ///
+    /// Returns `MissingRole` error if `caller` is not an admin.
+    pub fn revoke_role(
+        &mut self,
+        caller: AccountId,
+        role: RoleType,
+        account: AccountId,
+    ) -> Result<Vec<PSP22Event>, AccessControlError> {
+        self.ensure_role(DEFAULT_ADMIN_ROLE, caller)?;
+        Ok(self.revoke(role, account, caller))
+    }
+
+    /// Revokes `role` from `caller` itself, `account` is a confirmation of the caller.
+    ///
+    /// Returns `InvalidCaller` error if `account` is not `caller`.
+    pub fn renounce_role(
+        &mut self,
+        caller: AccountId,
+        role: RoleType,
+        account: AccountId,
+    ) -> Result<Vec<PSP22Event>, AccessControlError> {
+        if caller != account {
+            return Err(AccessControlError::InvalidCaller);
+        }
+        Ok(self.revoke(role, account, caller))
+    }
+
+    fn grant(&mut self, role: RoleType, account: AccountId, sender: AccountId) -> Vec<PSP22Event> {
+        if self.members.insert((role, account), &()).is_some() {
+            return vec![];
+        }
+        vec![PSP22Event::RoleGranted(RoleGranted {
+            role,
+            account,
+            sender,
+        })]
+    }
+
+    fn revoke(&mut self, role: RoleType, account: AccountId, sender: AccountId) -> Vec<PSP22Event> {
+        if self.members.take((role, account)).is_none() {
+            return vec![];
+        }
+        vec![PSP22Event::RoleRevoked(RoleRevoked {
+            role,
+            account,
+            sender,
+        })]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink::env::{test::default_accounts, DefaultEnvironment as E};
+
+    const MINTER: RoleType = 1;
+
+    #[ink::test]
+    fn granting_emits_one_event() {
+        let acc = default_accounts::<E>();
+        let (mut roles, events) = AccessControlData::new(acc.alice);
+        assert!(matches!(
+            &events[..],
+            [PSP22Event::RoleGranted(e)]
+                if e.role == DEFAULT_ADMIN_ROLE && e.account == acc.alice && e.sender == acc.alice
+        ));
+
+        assert_eq!(
+            roles.grant_role(acc.bob, MINTER, acc.bob).err(),
+            Some(AccessControlError::MissingRole)
+        );
+        let events = roles.grant_role(acc.alice, MINTER, acc.bob).unwrap();
+        assert!(matches!(
+            &events[..],
+            [PSP22Event::RoleGranted(e)]
+                if e.role == MINTER && e.account == acc.bob && e.sender == acc.alice
+        ));
+        assert!(roles.has_role(MINTER, acc.bob));
+        assert!(roles.ensure_role(MINTER, acc.bob).is_ok());
+        assert!(roles.ensure_role(MINTER, acc.alice).is_err());
+        // Already granted
+        assert!(roles
+            .grant_role(acc.alice, MINTER, acc.bob)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[ink::test]
+    fn revoking_and_renouncing_emit_one_event() {
+        let acc = default_accounts::<E>();
+        let (mut roles, _) = AccessControlData::new(acc.alice);
+        roles.grant_role(acc.alice, MINTER, acc.bob).unwrap();
+        roles.grant_role(acc.alice, MINTER, acc.charlie).unwrap();
+
+        assert_eq!(
+            roles.revoke_role(acc.bob, MINTER, acc.charlie).err(),
+            Some(AccessControlError::MissingRole)
+        );
+        let events = roles.revoke_role(acc.alice, MINTER, acc.bob).unwrap();
+        assert!(matches!(
+            &events[..],
+            [PSP22Event::RoleRevoked(e)]
+                if e.role == MINTER && e.account == acc.bob && e.sender == acc.alice
+        ));
+        assert!(!roles.has_role(MINTER, acc.bob));
+        // Already revoked
+        assert!(roles
+            .revoke_role(acc.alice, MINTER, acc.bob)
+            .unwrap()
+            .is_empty());
+
+        assert_eq!(
+            roles.renounce_role(acc.alice, MINTER, acc.charlie).err(),
+            Some(AccessControlError::InvalidCaller)
+        );
+        let events = roles
+            .renounce_role(acc.charlie, MINTER, acc.charlie)
+            .unwrap();
+        assert!(matches!(
+            &events[..],
+            [PSP22Event::RoleRevoked(e)]
+                if e.role == MINTER && e.account == acc.charlie && e.sender == acc.charlie
+        ));
+        assert!(!roles.has_role(MINTER, acc.charlie));
+    }
+}
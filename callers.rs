@@ -0,0 +1,470 @@
+//! Typed callers of the messages of other PSP22 tokens.
+//!
+//! Each `*Ref` struct wraps the address of a token and exposes the messages
+//! of the corresponding trait as methods building a `CallBuilder` with the selector
+//! from `selectors`, the message arguments and the return type already set.
+//! The call can be adjusted (e.g. the gas limit or transferred value) before being made
+//! with `invoke` or `try_invoke`:
+//!
+//! ```ignore
+//! let token = PSP22Ref::new(address);
+//! let balance = token.balance_of(owner).invoke();
+//! token.transfer(to, balance, Vec::new()).try_invoke()??;
+//! ```
+//!
+//! `PSP22Contract` makes the calls right away instead, flattening their results
+//! into `PSP22Error`, with the options of all calls set once:
+//!
+//! ```ignore
+//! let token = PSP22Contract::new(address).with_gas_limit(limit).lenient();
+//! let balance = token.balance_of(owner)?;
+//! token.transfer(to, balance, Vec::new())?;
+//! ```
+
+use crate::errors::PSP22Error;
+use crate::selectors;
+use ink::{
+    env::{
+        call::{
+            build_call,
+            utils::{Argument, ArgumentList, EmptyArgumentList, ReturnType, Set},
+            Call, CallBuilder, ExecutionInput, Selector,
+        },
+        DefaultEnvironment, Environment,
+    },
+    prelude::{string::String, vec::Vec},
+    primitives::AccountId,
+    scale::{Decode, Encode, Input},
+};
+
+/// A call to a PSP22 token with the arguments `Args` and the return type `R`.
+pub type PSP22Call<Args, R> = CallBuilder<
+    DefaultEnvironment,
+    Set<Call<DefaultEnvironment>>,
+    Set<ExecutionInput<Args>>,
+    Set<ReturnType<R>>,
+>;
+
+// Argument lists of the calls, in the reverse order of pushing
+type Args0 = EmptyArgumentList;
+type Args1<A> = ArgumentList<Argument<A>, Args0>;
+type Args2<A, B> = ArgumentList<Argument<B>, Args1<A>>;
+type Args3<A, B, C> = ArgumentList<Argument<C>, Args2<A, B>>;
+type Args4<A, B, C, D> = ArgumentList<Argument<D>, Args3<A, B, C>>;
+
+fn input(selector: [u8; 4]) -> ExecutionInput<Args0> {
+    ExecutionInput::new(Selector::new(selector))
+}
+
+fn call<Args, R>(address: AccountId, input: ExecutionInput<Args>) -> PSP22Call<Args, R> {
+    build_call::<DefaultEnvironment>()
+        .call(address)
+        .exec_input(input)
+        .returns::<R>()
+}
+
+/// Caller of the `PSP22` messages of the token at `address`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PSP22Ref {
+    pub address: AccountId,
+}
+
+impl PSP22Ref {
+    pub fn new(address: AccountId) -> Self {
+        Self { address }
+    }
+
+    pub fn total_supply(&self) -> PSP22Call<Args0, u128> {
+        call(self.address, input(selectors::TOTAL_SUPPLY))
+    }
+
+    pub fn balance_of(&self, owner: AccountId) -> PSP22Call<Args1<AccountId>, u128> {
+        call(self.address, input(selectors::BALANCE_OF).push_arg(owner))
+    }
+
+    pub fn allowance(
+        &self,
+        owner: AccountId,
+        spender: AccountId,
+    ) -> PSP22Call<Args2<AccountId, AccountId>, u128> {
+        let input = input(selectors::ALLOWANCE)
+            .push_arg(owner)
+            .push_arg(spender);
+        call(self.address, input)
+    }
+
+    pub fn transfer(
+        &self,
+        to: AccountId,
+        value: u128,
+        data: Vec<u8>,
+    ) -> PSP22Call<Args3<AccountId, u128, Vec<u8>>, Result<(), PSP22Error>> {
+        let input = input(selectors::TRANSFER)
+            .push_arg(to)
+            .push_arg(value)
+            .push_arg(data);
+        call(self.address, input)
+    }
+
+    pub fn transfer_from(
+        &self,
+        from: AccountId,
+        to: AccountId,
+        value: u128,
+        data: Vec<u8>,
+    ) -> PSP22Call<Args4<AccountId, AccountId, u128, Vec<u8>>, Result<(), PSP22Error>> {
+        let input = input(selectors::TRANSFER_FROM)
+            .push_arg(from)
+            .push_arg(to)
+            .push_arg(value)
+            .push_arg(data);
+        call(self.address, input)
+    }
+
+    pub fn approve(
+        &self,
+        spender: AccountId,
+        value: u128,
+    ) -> PSP22Call<Args2<AccountId, u128>, Result<(), PSP22Error>> {
+        let input = input(selectors::APPROVE).push_arg(spender).push_arg(value);
+        call(self.address, input)
+    }
+
+    pub fn increase_allowance(
+        &self,
+        spender: AccountId,
+        delta_value: u128,
+    ) -> PSP22Call<Args2<AccountId, u128>, Result<(), PSP22Error>> {
+        let input = input(selectors::INCREASE_ALLOWANCE)
+            .push_arg(spender)
+            .push_arg(delta_value);
+        call(self.address, input)
+    }
+
+    pub fn decrease_allowance(
+        &self,
+        spender: AccountId,
+        delta_value: u128,
+    ) -> PSP22Call<Args2<AccountId, u128>, Result<(), PSP22Error>> {
+        let input = input(selectors::DECREASE_ALLOWANCE)
+            .push_arg(spender)
+            .push_arg(delta_value);
+        call(self.address, input)
+    }
+}
+
+/// Caller of the `PSP22Metadata` messages of the token at `address`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PSP22MetadataRef {
+    pub address: AccountId,
+}
+
+impl PSP22MetadataRef {
+    pub fn new(address: AccountId) -> Self {
+        Self { address }
+    }
+
+    pub fn token_name(&self) -> PSP22Call<Args0, Option<String>> {
+        call(self.address, input(selectors::TOKEN_NAME))
+    }
+
+    pub fn token_symbol(&self) -> PSP22Call<Args0, Option<String>> {
+        call(self.address, input(selectors::TOKEN_SYMBOL))
+    }
+
+    pub fn token_decimals(&self) -> PSP22Call<Args0, u8> {
+        call(self.address, input(selectors::TOKEN_DECIMALS))
+    }
+
+    pub fn token_uri(&self) -> PSP22Call<Args0, Option<String>> {
+        call(self.address, input(selectors::TOKEN_URI))
+    }
+}
+
+/// Caller of the `PSP22Mintable` messages of the token at `address`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PSP22MintableRef {
+    pub address: AccountId,
+}
+
+impl PSP22MintableRef {
+    pub fn new(address: AccountId) -> Self {
+        Self { address }
+    }
+
+    pub fn mint(&self, value: u128) -> PSP22Call<Args1<u128>, Result<(), PSP22Error>> {
+        call(self.address, input(selectors::MINT).push_arg(value))
+    }
+}
+
+/// Caller of the `PSP22Burnable` messages of the token at `address`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PSP22BurnableRef {
+    pub address: AccountId,
+}
+
+impl PSP22BurnableRef {
+    pub fn new(address: AccountId) -> Self {
+        Self { address }
+    }
+
+    pub fn burn(&self, value: u128) -> PSP22Call<Args1<u128>, Result<(), PSP22Error>> {
+        call(self.address, input(selectors::BURN).push_arg(value))
+    }
+}
+
+/// A PSP22 token at `address` called in the environment `E`, with the options
+/// set by the `with_*` methods and `lenient` applied to all calls.
+///
+/// The methods mirror the `PSP22` trait and make the call right away. Errors returned
+/// by the token are passed through, failures of the call itself (e.g. a trap or running
+/// out of gas) become `UnderlyingCallFailed`.
+#[derive(Clone)]
+pub struct PSP22Contract<E: Environment = DefaultEnvironment> {
+    address: AccountId,
+    gas_limit: u64,
+    transferred_value: Option<E::Balance>,
+    lenient: bool,
+}
+
+impl PSP22Contract {
+    /// Creates a caller of the token at `address` in `DefaultEnvironment`,
+    /// without a gas limit and transferring no value.
+    pub fn new(address: AccountId) -> Self {
+        Self::new_in_env(address)
+    }
+}
+
+impl<E: Environment<AccountId = AccountId>> PSP22Contract<E> {
+    /// Same as `new`, with the calls made in the environment `E`.
+    pub fn new_in_env(address: AccountId) -> Self {
+        Self {
+            address,
+            gas_limit: 0,
+            transferred_value: None,
+            lenient: false,
+        }
+    }
+
+    /// Limits the gas of each call to `limit`, `0` for no limit.
+    pub fn with_gas_limit(mut self, limit: u64) -> Self {
+        self.gas_limit = limit;
+        self
+    }
+
+    /// Transfers `value` of the native currency with each call.
+    pub fn with_transferred_value(mut self, value: E::Balance) -> Self {
+        self.transferred_value = Some(value);
+        self
+    }
+
+    /// Accepts tokens which return nothing from `transfer`, `transfer_from`, `approve`,
+    /// `increase_allowance` and `decrease_allowance` instead of `Result<(), PSP22Error>`,
+    /// treating the missing return value as success. Such calls fail in the default
+    /// strict mode. Errors returned by the token are passed through in both modes.
+    pub fn lenient(mut self) -> Self {
+        self.lenient = true;
+        self
+    }
+
+    /// Returns the address of the token.
+    pub fn address(&self) -> AccountId {
+        self.address
+    }
+
+    /// Returns the gas limit of the calls, `0` for no limit.
+    pub fn gas_limit(&self) -> u64 {
+        self.gas_limit
+    }
+
+    /// Returns the native value transferred with each call, if any.
+    pub fn transferred_value(&self) -> Option<E::Balance> {
+        self.transferred_value
+    }
+
+    /// Returns `true` if missing return values are treated as success.
+    pub fn is_lenient(&self) -> bool {
+        self.lenient
+    }
+
+    pub fn total_supply(&self) -> Result<u128, PSP22Error> {
+        self.query(input(selectors::TOTAL_SUPPLY))
+    }
+
+    pub fn balance_of(&self, owner: AccountId) -> Result<u128, PSP22Error> {
+        self.query(input(selectors::BALANCE_OF).push_arg(owner))
+    }
+
+    pub fn allowance(&self, owner: AccountId, spender: AccountId) -> Result<u128, PSP22Error> {
+        let input = input(selectors::ALLOWANCE)
+            .push_arg(owner)
+            .push_arg(spender);
+        self.query(input)
+    }
+
+    pub fn transfer(&self, to: AccountId, value: u128, data: Vec<u8>) -> Result<(), PSP22Error> {
+        let input = input(selectors::TRANSFER)
+            .push_arg(to)
+            .push_arg(value)
+            .push_arg(data);
+        self.execute(input)
+    }
+
+    pub fn transfer_from(
+        &self,
+        from: AccountId,
+        to: AccountId,
+        value: u128,
+        data: Vec<u8>,
+    ) -> Result<(), PSP22Error> {
+        let input = input(selectors::TRANSFER_FROM)
+            .push_arg(from)
+            .push_arg(to)
+            .push_arg(value)
+            .push_arg(data);
+        self.execute(input)
+    }
+
+    pub fn approve(&self, spender: AccountId, value: u128) -> Result<(), PSP22Error> {
+        self.execute(input(selectors::APPROVE).push_arg(spender).push_arg(value))
+    }
+
+    pub fn increase_allowance(
+        &self,
+        spender: AccountId,
+        delta_value: u128,
+    ) -> Result<(), PSP22Error> {
+        let input = input(selectors::INCREASE_ALLOWANCE)
+            .push_arg(spender)
+            .push_arg(delta_value);
+        self.execute(input)
+    }
+
+    pub fn decrease_allowance(
+        &self,
+        spender: AccountId,
+        delta_value: u128,
+    ) -> Result<(), PSP22Error> {
+        let input = input(selectors::DECREASE_ALLOWANCE)
+            .push_arg(spender)
+            .push_arg(delta_value);
+        self.execute(input)
+    }
+
+    // Makes a call returning `R`.
+    fn query<Args: Encode, R: Decode>(&self, input: ExecutionInput<Args>) -> Result<R, PSP22Error> {
+        let mut call = build_call::<E>()
+            .call(self.address)
+            .gas_limit(self.gas_limit);
+        if let Some(value) = self.transferred_value {
+            call = call.transferred_value(value);
+        }
+        match call.exec_input(input).returns::<R>().try_invoke() {
+            Ok(Ok(value)) => Ok(value),
+            _ => Err(PSP22Error::UnderlyingCallFailed),
+        }
+    }
+
+    // Makes a call returning `Result<(), PSP22Error>` or, in lenient mode, nothing.
+    fn execute<Args: Encode>(&self, input: ExecutionInput<Args>) -> Result<(), PSP22Error> {
+        match self.query(input)? {
+            MaybeResult::Returned(result) => result,
+            MaybeResult::Missing if self.lenient => Ok(()),
+            MaybeResult::Missing => Err(PSP22Error::UnderlyingCallFailed),
+        }
+    }
+}
+
+// Return value of a message which should return `Result<(), PSP22Error>`,
+// `Missing` if the message returned nothing (i.e. `()`, encoded as no bytes).
+#[derive(Debug, PartialEq)]
+enum MaybeResult {
+    Returned(Result<(), PSP22Error>),
+    Missing,
+}
+
+impl Decode for MaybeResult {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, ink::scale::Error> {
+        if input.remaining_len()? == Some(0) {
+            return Ok(MaybeResult::Missing);
+        }
+        Decode::decode(input).map(MaybeResult::Returned)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink::env::{test::default_accounts, DefaultEnvironment as E};
+
+    fn encoded<Args: Encode, R>(call: &PSP22Call<Args, R>) -> Vec<u8> {
+        call.params().exec_input().encode()
+    }
+
+    #[ink::test]
+    fn calls_encode_selector_and_arguments() {
+        let acc = default_accounts::<E>();
+        let token = PSP22Ref::new(acc.django);
+
+        let mut expected = selectors::TRANSFER_FROM.to_vec();
+        (acc.alice, acc.bob, 100u128, vec![1u8, 2]).encode_to(&mut expected);
+        assert_eq!(
+            encoded(&token.transfer_from(acc.alice, acc.bob, 100, vec![1, 2])),
+            expected
+        );
+
+        let mut expected = selectors::BALANCE_OF.to_vec();
+        acc.alice.encode_to(&mut expected);
+        assert_eq!(encoded(&token.balance_of(acc.alice)), expected);
+
+        let mut expected = selectors::MINT.to_vec();
+        7u128.encode_to(&mut expected);
+        assert_eq!(
+            encoded(&PSP22MintableRef::new(acc.django).mint(7)),
+            expected
+        );
+        assert_eq!(
+            encoded(&PSP22MetadataRef::new(acc.django).token_decimals()),
+            selectors::TOKEN_DECIMALS.to_vec()
+        );
+    }
+
+    #[test]
+    fn options_are_set_by_builder() {
+        let acc = default_accounts::<E>();
+        let token = PSP22Contract::new(acc.django);
+        assert_eq!(token.address(), acc.django);
+        assert_eq!(token.gas_limit(), 0);
+        assert_eq!(token.transferred_value(), None);
+        assert!(!token.is_lenient());
+
+        let token = token
+            .with_gas_limit(5_000_000_000)
+            .with_transferred_value(10)
+            .lenient();
+        assert_eq!(token.gas_limit(), 5_000_000_000);
+        assert_eq!(token.transferred_value(), Some(10));
+        assert!(token.is_lenient());
+    }
+
+    #[test]
+    fn missing_return_value_is_decoded() {
+        let decode = |bytes: Vec<u8>| MaybeResult::decode(&mut &bytes[..]);
+
+        assert_eq!(decode(vec![]), Ok(MaybeResult::Missing));
+        assert_eq!(
+            decode(Ok::<(), PSP22Error>(()).encode()),
+            Ok(MaybeResult::Returned(Ok(())))
+        );
+        assert_eq!(
+            decode(Err::<(), _>(PSP22Error::InsufficientBalance).encode()),
+            Ok(MaybeResult::Returned(Err(PSP22Error::InsufficientBalance)))
+        );
+        // Returned by a message of a PSP22 token returning `()`, after `Ok` of `MessageResult`
+        let message_result = ink::MessageResult::<()>::Ok(()).encode();
+        assert_eq!(
+            ink::MessageResult::<MaybeResult>::decode(&mut &message_result[..]),
+            Ok(Ok(MaybeResult::Missing))
+        );
+    }
+}
@@ -0,0 +1,40 @@
+use ink::env::DefaultEnvironment;
+
+/// The clock used by historical extensions (snapshots, votes) to key their checkpoints,
+/// following the ERC-6372 convention.
+///
+/// The clock is selected when the extension data is constructed and must not be changed
+/// afterwards, as checkpoints recorded with different clocks are not comparable.
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Clock {
+    /// Timepoints are block numbers.
+    #[default]
+    BlockNumber,
+    /// Timepoints are block timestamps (in milliseconds).
+    Timestamp,
+}
+
+impl Clock {
+    /// `CLOCK_MODE` of the block number based clock.
+    pub const BLOCK_NUMBER_MODE: &'static str = "mode=blocknumber&from=default";
+    /// `CLOCK_MODE` of the timestamp based clock.
+    pub const TIMESTAMP_MODE: &'static str = "mode=timestamp";
+
+    /// Returns the current timepoint.
+    pub fn clock(&self) -> u64 {
+        match self {
+            Clock::BlockNumber => ink::env::block_number::<DefaultEnvironment>().into(),
+            Clock::Timestamp => ink::env::block_timestamp::<DefaultEnvironment>(),
+        }
+    }
+
+    /// Returns the machine-readable description of the clock (ERC-6372 `CLOCK_MODE`).
+    pub fn clock_mode(&self) -> &'static str {
+        match self {
+            Clock::BlockNumber => Self::BLOCK_NUMBER_MODE,
+            Clock::Timestamp => Self::TIMESTAMP_MODE,
+        }
+    }
+}
@@ -0,0 +1,170 @@
+//! Compatibility with the storage of other PSP22 implementations, for tokens upgraded
+//! in place (with `set_code_hash`) to code using `PSP22Data`.
+
+/// Reading the balances of OpenBrush's `psp22::Data` and migrating them to `PSP22Data`.
+///
+/// OpenBrush keeps the total supply in a `Lazy` and the balances in a `Mapping`, each under
+/// a root key derived by OpenBrush from the names of the struct and the field. The keys are
+/// listed in the storage layout of the old contract's metadata (`root_key` of the `supply`
+/// and `balances` fields) and have to be given to `OpenBrushPSP22Storage::new` exactly.
+///
+/// Balances are migrated lazily: the contract calls `migrate_account` for every account
+/// before touching it (e.g. for the sender and the recipient of each transfer), and reports
+/// balances and the total supply with `balance_of` and `total_supply`, which include
+/// the amounts not migrated yet. Allowances are not migrated, holders have to approve again.
+///
+/// The root storage struct of the new code must still decode from the storage cell
+/// written by the old code, which is up to the contract. A separate migration contract
+/// (see `examples/migrator`) avoids these pitfalls and is the recommended way.
+pub mod openbrush {
+    use crate::data::PSP22Data;
+    use crate::errors::PSP22Error;
+    use ink::primitives::AccountId;
+
+    /// The root keys of the fields of OpenBrush's `psp22::Data` in the old contract.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct OpenBrushPSP22Storage {
+        supply_key: u32,
+        balances_key: u32,
+    }
+
+    impl OpenBrushPSP22Storage {
+        /// Creates the legacy layout with the root keys of the `supply` and `balances` fields.
+        pub const fn new(supply_key: u32, balances_key: u32) -> Self {
+            Self {
+                supply_key,
+                balances_key,
+            }
+        }
+
+        /// Returns the total supply of the tokens not migrated yet.
+        pub fn legacy_total_supply(&self) -> u128 {
+            ink::env::get_contract_storage::<u32, u128>(&self.supply_key)
+                .ok()
+                .flatten()
+                .unwrap_or_default()
+        }
+
+        /// Returns the balance of `account` not migrated yet.
+        pub fn legacy_balance_of(&self, account: AccountId) -> u128 {
+            // The key of a `Mapping` entry is the encoded pair of the root key and the entry key
+            ink::env::get_contract_storage::<(u32, AccountId), u128>(&(self.balances_key, account))
+                .ok()
+                .flatten()
+                .unwrap_or_default()
+        }
+
+        /// Returns the balance of `account`, migrated or not.
+        pub fn balance_of(&self, data: &PSP22Data, account: AccountId) -> u128 {
+            data.balance_of(account)
+                .saturating_add(self.legacy_balance_of(account))
+        }
+
+        /// Returns the total supply of the token, migrated or not.
+        pub fn total_supply(&self, data: &PSP22Data) -> u128 {
+            data.total_supply()
+                .saturating_add(self.legacy_total_supply())
+        }
+
+        /// Moves the legacy balance of `account` to `data`, removing it from the legacy
+        /// storage, and returns the migrated amount. Does nothing for accounts already
+        /// migrated (or never holding tokens).
+        ///
+        /// No events are returned, as no tokens are created or moved from the point
+        /// of view of the holders. Fails like `PSP22Data::mint`, e.g. while paused.
+        pub fn migrate_account(
+            &self,
+            data: &mut PSP22Data,
+            account: AccountId,
+        ) -> Result<u128, PSP22Error> {
+            let balance = self.legacy_balance_of(account);
+            if balance == 0 {
+                return Ok(0);
+            }
+            data.mint(account, balance)?;
+            ink::env::clear_contract_storage(&(self.balances_key, account));
+            let supply = self.legacy_total_supply().saturating_sub(balance);
+            if supply == 0 {
+                ink::env::clear_contract_storage(&self.supply_key);
+            } else {
+                ink::env::set_contract_storage(&self.supply_key, &supply);
+            }
+            Ok(balance)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink::{
+            env::{test::default_accounts, DefaultEnvironment as E},
+            storage::{traits::ManualKey, Lazy, Mapping},
+        };
+
+        // Arbitrary keys, the keys of a real contract come from its metadata
+        const SUPPLY_KEY: u32 = 0x270a8fc3;
+        const BALANCES_KEY: u32 = 0xc2664826;
+        const LEGACY: OpenBrushPSP22Storage = OpenBrushPSP22Storage::new(SUPPLY_KEY, BALANCES_KEY);
+
+        #[ink::test]
+        fn legacy_keys_match_ink_storage() {
+            let acc = default_accounts::<E>();
+            // OpenBrush stores the fields in ink! `Lazy` and `Mapping` under manual keys
+            let mut supply: Lazy<u128, ManualKey<SUPPLY_KEY>> = Lazy::new();
+            let mut balances: Mapping<AccountId, u128, ManualKey<BALANCES_KEY>> = Mapping::new();
+            supply.set(&1000);
+            balances.insert(acc.alice, &600);
+            balances.insert(acc.bob, &400);
+
+            assert_eq!(LEGACY.legacy_total_supply(), 1000);
+            assert_eq!(LEGACY.legacy_balance_of(acc.alice), 600);
+            assert_eq!(LEGACY.legacy_balance_of(acc.bob), 400);
+            assert_eq!(LEGACY.legacy_balance_of(acc.charlie), 0);
+        }
+
+        #[ink::test]
+        fn balances_are_migrated_on_first_touch() {
+            let acc = default_accounts::<E>();
+            ink::env::set_contract_storage(&SUPPLY_KEY, &1000u128);
+            ink::env::set_contract_storage(&(BALANCES_KEY, acc.alice), &600u128);
+            ink::env::set_contract_storage(&(BALANCES_KEY, acc.bob), &400u128);
+            let mut data = PSP22Data::default();
+
+            assert_eq!(LEGACY.migrate_account(&mut data, acc.alice), Ok(600));
+            assert_eq!(data.balance_of(acc.alice), 600);
+            assert_eq!(LEGACY.legacy_balance_of(acc.alice), 0);
+            assert_eq!(LEGACY.legacy_total_supply(), 400);
+            // Balances and the total supply are the same as before the migration
+            assert_eq!(LEGACY.balance_of(&data, acc.alice), 600);
+            assert_eq!(LEGACY.balance_of(&data, acc.bob), 400);
+            assert_eq!(LEGACY.total_supply(&data), 1000);
+
+            // Already migrated
+            assert_eq!(LEGACY.migrate_account(&mut data, acc.alice), Ok(0));
+            assert_eq!(data.balance_of(acc.alice), 600);
+            // Never held tokens
+            assert_eq!(LEGACY.migrate_account(&mut data, acc.charlie), Ok(0));
+
+            assert_eq!(LEGACY.migrate_account(&mut data, acc.bob), Ok(400));
+            assert_eq!(data.total_supply(), 1000);
+            assert_eq!(LEGACY.legacy_total_supply(), 0);
+            assert_eq!(LEGACY.total_supply(&data), 1000);
+        }
+
+        #[ink::test]
+        fn failed_migration_keeps_legacy_balance() {
+            let acc = default_accounts::<E>();
+            ink::env::set_contract_storage(&SUPPLY_KEY, &1000u128);
+            ink::env::set_contract_storage(&(BALANCES_KEY, acc.alice), &1000u128);
+            let mut data = PSP22Data::default();
+            data.pause();
+
+            assert_eq!(
+                LEGACY.migrate_account(&mut data, acc.alice),
+                Err(PSP22Error::Paused)
+            );
+            assert_eq!(LEGACY.legacy_balance_of(acc.alice), 1000);
+            assert_eq!(LEGACY.total_supply(&data), 1000);
+        }
+    }
+}
@@ -1,17 +1,119 @@
+//! `PSP22Data`, the state of a PSP22 token and the logic of all its operations.
+//!
+//! `PSP22Data` does not call other contracts nor depend on the chain environment: it only
+//! reads and writes its own storage, so its methods can be tested with `#[ink::test]`
+//! alone, with any `AccountId`s. Operations calling another token, like wrapping
+//! (`PSP22WrapperData::deposit` etc.), live in separate types taking `&mut PSP22Data`.
+
 use crate::errors::PSP22Error;
-use crate::events::{Approval, Transfer};
-use ink::prelude::string::String;
+use crate::events::{
+    AddedToBlacklist, AddedToWhitelist, AirdropClaimed, Approval, BridgedIn, BridgedOut,
+    CallGasLimitUpdated, DelegateChanged, DelegateVotesChanged, FlashLoan, MintCancelled,
+    MintQueued, OwnershipTransferred, PermitUsed, RemovedFromBlacklist, RemovedFromWhitelist,
+    RoleGranted, RoleRevoked, SnapshotCreated, StorageVersion, TokensReleased, Transfer,
+    TransferWithData, VestingScheduleCreated,
+};
 use ink::{
-    prelude::{vec, vec::Vec},
+    prelude::vec::Vec,
     primitives::AccountId,
-    storage::Mapping,
+    storage::{traits::ManualKey, Lazy, Mapping},
 };
 
 /// Common wrapper type for events emitted during operations that change the
 /// state of PSP22Data struct.
+///
+/// `Minted` and `Burned` describe the intent of an operation more explicitly,
+/// but on-chain they have to be emitted as standard `Transfer` events
+/// (with `None` sender or recipient respectively), see `PSP22Event::as_transfer`.
 pub enum PSP22Event {
     Transfer(Transfer),
     Approval(Approval),
+    PermitUsed(PermitUsed),
+    TransferWithData(TransferWithData),
+    Minted { to: AccountId, value: u128 },
+    Burned { from: AccountId, value: u128 },
+    SnapshotCreated(SnapshotCreated),
+    DelegateChanged(DelegateChanged),
+    DelegateVotesChanged(DelegateVotesChanged),
+    FlashLoan(FlashLoan),
+    VestingScheduleCreated(VestingScheduleCreated),
+    TokensReleased(TokensReleased),
+    AirdropClaimed(AirdropClaimed),
+    MintQueued(MintQueued),
+    MintCancelled(MintCancelled),
+    AddedToWhitelist(AddedToWhitelist),
+    RemovedFromWhitelist(RemovedFromWhitelist),
+    BridgedIn(BridgedIn),
+    BridgedOut(BridgedOut),
+    AddedToBlacklist(AddedToBlacklist),
+    RemovedFromBlacklist(RemovedFromBlacklist),
+    OwnershipTransferred(OwnershipTransferred),
+    RoleGranted(RoleGranted),
+    RoleRevoked(RoleRevoked),
+    CallGasLimitUpdated(CallGasLimitUpdated),
+    StorageVersion(StorageVersion),
+}
+
+impl PSP22Event {
+    /// Returns the standard `Transfer` event corresponding to `Transfer`, `Minted`
+    /// and `Burned` events, and `None` for all other events.
+    pub fn as_transfer(&self) -> Option<Transfer> {
+        match self {
+            PSP22Event::Transfer(e) => Some(Transfer {
+                from: e.from,
+                to: e.to,
+                value: e.value,
+            }),
+            PSP22Event::Minted { to, value } => Some(Transfer {
+                from: None,
+                to: Some(*to),
+                value: *value,
+            }),
+            PSP22Event::Burned { from, value } => Some(Transfer {
+                from: Some(*from),
+                to: None,
+                value: *value,
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// A receiver of events generated by operations on `PSP22Data`.
+///
+/// Methods of `PSP22Data` with the `_with_sink` suffix push events to a sink as they happen,
+/// instead of returning them in a vector. A contract can use a sink emitting events
+/// right away (see `impls::EnvEventSink`), so that no event can be forgotten.
+pub trait EventSink {
+    fn emit(&mut self, event: PSP22Event);
+}
+
+impl EventSink for Vec<PSP22Event> {
+    fn emit(&mut self, event: PSP22Event) {
+        self.push(event);
+    }
+}
+
+/// Balances of both parties after a transfer, returned by the `_with_sink` transfer methods,
+/// so that the contract does not need to query them again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransferOutcome {
+    pub from_balance: u128,
+    pub to_balance: u128,
+}
+
+/// Allowance after an operation on allowances, returned by the `_with_sink` methods.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ApprovalOutcome {
+    pub allowance: u128,
+}
+
+/// Balance of the affected account and the total supply after minting or burning,
+/// returned by the `_with_sink` methods.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SupplyOutcome {
+    pub balance: u128,
+    pub total_supply: u128,
 }
 
 // Shortcut for Approval PSP22Event constructor.
@@ -28,6 +130,111 @@ fn transfer_event(from: Option<AccountId>, to: Option<AccountId>, value: u128) -
     PSP22Event::Transfer(Transfer { from, to, value })
 }
 
+// Emits a Transfer event from `from` to `to` followed by a TransferWithData event,
+// provided that `data` is non-empty.
+fn emit_transfer(
+    sink: &mut impl EventSink,
+    from: AccountId,
+    to: AccountId,
+    value: u128,
+    data: Vec<u8>,
+) {
+    sink.emit(transfer_event(Some(from), Some(to), value));
+    emit_transfer_data(sink, Some(from), Some(to), value, data);
+}
+
+// Emits a TransferWithData event, provided that `data` is non-empty.
+fn emit_transfer_data(
+    sink: &mut impl EventSink,
+    from: Option<AccountId>,
+    to: Option<AccountId>,
+    value: u128,
+    data: Vec<u8>,
+) {
+    if !data.is_empty() {
+        sink.emit(PSP22Event::TransferWithData(TransferWithData {
+            from,
+            to,
+            value,
+            data,
+        }));
+    }
+}
+
+// Arithmetic on balances and allowances.
+//
+// The sum of all balances is equal to the total supply, which never exceeds u128::MAX
+// (it is checked when minting). Hence subtracting from a balance or an allowance may fail
+// because of an insufficient amount, which is reported with an error (`debit`), while
+// adding to a balance cannot overflow as long as the invariant holds (`credit`).
+
+// Subtracts `value` from `amount`, returning `error` if `amount` is insufficient.
+fn debit(amount: u128, value: u128, error: PSP22Error) -> Result<u128, PSP22Error> {
+    amount.checked_sub(value).ok_or(error)
+}
+
+// Adds `value` to a balance. Saturates instead of panicking if the supply invariant was broken.
+fn credit(balance: u128, value: u128) -> u128 {
+    debug_assert!(
+        balance.checked_add(value).is_some(),
+        "balance exceeds u128::MAX"
+    );
+    balance.saturating_add(value)
+}
+
+// Rejects the all-zero account as a recipient if the `zero-address-guard` feature is enabled.
+fn check_recipient(to: AccountId) -> Result<(), PSP22Error> {
+    if cfg!(feature = "zero-address-guard") && to == AccountId::from([0u8; 32]) {
+        return Err(PSP22Error::ZeroRecipientAddress);
+    }
+    Ok(())
+}
+
+/// Storage key of the total supply of `PSP22Data`:
+/// first 4 bytes of `blake2b_256("PSP22Data::total_supply")`.
+pub const TOTAL_SUPPLY_KEY: u32 = 0x9cf57243;
+/// Storage key of the balances mapping of `PSP22Data`:
+/// first 4 bytes of `blake2b_256("PSP22Data::balances")`.
+pub const BALANCES_KEY: u32 = 0x245cdb6c;
+/// Storage key of the allowances mapping of `PSP22Data`:
+/// first 4 bytes of `blake2b_256("PSP22Data::allowances")`.
+pub const ALLOWANCES_KEY: u32 = 0x659cecdf;
+/// Storage key of the lifetime amount of minted tokens of `PSP22Data`:
+/// first 4 bytes of `blake2b_256("PSP22Data::total_minted")`.
+pub const TOTAL_MINTED_KEY: u32 = 0xf17eeef4;
+/// Storage key of the lifetime amount of burned tokens of `PSP22Data`:
+/// first 4 bytes of `blake2b_256("PSP22Data::total_burned")`.
+pub const TOTAL_BURNED_KEY: u32 = 0x268d4b05;
+/// Storage key of the maximal allowance of `PSP22Data`:
+/// first 4 bytes of `blake2b_256("PSP22Data::max_allowance")`.
+pub const MAX_ALLOWANCE_KEY: u32 = 0xe1b5d386;
+/// Storage key of the flag enabling the allowance index of `PSP22Data`:
+/// first 4 bytes of `blake2b_256("PSP22Data::allowance_index")`.
+pub const ALLOWANCE_INDEX_KEY: u32 = 0x578691ac;
+/// Storage key of the allowance index (spenders of each owner) of `PSP22Data`:
+/// first 4 bytes of `blake2b_256("PSP22Data::spenders")`.
+pub const SPENDERS_KEY: u32 = 0x0c1ed35f;
+/// Storage key of the flag enabling the events of self-transfers of `PSP22Data`:
+/// first 4 bytes of `blake2b_256("PSP22Data::self_transfer_events")`.
+pub const SELF_TRANSFER_EVENTS_KEY: u32 = 0x95ec8f29;
+/// Storage key of the version of the storage layout of `PSP22Data`:
+/// first 4 bytes of `blake2b_256("PSP22Data::version")`.
+pub const STORAGE_VERSION_KEY: u32 = 0xd00c3b36;
+/// Storage key of the minimum total supply of `PSP22Data`:
+/// first 4 bytes of `blake2b_256("PSP22Data::min_supply")`.
+pub const MIN_SUPPLY_KEY: u32 = 0xd5c03b19;
+
+/// Version of the storage layout of `PSP22Data` written by this code.
+/// Version 0 is the layout before the fixed storage keys, see `migrate_v0_to_v1`.
+pub const STORAGE_VERSION: u8 = 1;
+
+/// Maximal number of spenders with a non-zero allowance from a single owner
+/// while the allowance index of `PSP22Data` is enabled.
+pub const MAX_SPENDERS: usize = 32;
+
+/// Maximal number of allowances cleared by a single `PSP22Data::revoke_all_approvals` call.
+pub const MAX_REVOKED_PER_CALL: usize = 8;
+
 /// A class implementing the internal logic of a PSP22 token.
 //
 /// Holds the state of all account balances and allowances.
@@ -39,25 +246,131 @@ fn transfer_event(from: Option<AccountId>, to: Option<AccountId>, value: u128) -
 /// the methods that need to know the caller require an additional argument
 /// (compared to transactions defined by the PSP22 standard or the PSP22 trait).
 //
+/// While the token is paused (see `pause`), transfers, minting and burning fail
+/// with `PSP22Error::Paused`. Approvals are not affected.
+//
+/// The total supply, balances, allowances and lifetime counters are stored under fixed keys
+/// (`TOTAL_SUPPLY_KEY`, `BALANCES_KEY` etc.), which do not depend on the name or position of
+/// the `PSP22Data` field in the contract. Only the pause flag is stored inline,
+/// in the storage cell of the contract. Hence a contract can have only one `PSP22Data`.
+///
+/// | Key                        | Value                                 | Type                                  |
+/// |----------------------------|---------------------------------------|---------------------------------------|
+/// | `TOTAL_SUPPLY_KEY`         | total supply                          | `u128`                                |
+/// | `BALANCES_KEY`             | balance of each account               | `Mapping<AccountId, u128>`            |
+/// | `ALLOWANCES_KEY`           | allowance of each (owner, spender)    | `Mapping<(AccountId, AccountId), u128>` |
+/// | `TOTAL_MINTED_KEY`         | lifetime amount of minted tokens      | `u128`                                |
+/// | `TOTAL_BURNED_KEY`         | lifetime amount of burned tokens      | `u128`                                |
+/// | `MAX_ALLOWANCE_KEY`        | cap of allowances                     | `Option<u128>`                        |
+/// | `ALLOWANCE_INDEX_KEY`      | allowance index enabled               | `bool`                                |
+/// | `SPENDERS_KEY`             | spenders of each owner                | `Mapping<AccountId, Vec<AccountId>>`  |
+/// | `SELF_TRANSFER_EVENTS_KEY` | events of self-transfers enabled      | `bool`                                |
+/// | `STORAGE_VERSION_KEY`      | version of the layout (`STORAGE_VERSION`) | `u8`                              |
+/// | `MIN_SUPPLY_KEY`           | minimum total supply after burning    | `u128`                                |
+///
+/// The layout is versioned, contracts upgrading from an older layout have to migrate
+/// their storage (see `migrate_v0_to_v1`).
+//
 /// `lib.rs` contains an example implementation of a smart contract using this class.
 #[ink::storage_item]
 #[derive(Debug, Default)]
 pub struct PSP22Data {
-    total_supply: u128,
-    balances: Mapping<AccountId, u128>,
-    allowances: Mapping<(AccountId, AccountId), u128>,
+    total_supply: Lazy<u128, ManualKey<TOTAL_SUPPLY_KEY>>,
+    balances: Mapping<AccountId, u128, ManualKey<BALANCES_KEY>>,
+    allowances: Mapping<(AccountId, AccountId), u128, ManualKey<ALLOWANCES_KEY>>,
+    paused: bool,
+    total_minted: Lazy<u128, ManualKey<TOTAL_MINTED_KEY>>,
+    total_burned: Lazy<u128, ManualKey<TOTAL_BURNED_KEY>>,
+    max_allowance: Lazy<Option<u128>, ManualKey<MAX_ALLOWANCE_KEY>>,
+    allowance_index: Lazy<bool, ManualKey<ALLOWANCE_INDEX_KEY>>,
+    spenders: Mapping<AccountId, Vec<AccountId>, ManualKey<SPENDERS_KEY>>,
+    self_transfer_events: Lazy<bool, ManualKey<SELF_TRANSFER_EVENTS_KEY>>,
+    version: Lazy<u8, ManualKey<STORAGE_VERSION_KEY>>,
+    min_supply: Lazy<u128, ManualKey<MIN_SUPPLY_KEY>>,
 }
 
 impl PSP22Data {
     /// Creates a token with `supply` balance, initially held by the `creator` account.
     pub fn new(supply: u128, creator: AccountId) -> (PSP22Data, Vec<PSP22Event>) {
+        let mut events = Vec::new();
+        let data = PSP22Data::new_with_sink(supply, creator, &mut events);
+        (data, events)
+    }
+
+    /// Creates a token with `supply` balance, initially held by the `creator` account.
+    /// Pushes the resulting events to `sink`.
+    pub fn new_with_sink(supply: u128, creator: AccountId, sink: &mut impl EventSink) -> PSP22Data {
+        let mut data: PSP22Data = Default::default();
+        data.mark_storage_version();
+        data.mint_with_sink(creator, supply, sink).unwrap();
+        data
+    }
+
+    /// Returns the version of the storage layout: `STORAGE_VERSION` if it is stored,
+    /// which happens when the token is created with `new` (or `new_with_initial_balances`)
+    /// or migrated, and `0` otherwise, meaning that `migrate_v0_to_v1` is still pending.
+    pub fn storage_version(&self) -> u8 {
+        self.version.get().unwrap_or(0)
+    }
+
+    // Returns `true` if the version of the storage layout is stored, which happens
+    // on creation with `new` and on migration.
+    pub(crate) fn is_storage_version_marked(&self) -> bool {
+        self.version.get().is_some()
+    }
+
+    pub(crate) fn mark_storage_version(&mut self) {
+        self.version.set(&STORAGE_VERSION);
+    }
+
+    /// Creates a token with the initial balances of several accounts (e.g. a genesis
+    /// distribution), instead of a single creator. The total supply is the sum of the balances
+    /// and amounts of an account appearing more than once are added up.
+    ///
+    /// Returns a `Minted` event for each entry with a non-zero amount.
+    ///
+    /// Panics if the sum of the amounts exceeds `u128::MAX` or, with the `zero-address-guard`
+    /// feature, if an account is the zero address.
+    pub fn new_with_initial_balances(
+        accounts: Vec<(AccountId, u128)>,
+    ) -> (PSP22Data, Vec<PSP22Event>) {
         let mut data: PSP22Data = Default::default();
-        let events = data.mint(creator, supply).unwrap();
+        data.mark_storage_version();
+        let mut events = Vec::new();
+        for (account, amount) in accounts {
+            data.mint_with_sink(account, amount, &mut events).unwrap();
+        }
         (data, events)
     }
 
     pub fn total_supply(&self) -> u128 {
-        self.total_supply
+        self.total_supply.get().unwrap_or_default()
+    }
+
+    /// Returns the amount of tokens minted over the lifetime of the token,
+    /// including the initial supply.
+    ///
+    /// `total_supply() == total_minted() - total_burned()` always holds: minting fails
+    /// if the lifetime amount of minted tokens would exceed `u128::MAX`, even if the total
+    /// supply would not. Tokens lent in flash loans are not counted, as they are burned
+    /// again in the same transaction.
+    pub fn total_minted(&self) -> u128 {
+        self.total_minted.get().unwrap_or_default()
+    }
+
+    /// Returns the amount of tokens burned over the lifetime of the token.
+    pub fn total_burned(&self) -> u128 {
+        self.total_burned.get().unwrap_or_default()
+    }
+
+    /// Panics if the total supply is not the difference of the lifetime counters.
+    #[cfg(test)]
+    pub fn assert_invariants(&self) {
+        assert_eq!(
+            Some(self.total_supply()),
+            self.total_minted().checked_sub(self.total_burned()),
+            "total supply does not match lifetime counters"
+        );
     }
 
     pub fn balance_of(&self, owner: AccountId) -> u128 {
@@ -68,36 +381,280 @@ impl PSP22Data {
         self.allowances.get((owner, spender)).unwrap_or_default()
     }
 
+    /// Returns `true` if the token is paused.
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Pauses the token: all subsequent transfers, minting and burning fail with
+    /// `PSP22Error::Paused` until `unpause` is called. Access control is left to the contract.
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    /// Unpauses the token.
+    pub fn unpause(&mut self) {
+        self.paused = false;
+    }
+
+    /// Returns the maximal allowance an account can grant, `None` if allowances are unlimited.
+    pub fn max_allowance(&self) -> Option<u128> {
+        self.max_allowance.get().flatten()
+    }
+
+    /// Sets the maximal allowance an account can grant: `approve` and `increase_allowance`
+    /// fail with `PSP22Error::AllowanceCapExceeded` for larger allowances. `None` removes
+    /// the cap. Existing allowances are not affected. Access control is left to the contract.
+    pub fn set_max_allowance(&mut self, max_allowance: Option<u128>) {
+        self.max_allowance.set(&max_allowance);
+    }
+
+    /// Returns the total supply that burning can never go below, 0 by default.
+    pub fn min_supply(&self) -> u128 {
+        self.min_supply.get().unwrap_or_default()
+    }
+
+    /// Sets the total supply that burning can never go below: `burn` (and everything burning
+    /// tokens, e.g. `PSP22WrapperData::withdraw`) fails with `PSP22Error::SupplyBelowMinimum`
+    /// if the total supply would fall below `min_supply`. 0 removes the floor. A total supply
+    /// already below the floor is not affected. Access control is left to the contract.
+    pub fn set_min_supply(&mut self, min_supply: u128) {
+        self.min_supply.set(&min_supply);
+    }
+
+    /// Enables the allowance index: from now on, spenders with a non-zero allowance are listed
+    /// for each owner (see `spenders_of`) and each owner can have at most `MAX_SPENDERS`
+    /// of them. Granting an allowance to one more spender fails with `Custom (too many spenders)`.
+    ///
+    /// Allowances granted before are not indexed, so the index should be enabled
+    /// in the constructor of the contract. It cannot be disabled.
+    pub fn enable_allowance_index(&mut self) {
+        self.allowance_index.set(&true);
+    }
+
+    /// Returns `true` if the allowance index is enabled.
+    pub fn is_allowance_index_enabled(&self) -> bool {
+        self.allowance_index.get().unwrap_or_default()
+    }
+
+    /// Enables the events of self-transfers: from now on, a transfer of a non-zero `value`
+    /// from an account to itself returns a `Transfer` event (and a `TransferWithData` event
+    /// if `data` is non-empty), as expected by some indexers.
+    ///
+    /// By default self-transfers return no events. Either way they leave balances
+    /// and allowances unchanged. Meant to be called
+    /// in the constructor of the contract, it cannot be disabled.
+    pub fn enable_self_transfer_events(&mut self) {
+        self.self_transfer_events.set(&true);
+    }
+
+    /// Returns `true` if the events of self-transfers are enabled.
+    pub fn is_self_transfer_events_enabled(&self) -> bool {
+        self.self_transfer_events.get().unwrap_or_default()
+    }
+
+    /// Returns the spenders with a non-zero allowance granted by `owner` (at most `MAX_SPENDERS`),
+    /// in no particular order. Always empty if the allowance index is disabled.
+    pub fn spenders_of(&self, owner: AccountId) -> Vec<AccountId> {
+        self.spenders.get(owner).unwrap_or_default()
+    }
+
+    /// Returns the spenders with a non-zero allowance granted by `owner` together with
+    /// the allowances, see `spenders_of`.
+    pub fn approvals_of(&self, owner: AccountId) -> Vec<(AccountId, u128)> {
+        self.spenders_of(owner)
+            .into_iter()
+            .map(|spender| (spender, self.allowance(owner, spender)))
+            .collect()
+    }
+
+    /// Clears up to `MAX_REVOKED_PER_CALL` allowances granted by `owner`, taken from
+    /// the allowance index, and returns the number of allowances left to be cleared
+    /// by subsequent calls. The bound keeps the weight of a single call limited.
+    ///
+    /// Returns an `Approval` event with zero amount for each cleared allowance.
+    /// Clears nothing if the allowance index is disabled.
+    pub fn revoke_all_approvals(&mut self, owner: AccountId) -> (u32, Vec<PSP22Event>) {
+        let mut spenders = self.spenders_of(owner);
+        let left = spenders.len().saturating_sub(MAX_REVOKED_PER_CALL);
+        let events = spenders
+            .drain(left..)
+            .map(|spender| {
+                self.allowances.remove((owner, spender));
+                approval_event(owner, spender, 0)
+            })
+            .collect();
+        if spenders.is_empty() {
+            self.spenders.remove(owner);
+        } else {
+            self.spenders.insert(owner, &spenders);
+        }
+        // `left` is at most `MAX_SPENDERS`
+        (left as u32, events)
+    }
+
+    /// Clears all the allowances granted by `owner`, taken from the allowance index,
+    /// at once. Unlike `revoke_all_approvals` it is not split into several calls:
+    /// the index holds at most `MAX_SPENDERS` spenders, which bounds the weight anyway.
+    ///
+    /// Returns an `Approval` event with zero amount for each cleared allowance.
+    /// Returns `Custom (allowance index disabled)` error if the allowance index is disabled,
+    /// as the allowances of `owner` cannot be listed then.
+    pub fn revoke_all(&mut self, owner: AccountId) -> Result<Vec<PSP22Event>, PSP22Error> {
+        if !self.is_allowance_index_enabled() {
+            return Err(PSP22Error::custom("allowance index disabled"));
+        }
+        let events = self
+            .spenders_of(owner)
+            .into_iter()
+            .map(|spender| {
+                self.allowances.remove((owner, spender));
+                approval_event(owner, spender, 0)
+            })
+            .collect();
+        self.spenders.remove(owner);
+        Ok(events)
+    }
+
     /// Transfers `value` tokens from `caller` to `to`.
+    ///
+    /// Transferring zero tokens is a no-op returning no events. Transferring to `caller` itself
+    /// does not change balances, but fails with `InsufficientBalance` if `value` exceeds
+    /// the balance of `caller`, like any other transfer. It returns no events,
+    /// unless self-transfer events are enabled (see `enable_self_transfer_events`).
     pub fn transfer(
         &mut self,
         caller: AccountId,
         to: AccountId,
         value: u128,
     ) -> Result<Vec<PSP22Event>, PSP22Error> {
-        if caller == to || value == 0 {
-            return Ok(vec![]);
+        self.transfer_with_data(caller, to, value, Vec::new())
+    }
+
+    /// Transfers `value` tokens from `caller` to `to` with additional `data`.
+    ///
+    /// Works like `transfer`, but if `data` is non-empty and the transfer is not a no-op,
+    /// a `TransferWithData` event is returned after the `Transfer` event.
+    pub fn transfer_with_data(
+        &mut self,
+        caller: AccountId,
+        to: AccountId,
+        value: u128,
+        data: Vec<u8>,
+    ) -> Result<Vec<PSP22Event>, PSP22Error> {
+        let mut events = Vec::new();
+        self.transfer_with_sink(caller, to, value, data, &mut events)?;
+        Ok(events)
+    }
+
+    /// Transfers `value` tokens from `caller` to `to` with additional `data`
+    /// (possibly empty), pushing the resulting events to `sink`.
+    ///
+    /// Returns the balances of `caller` and `to` after the transfer.
+    pub fn transfer_with_sink(
+        &mut self,
+        caller: AccountId,
+        to: AccountId,
+        value: u128,
+        data: Vec<u8>,
+        sink: &mut impl EventSink,
+    ) -> Result<TransferOutcome, PSP22Error> {
+        self.check_transfer(caller, to, value)?;
+        if value == 0 {
+            return Ok(TransferOutcome {
+                from_balance: self.balance_of(caller),
+                to_balance: self.balance_of(to),
+            });
         }
-        let from_balance = self.balance_of(caller);
-        if from_balance < value {
-            return Err(PSP22Error::InsufficientBalance);
+        if caller == to {
+            return Ok(self.self_transfer_with_sink(caller, value, data, sink));
         }
+        // Cannot underflow, checked above
+        let from_balance = self.balance_of(caller).saturating_sub(value);
+        let to_balance = credit(self.balance_of(to), value);
+        self.set_balance(caller, from_balance);
+        self.set_balance(to, to_balance);
+        emit_transfer(sink, caller, to, value, data);
+        Ok(TransferOutcome {
+            from_balance,
+            to_balance,
+        })
+    }
 
-        if from_balance == value {
-            self.balances.remove(caller);
+    /// Checks whether `transfer` of `value` tokens from `caller` to `to` would succeed,
+    /// without changing the state. Returns the error `transfer` would return otherwise.
+    pub fn verify_transfer(
+        &self,
+        caller: AccountId,
+        to: AccountId,
+        value: u128,
+    ) -> Result<(), PSP22Error> {
+        self.check_transfer(caller, to, value)
+    }
+
+    /// Transfers the whole balance of `caller` to `to` with additional `data`.
+    ///
+    /// Returns the amount transferred alongside the events. It is a no-op returning `0`
+    /// if the balance is zero or `to` is `caller`.
+    pub fn transfer_all(
+        &mut self,
+        caller: AccountId,
+        to: AccountId,
+        data: Vec<u8>,
+    ) -> Result<(u128, Vec<PSP22Event>), PSP22Error> {
+        let mut events = Vec::new();
+        let value = self.transfer_all_with_sink(caller, to, data, &mut events)?;
+        Ok((value, events))
+    }
+
+    /// Works like `transfer_all`, but pushes the resulting events to `sink`.
+    pub fn transfer_all_with_sink(
+        &mut self,
+        caller: AccountId,
+        to: AccountId,
+        data: Vec<u8>,
+        sink: &mut impl EventSink,
+    ) -> Result<u128, PSP22Error> {
+        let value = if caller == to {
+            0
         } else {
-            self.balances
-                .insert(caller, &(from_balance.saturating_sub(value)));
+            self.balance_of(caller)
+        };
+        self.transfer_with_sink(caller, to, value, data, sink)?;
+        Ok(value)
+    }
+
+    /// Moves the whole balance of each of `accounts` holding less than `threshold` tokens
+    /// to `collector`, e.g. to consolidate dust left on exchange deposit accounts.
+    /// Accounts holding `threshold` tokens or more and `collector` itself are skipped.
+    /// Access control is left to the contract.
+    ///
+    /// Returns the total amount swept alongside a `Transfer` event for each non-zero sweep.
+    pub fn sweep_dust(
+        &mut self,
+        accounts: Vec<AccountId>,
+        threshold: u128,
+        collector: AccountId,
+    ) -> Result<(u128, Vec<PSP22Event>), PSP22Error> {
+        let mut events = Vec::new();
+        let mut total = 0;
+        for account in accounts {
+            if self.balance_of(account) < threshold {
+                // Cannot overflow, the sum of balances does not exceed the total supply
+                total +=
+                    self.transfer_all_with_sink(account, collector, Vec::new(), &mut events)?;
+            }
         }
-        let to_balance = self.balance_of(to);
-        // Total supply is limited by u128.MAX so no overflow is possible
-        self.balances
-            .insert(to, &(to_balance.saturating_add(value)));
-        Ok(vec![transfer_event(Some(caller), Some(to), value)])
+        Ok((total, events))
     }
 
     /// Transfers `value` tokens from `from` to `to`, but using the allowance
     /// granted be `from` to `caller.
+    ///
+    /// A transfer from `from` to itself does not change balances nor the allowance,
+    /// but is validated like any other: it fails with `InsufficientAllowance`
+    /// if `value` exceeds the allowance (unless `caller` is `from`)
+    /// and with `InsufficientBalance` if `value` exceeds the balance of `from`.
     pub fn transfer_from(
         &mut self,
         caller: AccountId,
@@ -105,43 +662,89 @@ impl PSP22Data {
         to: AccountId,
         value: u128,
     ) -> Result<Vec<PSP22Event>, PSP22Error> {
-        if from == to || value == 0 {
-            return Ok(vec![]);
+        self.transfer_from_with_data(caller, from, to, value, Vec::new())
+    }
+
+    /// Transfers `value` tokens from `from` to `to` with additional `data`,
+    /// using the allowance granted by `from` to `caller`.
+    ///
+    /// Works like `transfer_from`, but if `data` is non-empty and the transfer is not a no-op,
+    /// a `TransferWithData` event is returned after the `Transfer` event.
+    pub fn transfer_from_with_data(
+        &mut self,
+        caller: AccountId,
+        from: AccountId,
+        to: AccountId,
+        value: u128,
+        data: Vec<u8>,
+    ) -> Result<Vec<PSP22Event>, PSP22Error> {
+        let mut events = Vec::new();
+        self.transfer_from_with_sink(caller, from, to, value, data, &mut events)?;
+        Ok(events)
+    }
+
+    /// Transfers `value` tokens from `from` to `to` with additional `data` (possibly empty),
+    /// using the allowance granted by `from` to `caller`. Pushes the resulting events to `sink`.
+    ///
+    /// Returns the balances of `from` and `to` after the transfer.
+    pub fn transfer_from_with_sink(
+        &mut self,
+        caller: AccountId,
+        from: AccountId,
+        to: AccountId,
+        value: u128,
+        data: Vec<u8>,
+        sink: &mut impl EventSink,
+    ) -> Result<TransferOutcome, PSP22Error> {
+        self.check_transfer_from(caller, from, to, value)?;
+        if value == 0 {
+            return Ok(TransferOutcome {
+                from_balance: self.balance_of(from),
+                to_balance: self.balance_of(to),
+            });
+        }
+        if from == to {
+            return Ok(self.self_transfer_with_sink(from, value, data, sink));
         }
         if caller == from {
-            return self.transfer(caller, to, value);
+            return self.transfer_with_sink(caller, to, value, data, sink);
         }
 
-        let allowance = self.allowance(from, caller);
-        if allowance < value {
-            return Err(PSP22Error::InsufficientAllowance);
-        }
-        let from_balance = self.balance_of(from);
-        if from_balance < value {
-            return Err(PSP22Error::InsufficientBalance);
-        }
+        // Cannot underflow, checked above
+        let allowance = self.allowance(from, caller).saturating_sub(value);
+        let from_balance = self.balance_of(from).saturating_sub(value);
+        let to_balance = credit(self.balance_of(to), value);
+        self.set_allowance(from, caller, allowance)?;
+        self.set_balance(from, from_balance);
+        self.set_balance(to, to_balance);
+        sink.emit(approval_event(from, caller, allowance));
+        emit_transfer(sink, from, to, value, data);
+        Ok(TransferOutcome {
+            from_balance,
+            to_balance,
+        })
+    }
 
-        if allowance == value {
-            self.allowances.remove((from, caller));
-        } else {
-            self.allowances
-                .insert((from, caller), &(allowance.saturating_sub(value)));
+    // Transfer of a non-zero `value` from `owner` to itself. Balances are not touched,
+    // but `value` is validated like in any other transfer by `check_transfer`
+    // or `check_transfer_from`, so that a self-transfer does not succeed where a transfer
+    // to another account would fail. The events are emitted only if self-transfer events
+    // are enabled.
+    fn self_transfer_with_sink(
+        &self,
+        owner: AccountId,
+        value: u128,
+        data: Vec<u8>,
+        sink: &mut impl EventSink,
+    ) -> TransferOutcome {
+        let balance = self.balance_of(owner);
+        if self.is_self_transfer_events_enabled() {
+            emit_transfer(sink, owner, owner, value, data);
         }
-
-        if from_balance == value {
-            self.balances.remove(from);
-        } else {
-            self.balances
-                .insert(from, &(from_balance.saturating_sub(value)));
+        TransferOutcome {
+            from_balance: balance,
+            to_balance: balance,
         }
-        let to_balance = self.balance_of(to);
-        // Total supply is limited by u128.MAX so no overflow is possible
-        self.balances
-            .insert(to, &(to_balance.saturating_add(value)));
-        Ok(vec![
-            approval_event(from, caller, allowance.saturating_sub(value)),
-            transfer_event(Some(from), Some(to), value),
-        ])
     }
 
     /// Sets a new `value` for allowance granted by `owner` to `spender`.
@@ -152,31 +755,132 @@ impl PSP22Data {
         spender: AccountId,
         value: u128,
     ) -> Result<Vec<PSP22Event>, PSP22Error> {
+        let mut events = Vec::new();
+        self.approve_with_sink(owner, spender, value, &mut events)?;
+        Ok(events)
+    }
+
+    /// Works like `approve`, but pushes the resulting events to `sink`.
+    /// Returns the allowance after the operation.
+    pub fn approve_with_sink(
+        &mut self,
+        owner: AccountId,
+        spender: AccountId,
+        value: u128,
+        sink: &mut impl EventSink,
+    ) -> Result<ApprovalOutcome, PSP22Error> {
         if owner == spender {
-            return Ok(vec![]);
+            return Ok(ApprovalOutcome {
+                allowance: self.allowance(owner, spender),
+            });
         }
-        if value == 0 {
-            self.allowances.remove((owner, spender));
-        } else {
-            self.allowances.insert((owner, spender), &value);
+        self.check_allowance_cap(value)?;
+        self.set_allowance(owner, spender, value)?;
+        sink.emit(approval_event(owner, spender, value));
+        Ok(ApprovalOutcome { allowance: value })
+    }
+
+    /// Sets the allowances granted by `owner` to several spenders, like `approve`
+    /// for each `(spender, value)` of `approvals`, in order.
+    ///
+    /// Entries approving `owner` itself are skipped, like in `approve`. Returns an `Approval`
+    /// event for each other entry. Fails with the error of the first failing entry.
+    pub fn batch_approve(
+        &mut self,
+        owner: AccountId,
+        approvals: Vec<(AccountId, u128)>,
+    ) -> Result<Vec<PSP22Event>, PSP22Error> {
+        let mut events = Vec::new();
+        for (spender, value) in approvals {
+            self.approve_with_sink(owner, spender, value, &mut events)?;
+        }
+        Ok(events)
+    }
+
+    /// Sets a new `value` for allowance granted by `owner` to `spender`, but only if
+    /// the current allowance is equal to `expected_current`.
+    ///
+    /// Returns `AllowanceMismatch` error otherwise, so that a spender front-running
+    /// the change cannot spend both the old and the new allowance.
+    pub fn approve_checked(
+        &mut self,
+        owner: AccountId,
+        spender: AccountId,
+        expected_current: u128,
+        value: u128,
+    ) -> Result<Vec<PSP22Event>, PSP22Error> {
+        let mut events = Vec::new();
+        self.approve_checked_with_sink(owner, spender, expected_current, value, &mut events)?;
+        Ok(events)
+    }
+
+    /// Works like `approve_checked`, but pushes the resulting events to `sink`.
+    /// Returns the allowance after the operation.
+    pub fn approve_checked_with_sink(
+        &mut self,
+        owner: AccountId,
+        spender: AccountId,
+        expected_current: u128,
+        value: u128,
+        sink: &mut impl EventSink,
+    ) -> Result<ApprovalOutcome, PSP22Error> {
+        if self.allowance(owner, spender) != expected_current {
+            return Err(PSP22Error::AllowanceMismatch);
         }
-        Ok(vec![approval_event(owner, spender, value)])
+        self.approve_with_sink(owner, spender, value, sink)
     }
 
     /// Increases the allowance granted  by `owner` to `spender` by `delta_value`.
+    ///
+    /// Returns `AllowanceOverflow` error if the allowance would exceed `u128::MAX`.
     pub fn increase_allowance(
         &mut self,
         owner: AccountId,
         spender: AccountId,
         delta_value: u128,
     ) -> Result<Vec<PSP22Event>, PSP22Error> {
+        let mut events = Vec::new();
+        self.increase_allowance_with_sink(owner, spender, delta_value, &mut events)?;
+        Ok(events)
+    }
+
+    /// Works like `increase_allowance`, but pushes the resulting events to `sink`.
+    /// Returns the allowance after the operation.
+    pub fn increase_allowance_with_sink(
+        &mut self,
+        owner: AccountId,
+        spender: AccountId,
+        delta_value: u128,
+        sink: &mut impl EventSink,
+    ) -> Result<ApprovalOutcome, PSP22Error> {
         if owner == spender || delta_value == 0 {
-            return Ok(vec![]);
+            return Ok(ApprovalOutcome {
+                allowance: self.allowance(owner, spender),
+            });
         }
-        let allowance = self.allowance(owner, spender);
-        let amount = allowance.saturating_add(delta_value);
-        self.allowances.insert((owner, spender), &amount);
-        Ok(vec![approval_event(owner, spender, amount)])
+        let amount = self
+            .allowance(owner, spender)
+            .checked_add(delta_value)
+            .ok_or(PSP22Error::AllowanceOverflow)?;
+        self.check_allowance_cap(amount)?;
+        self.set_allowance(owner, spender, amount)?;
+        sink.emit(approval_event(owner, spender, amount));
+        Ok(ApprovalOutcome { allowance: amount })
+    }
+
+    /// Works like `increase_allowance`, but saturates at `u128::MAX` instead of returning
+    /// `AllowanceOverflow` error, the behavior of `increase_allowance` before it was checked.
+    ///
+    /// The returned `Approval` event carries the allowance actually stored. No events
+    /// are returned if the allowance is already `u128::MAX`.
+    pub fn increase_allowance_saturating(
+        &mut self,
+        owner: AccountId,
+        spender: AccountId,
+        delta_value: u128,
+    ) -> Result<Vec<PSP22Event>, PSP22Error> {
+        let headroom = u128::MAX - self.allowance(owner, spender);
+        self.increase_allowance(owner, spender, delta_value.min(headroom))
     }
 
     /// Decreases the allowance granted  by `owner` to `spender` by `delta_value`.
@@ -186,54 +890,1533 @@ impl PSP22Data {
         spender: AccountId,
         delta_value: u128,
     ) -> Result<Vec<PSP22Event>, PSP22Error> {
+        let mut events = Vec::new();
+        self.decrease_allowance_with_sink(owner, spender, delta_value, &mut events)?;
+        Ok(events)
+    }
+
+    /// Works like `decrease_allowance`, but pushes the resulting events to `sink`.
+    /// Returns the allowance after the operation.
+    pub fn decrease_allowance_with_sink(
+        &mut self,
+        owner: AccountId,
+        spender: AccountId,
+        delta_value: u128,
+        sink: &mut impl EventSink,
+    ) -> Result<ApprovalOutcome, PSP22Error> {
         if owner == spender || delta_value == 0 {
-            return Ok(vec![]);
-        }
-        let allowance = self.allowance(owner, spender);
-        if allowance < delta_value {
-            return Err(PSP22Error::InsufficientAllowance);
-        }
-        let amount = allowance.saturating_sub(delta_value);
-        if amount == 0 {
-            self.allowances.remove((owner, spender));
-        } else {
-            self.allowances.insert((owner, spender), &amount);
+            return Ok(ApprovalOutcome {
+                allowance: self.allowance(owner, spender),
+            });
         }
-        Ok(vec![approval_event(owner, spender, amount)])
+        let amount = debit(
+            self.allowance(owner, spender),
+            delta_value,
+            PSP22Error::InsufficientAllowance,
+        )?;
+        self.set_allowance(owner, spender, amount)?;
+        sink.emit(approval_event(owner, spender, amount));
+        Ok(ApprovalOutcome { allowance: amount })
     }
 
     /// Mints a `value` of new tokens to `to` account.
     pub fn mint(&mut self, to: AccountId, value: u128) -> Result<Vec<PSP22Event>, PSP22Error> {
+        let mut events = Vec::new();
+        self.mint_with_sink(to, value, &mut events)?;
+        Ok(events)
+    }
+
+    /// Works like `mint`, but returns `CapExceeded` error if the total supply
+    /// after minting would exceed `cap`, see `PSP22Capped`.
+    pub fn mint_capped(
+        &mut self,
+        to: AccountId,
+        value: u128,
+        cap: u128,
+    ) -> Result<Vec<PSP22Event>, PSP22Error> {
+        if value > cap.saturating_sub(self.total_supply()) {
+            return Err(PSP22Error::CapExceeded);
+        }
+        self.mint(to, value)
+    }
+
+    /// Mints a `value` of new tokens to `to` account with additional `data`,
+    /// e.g. a reference to the deposit on another chain.
+    ///
+    /// Works like `mint`, but if `data` is non-empty and `value` is not zero,
+    /// a `TransferWithData` event with `None` sender is returned after the `Minted` event.
+    pub fn mint_with_data(
+        &mut self,
+        to: AccountId,
+        value: u128,
+        data: Vec<u8>,
+    ) -> Result<Vec<PSP22Event>, PSP22Error> {
+        let mut events = Vec::new();
+        self.mint_with_sink(to, value, &mut events)?;
+        if value > 0 {
+            emit_transfer_data(&mut events, None, Some(to), value, data);
+        }
+        Ok(events)
+    }
+
+    /// Works like `mint`, but pushes the resulting events to `sink`.
+    /// Returns the balance of `to` and the total supply after minting.
+    pub fn mint_with_sink(
+        &mut self,
+        to: AccountId,
+        value: u128,
+        sink: &mut impl EventSink,
+    ) -> Result<SupplyOutcome, PSP22Error> {
+        self.mint_counted(to, value, value, sink)
+    }
+
+    /// Works like `mint_with_sink`, but does not count `value` in `total_minted`.
+    /// Used by flash loans, which take the lent tokens back in the same transaction
+    /// with `flash_burn_with_sink`.
+    pub(crate) fn flash_mint_with_sink(
+        &mut self,
+        to: AccountId,
+        value: u128,
+        sink: &mut impl EventSink,
+    ) -> Result<SupplyOutcome, PSP22Error> {
+        self.mint_counted(to, value, 0, sink)
+    }
+
+    // Mints `value` tokens to `to`, of which `counted` are added to `total_minted`.
+    fn mint_counted(
+        &mut self,
+        to: AccountId,
+        value: u128,
+        counted: u128,
+        sink: &mut impl EventSink,
+    ) -> Result<SupplyOutcome, PSP22Error> {
+        self.check_mint(to, value, counted)?;
         if value == 0 {
-            return Ok(vec![]);
+            return Ok(SupplyOutcome {
+                balance: self.balance_of(to),
+                total_supply: self.total_supply(),
+            });
         }
-        let new_supply = self
-            .total_supply
-            .checked_add(value)
-            .ok_or(PSP22Error::Custom(String::from(
-                "Max PSP22 supply exceeded. Max supply limited to 2^128-1.",
-            )))?;
-        self.total_supply = new_supply;
-        let new_balance = self.balance_of(to).saturating_add(value);
-        self.balances.insert(to, &new_balance);
-        Ok(vec![transfer_event(None, Some(to), value)])
+        // Cannot overflow, checked above
+        let new_supply = self.total_supply().saturating_add(value);
+        let new_minted = self.total_minted().saturating_add(counted);
+        self.total_supply.set(&new_supply);
+        self.total_minted.set(&new_minted);
+        let new_balance = credit(self.balance_of(to), value);
+        self.set_balance(to, new_balance);
+        sink.emit(PSP22Event::Minted { to, value });
+        Ok(SupplyOutcome {
+            balance: new_balance,
+            total_supply: new_supply,
+        })
     }
 
     /// Burns `value` tokens from `from` account.
     pub fn burn(&mut self, from: AccountId, value: u128) -> Result<Vec<PSP22Event>, PSP22Error> {
-        if value == 0 {
-            return Ok(vec![]);
+        let mut events = Vec::new();
+        self.burn_with_sink(from, value, &mut events)?;
+        Ok(events)
+    }
+
+    /// Burns `value` tokens from `from` account with additional `data`,
+    /// e.g. the recipient of the withdrawal on another chain.
+    ///
+    /// Works like `burn`, but if `data` is non-empty and `value` is not zero,
+    /// a `TransferWithData` event with `None` recipient is returned after the `Burned` event.
+    pub fn burn_with_data(
+        &mut self,
+        from: AccountId,
+        value: u128,
+        data: Vec<u8>,
+    ) -> Result<Vec<PSP22Event>, PSP22Error> {
+        let mut events = Vec::new();
+        self.burn_with_sink(from, value, &mut events)?;
+        if value > 0 {
+            emit_transfer_data(&mut events, Some(from), None, value, data);
         }
+        Ok(events)
+    }
+
+    /// Works like `burn`, but pushes the resulting events to `sink`.
+    /// Returns the balance of `from` and the total supply after burning.
+    pub fn burn_with_sink(
+        &mut self,
+        from: AccountId,
+        value: u128,
+        sink: &mut impl EventSink,
+    ) -> Result<SupplyOutcome, PSP22Error> {
+        self.burn_counted(from, value, value, sink)
+    }
+
+    /// Works like `burn_with_sink`, burning `value` plus `fee` tokens, but counts only
+    /// `fee` in `total_burned`. Used to take back the tokens lent with
+    /// `flash_mint_with_sink` together with the fee of the loan.
+    pub(crate) fn flash_burn_with_sink(
+        &mut self,
+        from: AccountId,
+        value: u128,
+        fee: u128,
+        sink: &mut impl EventSink,
+    ) -> Result<SupplyOutcome, PSP22Error> {
+        let total = value
+            .checked_add(fee)
+            .ok_or(PSP22Error::InsufficientBalance)?;
+        self.burn_counted(from, total, fee, sink)
+    }
+
+    // Burns `value` tokens from `from`, of which `counted` are added to `total_burned`.
+    fn burn_counted(
+        &mut self,
+        from: AccountId,
+        value: u128,
+        counted: u128,
+        sink: &mut impl EventSink,
+    ) -> Result<SupplyOutcome, PSP22Error> {
+        self.check_burn(from, value)?;
         let balance = self.balance_of(from);
-        if balance < value {
-            return Err(PSP22Error::InsufficientBalance);
+        if value == 0 {
+            return Ok(SupplyOutcome {
+                balance,
+                total_supply: self.total_supply(),
+            });
+        }
+        // Cannot underflow, checked above
+        let new_balance = balance.saturating_sub(value);
+        self.set_balance(from, new_balance);
+        // Cannot underflow, as the burned balance is a part of the total supply
+        let new_supply = self.total_supply().saturating_sub(value);
+        self.total_supply.set(&new_supply);
+        // Cannot overflow, since the burned tokens were minted before
+        self.total_burned
+            .set(&self.total_burned().saturating_add(counted));
+        sink.emit(PSP22Event::Burned { from, value });
+        Ok(SupplyOutcome {
+            balance: new_balance,
+            total_supply: new_supply,
+        })
+    }
+
+    // All the checks of `transfer_with_sink`, which applies the transfer only if they pass.
+    fn check_transfer(
+        &self,
+        caller: AccountId,
+        to: AccountId,
+        value: u128,
+    ) -> Result<(), PSP22Error> {
+        self.check_not_paused()?;
+        check_recipient(to)?;
+        debit(
+            self.balance_of(caller),
+            value,
+            PSP22Error::InsufficientBalance,
+        )?;
+        Ok(())
+    }
+
+    // All the checks of `transfer_from_with_sink`. The allowance is not needed
+    // if `caller` is `from`.
+    fn check_transfer_from(
+        &self,
+        caller: AccountId,
+        from: AccountId,
+        to: AccountId,
+        value: u128,
+    ) -> Result<(), PSP22Error> {
+        self.check_not_paused()?;
+        check_recipient(to)?;
+        if caller != from {
+            debit(
+                self.allowance(from, caller),
+                value,
+                PSP22Error::InsufficientAllowance,
+            )?;
+        }
+        debit(
+            self.balance_of(from),
+            value,
+            PSP22Error::InsufficientBalance,
+        )?;
+        Ok(())
+    }
+
+    // All the checks of `mint_counted`.
+    fn check_mint(&self, to: AccountId, value: u128, counted: u128) -> Result<(), PSP22Error> {
+        self.check_not_paused()?;
+        check_recipient(to)?;
+        if self.total_supply().checked_add(value).is_none() {
+            return Err(PSP22Error::custom(
+                "Max PSP22 supply exceeded. Max supply limited to 2^128-1.",
+            ));
+        }
+        if self.total_minted().checked_add(counted).is_none() {
+            return Err(PSP22Error::custom("Max PSP22 lifetime minting exceeded."));
         }
-        if balance == value {
-            self.balances.remove(from);
+        Ok(())
+    }
+
+    // All the checks of `burn_with_sink`.
+    fn check_burn(&self, from: AccountId, value: u128) -> Result<(), PSP22Error> {
+        self.check_not_paused()?;
+        debit(
+            self.balance_of(from),
+            value,
+            PSP22Error::InsufficientBalance,
+        )?;
+        if value > 0 && self.total_supply().saturating_sub(value) < self.min_supply() {
+            return Err(PSP22Error::SupplyBelowMinimum);
+        }
+        Ok(())
+    }
+
+    fn check_allowance_cap(&self, amount: u128) -> Result<(), PSP22Error> {
+        match self.max_allowance() {
+            Some(max_allowance) if amount > max_allowance => Err(PSP22Error::AllowanceCapExceeded),
+            _ => Ok(()),
+        }
+    }
+
+    fn check_not_paused(&self) -> Result<(), PSP22Error> {
+        if self.paused {
+            return Err(PSP22Error::Paused);
+        }
+        Ok(())
+    }
+
+    // Stores the balance of `owner`, removing the entry for a zero balance.
+    fn set_balance(&mut self, owner: AccountId, balance: u128) {
+        if balance == 0 {
+            self.balances.remove(owner);
+        } else {
+            self.balances.insert(owner, &balance);
+        }
+    }
+
+    // Stores the allowance granted by `owner` to `spender`, removing the entry for a zero allowance.
+    // Keeps the allowance index up to date, failing only if a new spender does not fit in it.
+    fn set_allowance(
+        &mut self,
+        owner: AccountId,
+        spender: AccountId,
+        amount: u128,
+    ) -> Result<(), PSP22Error> {
+        let existed = self.allowances.contains((owner, spender));
+        if existed != (amount > 0) && self.is_allowance_index_enabled() {
+            let mut spenders = self.spenders_of(owner);
+            if existed {
+                if let Some(i) = spenders.iter().position(|s| *s == spender) {
+                    spenders.swap_remove(i);
+                }
+            } else if spenders.len() < MAX_SPENDERS {
+                spenders.push(spender);
+            } else {
+                return Err(PSP22Error::custom("too many spenders"));
+            }
+            if spenders.is_empty() {
+                self.spenders.remove(owner);
+            } else {
+                self.spenders.insert(owner, &spenders);
+            }
+        }
+        if amount == 0 {
+            self.allowances.remove((owner, spender));
         } else {
-            self.balances.insert(from, &(balance.saturating_sub(value)));
+            self.allowances.insert((owner, spender), &amount);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink::env::{test::default_accounts, DefaultEnvironment as E};
+
+    const ZERO: [u8; 32] = [0u8; 32];
+
+    #[ink::test]
+    fn initial_balances_add_up_to_total_supply() {
+        let acc = default_accounts::<E>();
+        let (data, events) = PSP22Data::new_with_initial_balances(vec![
+            (acc.alice, 100),
+            (acc.bob, 0),
+            (acc.charlie, 30),
+            (acc.alice, 20),
+        ]);
+
+        assert_eq!(data.total_supply(), 150);
+        assert_eq!(data.total_minted(), 150);
+        assert_eq!(data.balance_of(acc.alice), 120);
+        assert_eq!(data.balance_of(acc.bob), 0);
+        assert_eq!(data.balance_of(acc.charlie), 30);
+        let transfers: Vec<_> = events
+            .iter()
+            .map(|e| e.as_transfer().unwrap())
+            .map(|t| (t.from, t.to, t.value))
+            .collect();
+        assert_eq!(
+            transfers,
+            vec![
+                (None, Some(acc.alice), 100),
+                (None, Some(acc.charlie), 30),
+                (None, Some(acc.alice), 20),
+            ]
+        );
+
+        let (data, events) = PSP22Data::new_with_initial_balances(vec![]);
+        assert_eq!(data.total_supply(), 0);
+        assert!(events.is_empty());
+    }
+
+    #[ink::test]
+    #[should_panic]
+    fn initial_balances_exceeding_max_supply_panic() {
+        let acc = default_accounts::<E>();
+        PSP22Data::new_with_initial_balances(vec![(acc.alice, u128::MAX), (acc.bob, 1)]);
+    }
+
+    #[ink::test]
+    fn self_transfers_emit_no_events_by_default() {
+        let acc = default_accounts::<E>();
+        let (mut data, _) = PSP22Data::new(1000, acc.alice);
+        assert!(data.approve(acc.alice, acc.bob, 100).is_ok());
+
+        assert!(data.transfer(acc.alice, acc.alice, 100).unwrap().is_empty());
+        assert!(data
+            .transfer_from(acc.bob, acc.alice, acc.alice, 100)
+            .unwrap()
+            .is_empty());
+        assert_eq!(data.balance_of(acc.alice), 1000);
+        assert_eq!(data.allowance(acc.alice, acc.bob), 100);
+    }
+
+    #[ink::test]
+    fn self_transfers_emit_events_when_enabled() {
+        let acc = default_accounts::<E>();
+        let (mut data, _) = PSP22Data::new(1000, acc.alice);
+        data.enable_self_transfer_events();
+        assert!(data.is_self_transfer_events_enabled());
+        assert!(data.approve(acc.alice, acc.bob, 100).is_ok());
+
+        let events = data.transfer(acc.alice, acc.alice, 100).unwrap();
+        assert!(matches!(
+            &events[..],
+            [PSP22Event::Transfer(e)]
+                if e.from == Some(acc.alice) && e.to == Some(acc.alice) && e.value == 100
+        ));
+        let events = data
+            .transfer_from_with_data(acc.bob, acc.alice, acc.alice, 100, vec![1])
+            .unwrap();
+        assert!(matches!(
+            &events[..],
+            [PSP22Event::Transfer(e), PSP22Event::TransferWithData(_)]
+                if e.from == Some(acc.alice) && e.to == Some(acc.alice) && e.value == 100
+        ));
+        // Zero transfers are still no-ops
+        assert!(data.transfer(acc.alice, acc.alice, 0).unwrap().is_empty());
+        assert_eq!(
+            data.transfer(acc.alice, acc.alice, 2000).err(),
+            Some(PSP22Error::InsufficientBalance)
+        );
+        assert_eq!(
+            data.transfer_from(acc.bob, acc.alice, acc.alice, 101).err(),
+            Some(PSP22Error::InsufficientAllowance)
+        );
+        // Balances and allowances are not touched
+        assert_eq!(data.balance_of(acc.alice), 1000);
+        assert_eq!(data.total_supply(), 1000);
+        assert_eq!(data.allowance(acc.alice, acc.bob), 100);
+    }
+
+    #[ink::test]
+    fn sweep_dust_moves_only_small_balances() {
+        let acc = default_accounts::<E>();
+        let (mut data, _) = PSP22Data::new(1000, acc.alice);
+        assert!(data.transfer(acc.alice, acc.bob, 9).is_ok());
+        assert!(data.transfer(acc.alice, acc.charlie, 10).is_ok());
+        assert!(data.transfer(acc.alice, acc.django, 3).is_ok());
+
+        let (total, events) = data
+            .sweep_dust(
+                vec![
+                    acc.bob,
+                    acc.charlie,
+                    acc.django,
+                    acc.eve,
+                    acc.bob,
+                    acc.frank,
+                ],
+                10,
+                acc.frank,
+            )
+            .unwrap();
+
+        assert_eq!(total, 12);
+        let transfers: Vec<_> = events
+            .iter()
+            .map(|e| e.as_transfer().unwrap())
+            .map(|t| (t.from, t.to, t.value))
+            .collect();
+        assert_eq!(
+            transfers,
+            vec![
+                (Some(acc.bob), Some(acc.frank), 9),
+                (Some(acc.django), Some(acc.frank), 3),
+            ]
+        );
+        assert_eq!(data.balance_of(acc.bob), 0);
+        assert_eq!(data.balance_of(acc.charlie), 10);
+        assert_eq!(data.balance_of(acc.django), 0);
+        assert_eq!(data.balance_of(acc.frank), 12);
+        assert_eq!(data.total_supply(), 1000);
+    }
+
+    #[ink::test]
+    fn mint_and_burn_events_match_transfer_events() {
+        let acc = default_accounts::<E>();
+        let (mut data, events) = PSP22Data::new(1000, acc.alice);
+        assert!(matches!(
+            events[..],
+            [PSP22Event::Minted { to, value: 1000 }] if to == acc.alice
+        ));
+        let transfer = events[0].as_transfer().unwrap();
+        assert_eq!(transfer.from, None);
+        assert_eq!(transfer.to, Some(acc.alice));
+        assert_eq!(transfer.value, 1000);
+
+        let events = data.burn(acc.alice, 100).unwrap();
+        assert!(matches!(
+            events[..],
+            [PSP22Event::Burned { from, value: 100 }] if from == acc.alice
+        ));
+        let transfer = events[0].as_transfer().unwrap();
+        assert_eq!(transfer.from, Some(acc.alice));
+        assert_eq!(transfer.to, None);
+        assert_eq!(transfer.value, 100);
+
+        let events = data.transfer(acc.alice, acc.bob, 10).unwrap();
+        let transfer = events[0].as_transfer().unwrap();
+        assert_eq!(transfer.from, Some(acc.alice));
+        assert_eq!(transfer.to, Some(acc.bob));
+        assert_eq!(transfer.value, 10);
+
+        let events = data.approve(acc.alice, acc.bob, 10).unwrap();
+        assert!(events[0].as_transfer().is_none());
+    }
+
+    #[ink::test]
+    fn mint_and_burn_with_data_carry_data() {
+        let acc = default_accounts::<E>();
+        let (mut data, _) = PSP22Data::new(1000, acc.alice);
+
+        let events = data.mint_with_data(acc.bob, 100, vec![1, 2, 3]).unwrap();
+        assert!(matches!(
+            &events[..],
+            [PSP22Event::Minted { to, value: 100 }, PSP22Event::TransferWithData(e)]
+                if *to == acc.bob && e.from.is_none() && e.to == Some(acc.bob)
+                    && e.value == 100 && e.data == [1, 2, 3]
+        ));
+        let events = data.burn_with_data(acc.bob, 40, vec![4, 5]).unwrap();
+        assert!(matches!(
+            &events[..],
+            [PSP22Event::Burned { from, value: 40 }, PSP22Event::TransferWithData(e)]
+                if *from == acc.bob && e.from == Some(acc.bob) && e.to.is_none()
+                    && e.value == 40 && e.data == [4, 5]
+        ));
+        assert_eq!(data.balance_of(acc.bob), 60);
+        assert_eq!(data.total_supply(), 1060);
+
+        // Same as plain mint and burn for empty data or zero value
+        let events = data.mint_with_data(acc.bob, 10, vec![]).unwrap();
+        assert!(matches!(&events[..], [PSP22Event::Minted { .. }]));
+        assert!(data.mint_with_data(acc.bob, 0, vec![1]).unwrap().is_empty());
+        assert!(data.burn_with_data(acc.bob, 0, vec![1]).unwrap().is_empty());
+    }
+
+    #[ink::test]
+    fn allowances_above_cap_are_rejected() {
+        let acc = default_accounts::<E>();
+        let (mut data, _) = PSP22Data::new(1000, acc.alice);
+        assert_eq!(data.max_allowance(), None);
+        assert!(data.approve(acc.alice, acc.bob, u128::MAX).is_ok());
+
+        data.set_max_allowance(Some(100));
+        assert_eq!(data.max_allowance(), Some(100));
+        assert_eq!(
+            data.approve(acc.alice, acc.charlie, 101).err(),
+            Some(PSP22Error::AllowanceCapExceeded)
+        );
+        assert!(data.approve(acc.alice, acc.charlie, 100).is_ok());
+        assert!(data.approve(acc.alice, acc.django, 60).is_ok());
+        assert_eq!(
+            data.increase_allowance(acc.alice, acc.django, 41).err(),
+            Some(PSP22Error::AllowanceCapExceeded)
+        );
+        assert!(data.increase_allowance(acc.alice, acc.django, 40).is_ok());
+        assert_eq!(
+            data.approve_checked(acc.alice, acc.django, 100, 200).err(),
+            Some(PSP22Error::AllowanceCapExceeded)
+        );
+        assert_eq!(data.allowance(acc.alice, acc.django), 100);
+
+        data.set_max_allowance(None);
+        assert!(data.approve(acc.alice, acc.charlie, 1000).is_ok());
+    }
+
+    #[ink::test]
+    fn burning_stops_at_min_supply() {
+        let acc = default_accounts::<E>();
+        let (mut data, _) = PSP22Data::new(1000, acc.alice);
+        assert_eq!(data.min_supply(), 0);
+        assert!(data.transfer(acc.alice, acc.bob, 400).is_ok());
+
+        data.set_min_supply(700);
+        assert_eq!(data.min_supply(), 700);
+        assert!(data.burn(acc.bob, 200).is_ok());
+        assert!(data.burn(acc.alice, 100).is_ok());
+        assert_eq!(data.total_supply(), 700);
+        assert_eq!(
+            data.burn(acc.bob, 1).err(),
+            Some(PSP22Error::SupplyBelowMinimum)
+        );
+        assert_eq!(data.total_supply(), 700);
+        assert_eq!(data.balance_of(acc.alice), 500);
+        assert_eq!(data.balance_of(acc.bob), 200);
+        // Burning nothing always works
+        assert!(data.burn(acc.bob, 0).is_ok());
+
+        data.set_min_supply(0);
+        assert!(data.burn(acc.bob, 200).is_ok());
+        assert_eq!(data.total_supply(), 500);
+    }
+
+    #[ink::test]
+    fn lowering_cap_keeps_existing_allowances() {
+        let acc = default_accounts::<E>();
+        let (mut data, _) = PSP22Data::new(1000, acc.alice);
+        assert!(data.approve(acc.alice, acc.bob, 500).is_ok());
+
+        data.set_max_allowance(Some(100));
+        assert_eq!(data.allowance(acc.alice, acc.bob), 500);
+        // Spending and decreasing the allowance still work
+        assert!(data.transfer_from(acc.bob, acc.alice, acc.bob, 200).is_ok());
+        assert!(data.decrease_allowance(acc.alice, acc.bob, 100).is_ok());
+        assert_eq!(data.allowance(acc.alice, acc.bob), 200);
+        assert_eq!(
+            data.increase_allowance(acc.alice, acc.bob, 1).err(),
+            Some(PSP22Error::AllowanceCapExceeded)
+        );
+    }
+
+    #[ink::test]
+    fn lifetime_counters_are_consistent_with_supply() {
+        let acc = default_accounts::<E>();
+        let (mut data, _) = PSP22Data::new(1000, acc.alice);
+        assert_eq!((data.total_minted(), data.total_burned()), (1000, 0));
+
+        assert!(data.mint(acc.bob, 500).is_ok());
+        assert!(data.burn(acc.alice, 300).is_ok());
+        assert!(data.mint_with_data(acc.alice, 200, vec![1]).is_ok());
+        assert!(data.burn_with_data(acc.bob, 100, vec![1]).is_ok());
+        // Transfers, no-ops and failed operations do not count
+        assert!(data.transfer(acc.alice, acc.bob, 100).is_ok());
+        assert!(data.mint(acc.bob, 0).is_ok());
+        assert!(data.burn(acc.charlie, 1).is_err());
+        assert!(data.mint(acc.bob, u128::MAX).is_err());
+
+        assert_eq!(data.total_minted(), 1700);
+        assert_eq!(data.total_burned(), 400);
+        assert_eq!(data.total_supply(), 1300);
+        data.assert_invariants();
+    }
+
+    #[ink::test]
+    fn lifetime_minting_cannot_overflow() {
+        let acc = default_accounts::<E>();
+        let (mut data, _) = PSP22Data::new(1000, acc.alice);
+        assert!(data.mint(acc.alice, u128::MAX - 1000).is_ok());
+        assert!(data.burn(acc.alice, u128::MAX).is_ok());
+        assert_eq!(data.total_supply(), 0);
+
+        // The supply would fit, the lifetime amount would not
+        assert_eq!(
+            data.mint(acc.alice, 1).err(),
+            Some(PSP22Error::custom("Max PSP22 lifetime minting exceeded."))
+        );
+        assert_eq!(data.total_supply(), 0);
+        assert_eq!(data.total_minted(), u128::MAX);
+        data.assert_invariants();
+    }
+
+    // Counts events pushed to it, without storing them.
+    #[derive(Default)]
+    struct CountingSink(usize);
+
+    impl EventSink for CountingSink {
+        fn emit(&mut self, _event: PSP22Event) {
+            self.0 += 1;
+        }
+    }
+
+    #[ink::test]
+    fn no_op_operations_do_not_allocate_events() {
+        let acc = default_accounts::<E>();
+        let (mut data, _) = PSP22Data::new(1000, acc.alice);
+        assert!(data.approve(acc.alice, acc.bob, 1000).is_ok());
+
+        let results = [
+            data.transfer(acc.alice, acc.bob, 0),
+            data.transfer(acc.alice, acc.alice, 100),
+            data.transfer_from(acc.bob, acc.alice, acc.charlie, 0),
+            data.transfer_from(acc.bob, acc.alice, acc.alice, 100),
+            data.approve(acc.alice, acc.alice, 100),
+            data.increase_allowance(acc.alice, acc.bob, 0),
+            data.decrease_allowance(acc.alice, acc.bob, 0),
+            data.mint(acc.alice, 0),
+            data.burn(acc.alice, 0),
+        ];
+        for result in results {
+            assert_eq!(result.unwrap().capacity(), 0);
+        }
+
+        let mut sink = CountingSink::default();
+        assert!(data
+            .transfer_with_sink(acc.alice, acc.alice, 100, vec![1], &mut sink)
+            .is_ok());
+        assert!(data.mint_with_sink(acc.alice, 0, &mut sink).is_ok());
+        assert_eq!(sink.0, 0);
+        assert_eq!(data.balance_of(acc.alice), 1000);
+    }
+
+    #[ink::test]
+    fn sink_receives_same_events_as_vector() {
+        let acc = default_accounts::<E>();
+        let (mut data, _) = PSP22Data::new(1000, acc.alice);
+        assert!(data.approve(acc.alice, acc.bob, 500).is_ok());
+
+        let mut sink = CountingSink::default();
+        assert!(data
+            .transfer_from_with_sink(acc.bob, acc.alice, acc.charlie, 100, vec![1], &mut sink)
+            .is_ok());
+        // Approval, Transfer and TransferWithData
+        assert_eq!(sink.0, 3);
+
+        let events = data
+            .transfer_from_with_data(acc.bob, acc.alice, acc.charlie, 100, vec![1])
+            .unwrap();
+        assert!(matches!(
+            events[..],
+            [
+                PSP22Event::Approval(_),
+                PSP22Event::Transfer(_),
+                PSP22Event::TransferWithData(_)
+            ]
+        ));
+        assert_eq!(data.balance_of(acc.charlie), 200);
+        assert_eq!(data.allowance(acc.alice, acc.bob), 300);
+
+        let mut sink = CountingSink::default();
+        assert_eq!(
+            data.burn_with_sink(acc.charlie, 300, &mut sink).err(),
+            Some(PSP22Error::InsufficientBalance)
+        );
+        assert_eq!(sink.0, 0);
+    }
+
+    #[ink::test]
+    fn transfer_all_moves_whole_balance() {
+        let acc = default_accounts::<E>();
+        let (mut data, _) = PSP22Data::new(1000, acc.alice);
+
+        let (value, events) = data.transfer_all(acc.alice, acc.bob, vec![7]).unwrap();
+        assert_eq!(value, 1000);
+        assert_eq!(data.balance_of(acc.alice), 0);
+        assert_eq!(data.balance_of(acc.bob), 1000);
+        let transfer = events[0].as_transfer().unwrap();
+        assert_eq!(transfer.from, Some(acc.alice));
+        assert_eq!(transfer.to, Some(acc.bob));
+        assert_eq!(transfer.value, 1000);
+        assert!(matches!(
+            &events[1],
+            PSP22Event::TransferWithData(e) if e.value == 1000 && e.data == vec![7]
+        ));
+    }
+
+    #[ink::test]
+    fn transfer_all_is_no_op_for_empty_balance_and_self_transfer() {
+        let acc = default_accounts::<E>();
+        let (mut data, _) = PSP22Data::new(1000, acc.alice);
+
+        let (value, events) = data.transfer_all(acc.bob, acc.charlie, vec![]).unwrap();
+        assert_eq!(value, 0);
+        assert!(events.is_empty());
+
+        let (value, events) = data.transfer_all(acc.alice, acc.alice, vec![]).unwrap();
+        assert_eq!(value, 0);
+        assert!(events.is_empty());
+        assert_eq!(data.balance_of(acc.alice), 1000);
+    }
+
+    #[ink::test]
+    fn outcomes_match_subsequent_queries() {
+        let acc = default_accounts::<E>();
+        let (mut data, _) = PSP22Data::new(1000, acc.alice);
+        let mut events = Vec::new();
+
+        let outcome = data
+            .transfer_with_sink(acc.alice, acc.bob, 100, vec![], &mut events)
+            .unwrap();
+        assert_eq!(outcome.from_balance, data.balance_of(acc.alice));
+        assert_eq!(outcome.to_balance, data.balance_of(acc.bob));
+        assert_eq!(outcome.to_balance, 100);
+
+        let outcome = data
+            .increase_allowance_with_sink(acc.alice, acc.bob, 300, &mut events)
+            .unwrap();
+        assert_eq!(outcome.allowance, data.allowance(acc.alice, acc.bob));
+        let outcome = data
+            .decrease_allowance_with_sink(acc.alice, acc.bob, 100, &mut events)
+            .unwrap();
+        assert_eq!(outcome.allowance, data.allowance(acc.alice, acc.bob));
+        assert_eq!(outcome.allowance, 200);
+
+        let outcome = data
+            .transfer_from_with_sink(acc.bob, acc.alice, acc.charlie, 150, vec![], &mut events)
+            .unwrap();
+        assert_eq!(outcome.from_balance, data.balance_of(acc.alice));
+        assert_eq!(outcome.to_balance, data.balance_of(acc.charlie));
+        assert_eq!(outcome.from_balance, 750);
+
+        let outcome = data.mint_with_sink(acc.bob, 50, &mut events).unwrap();
+        assert_eq!(outcome.balance, data.balance_of(acc.bob));
+        assert_eq!(outcome.total_supply, data.total_supply());
+        assert_eq!(outcome.total_supply, 1050);
+
+        let outcome = data.burn_with_sink(acc.bob, 150, &mut events).unwrap();
+        assert_eq!(outcome.balance, data.balance_of(acc.bob));
+        assert_eq!(outcome.total_supply, data.total_supply());
+        assert_eq!(outcome.balance, 0);
+
+        // No-op operations report current values as well
+        let outcome = data
+            .transfer_with_sink(acc.alice, acc.alice, 100, vec![], &mut events)
+            .unwrap();
+        assert_eq!(outcome.from_balance, 750);
+        assert_eq!(outcome.to_balance, 750);
+        let outcome = data.mint_with_sink(acc.charlie, 0, &mut events).unwrap();
+        assert_eq!(outcome.balance, 150);
+        assert_eq!(outcome.total_supply, 900);
+    }
+
+    #[ink::test]
+    fn verify_transfer_predicts_transfer() {
+        let acc = default_accounts::<E>();
+        let (mut data, _) = PSP22Data::new(1000, acc.alice);
+
+        for (from, to, value) in [
+            (acc.alice, acc.bob, 1001),
+            (acc.alice, acc.alice, 1001),
+            (acc.bob, acc.alice, 1),
+            (acc.bob, acc.alice, 0),
+            (acc.alice, acc.alice, 1000),
+            (acc.alice, acc.bob, 1000),
+        ] {
+            let verified = data.verify_transfer(from, to, value);
+            assert_eq!(data.transfer(from, to, value).map(|_| ()), verified);
+        }
+        assert_eq!(data.balance_of(acc.bob), 1000);
+
+        data.pause();
+        assert_eq!(
+            data.verify_transfer(acc.bob, acc.alice, 0),
+            Err(PSP22Error::Paused)
+        );
+        // Verification does not change the state
+        assert_eq!(data.balance_of(acc.bob), 1000);
+    }
+
+    #[ink::test]
+    fn batch_approve_sets_all_allowances() {
+        let acc = default_accounts::<E>();
+        let (mut data, _) = PSP22Data::new(1000, acc.alice);
+        assert!(data.approve(acc.alice, acc.bob, 500).is_ok());
+
+        let events = data
+            .batch_approve(
+                acc.alice,
+                vec![(acc.bob, 100), (acc.alice, 300), (acc.charlie, 200)],
+            )
+            .unwrap();
+        assert!(matches!(
+            &events[..],
+            [PSP22Event::Approval(first), PSP22Event::Approval(second)]
+                if first.spender == acc.bob && first.amount == 100
+                    && second.spender == acc.charlie && second.amount == 200
+        ));
+        assert_eq!(data.allowance(acc.alice, acc.bob), 100);
+        assert_eq!(data.allowance(acc.alice, acc.charlie), 200);
+        // Self-approval is skipped
+        assert_eq!(data.allowance(acc.alice, acc.alice), 0);
+
+        assert!(data.batch_approve(acc.alice, vec![]).unwrap().is_empty());
+    }
+
+    #[ink::test]
+    fn approve_checked_with_matching_allowance_works() {
+        let acc = default_accounts::<E>();
+        let (mut data, _) = PSP22Data::new(1000, acc.alice);
+        assert!(data.approve(acc.alice, acc.bob, 100).is_ok());
+
+        let events = data.approve_checked(acc.alice, acc.bob, 100, 50).unwrap();
+        assert!(matches!(
+            &events[..],
+            [PSP22Event::Approval(e)] if e.amount == 50
+        ));
+        assert_eq!(data.allowance(acc.alice, acc.bob), 50);
+    }
+
+    #[ink::test]
+    fn approve_checked_with_mismatched_allowance_fails() {
+        let acc = default_accounts::<E>();
+        let (mut data, _) = PSP22Data::new(1000, acc.alice);
+        assert!(data.approve(acc.alice, acc.bob, 100).is_ok());
+        // The spender front-runs the change
+        assert!(data.transfer_from(acc.bob, acc.alice, acc.bob, 100).is_ok());
+
+        assert_eq!(
+            data.approve_checked(acc.alice, acc.bob, 100, 50).err(),
+            Some(PSP22Error::AllowanceMismatch)
+        );
+        assert_eq!(data.allowance(acc.alice, acc.bob), 0);
+    }
+
+    #[ink::test]
+    fn paused_token_blocks_core_operations() {
+        let acc = default_accounts::<E>();
+        let (mut data, _) = PSP22Data::new(1000, acc.alice);
+        assert!(data.approve(acc.alice, acc.bob, 100).is_ok());
+
+        data.pause();
+        assert!(data.is_paused());
+        assert_eq!(
+            data.transfer(acc.alice, acc.bob, 10).err(),
+            Some(PSP22Error::Paused)
+        );
+        assert_eq!(
+            data.transfer_from(acc.bob, acc.alice, acc.charlie, 10)
+                .err(),
+            Some(PSP22Error::Paused)
+        );
+        assert_eq!(data.mint(acc.bob, 10).err(), Some(PSP22Error::Paused));
+        assert_eq!(data.burn(acc.alice, 10).err(), Some(PSP22Error::Paused));
+        // Even no-ops fail
+        assert_eq!(
+            data.transfer(acc.alice, acc.bob, 0).err(),
+            Some(PSP22Error::Paused)
+        );
+        // Approvals still work
+        assert!(data.approve(acc.alice, acc.bob, 50).is_ok());
+
+        assert_eq!(data.total_supply(), 1000);
+        assert_eq!(data.balance_of(acc.alice), 1000);
+        assert_eq!(data.allowance(acc.alice, acc.bob), 50);
+    }
+
+    #[ink::test]
+    fn unpausing_restores_core_operations() {
+        let acc = default_accounts::<E>();
+        let (mut data, _) = PSP22Data::new(1000, acc.alice);
+        assert!(data.approve(acc.alice, acc.bob, 100).is_ok());
+        data.pause();
+        data.unpause();
+
+        assert!(!data.is_paused());
+        assert!(data.transfer(acc.alice, acc.bob, 10).is_ok());
+        assert!(data
+            .transfer_from(acc.bob, acc.alice, acc.charlie, 10)
+            .is_ok());
+        assert!(data.mint(acc.bob, 10).is_ok());
+        assert!(data.burn(acc.alice, 10).is_ok());
+        assert_eq!(data.balance_of(acc.alice), 970);
+        assert_eq!(data.balance_of(acc.bob), 20);
+        assert_eq!(data.balance_of(acc.charlie), 10);
+        assert_eq!(data.total_supply(), 1000);
+    }
+
+    #[cfg(feature = "zero-address-guard")]
+    #[ink::test]
+    fn zero_address_guard_rejects_zero_recipient() {
+        let acc = default_accounts::<E>();
+        let zero = AccountId::from(ZERO);
+        let (mut data, _) = PSP22Data::new(1000, acc.alice);
+        assert!(data.approve(acc.alice, acc.bob, 1000).is_ok());
+
+        assert_eq!(
+            data.transfer(acc.alice, zero, 100).err(),
+            Some(PSP22Error::ZeroRecipientAddress)
+        );
+        assert_eq!(
+            data.transfer_from(acc.bob, acc.alice, zero, 100).err(),
+            Some(PSP22Error::ZeroRecipientAddress)
+        );
+        assert_eq!(
+            data.mint(zero, 100).err(),
+            Some(PSP22Error::ZeroRecipientAddress)
+        );
+        assert_eq!(data.balance_of(zero), 0);
+        assert_eq!(data.balance_of(acc.alice), 1000);
+        assert_eq!(data.total_supply(), 1000);
+    }
+
+    #[cfg(not(feature = "zero-address-guard"))]
+    #[ink::test]
+    fn zero_recipient_is_accepted_without_guard() {
+        let acc = default_accounts::<E>();
+        let zero = AccountId::from(ZERO);
+        let (mut data, _) = PSP22Data::new(1000, acc.alice);
+        assert!(data.approve(acc.alice, acc.bob, 1000).is_ok());
+
+        assert!(data.transfer(acc.alice, zero, 100).is_ok());
+        assert!(data.transfer_from(acc.bob, acc.alice, zero, 100).is_ok());
+        assert!(data.mint(zero, 100).is_ok());
+        assert_eq!(data.balance_of(zero), 300);
+        assert_eq!(data.total_supply(), 1100);
+    }
+}
+
+// Tests of every operation of `PSP22Data` with fabricated accounts, needing nothing
+// from the environment except the off-chain storage set up by `#[ink::test]`.
+#[cfg(test)]
+mod operation_tests {
+    use super::*;
+
+    fn account(n: u8) -> AccountId {
+        AccountId::from([n; 32])
+    }
+
+    #[ink::test]
+    fn construction_and_queries() {
+        let (data, events) = PSP22Data::new(1000, account(1));
+        assert_eq!(events.len(), 1);
+        assert_eq!(data.total_supply(), 1000);
+        assert_eq!(data.total_minted(), 1000);
+        assert_eq!(data.total_burned(), 0);
+        assert_eq!(data.balance_of(account(1)), 1000);
+        assert_eq!(data.balance_of(account(2)), 0);
+        assert_eq!(data.allowance(account(1), account(2)), 0);
+        assert_eq!(data.storage_version(), STORAGE_VERSION);
+        assert!(!data.is_paused());
+        assert_eq!(data.max_allowance(), None);
+        data.assert_invariants();
+
+        let (data, events) =
+            PSP22Data::new_with_initial_balances(vec![(account(1), 10), (account(2), 20)]);
+        assert_eq!(events.len(), 2);
+        assert_eq!(data.total_supply(), 30);
+        assert_eq!(data.balance_of(account(2)), 20);
+        data.assert_invariants();
+    }
+
+    #[ink::test]
+    fn transfers() {
+        let (mut data, _) = PSP22Data::new(1000, account(1));
+
+        assert_eq!(data.verify_transfer(account(1), account(2), 100), Ok(()));
+        assert_eq!(data.transfer(account(1), account(2), 100).unwrap().len(), 1);
+        assert_eq!(
+            data.transfer_with_data(account(2), account(3), 10, vec![1])
+                .unwrap()
+                .len(),
+            2
+        );
+        assert_eq!(
+            data.transfer(account(2), account(3), 91).err(),
+            Some(PSP22Error::InsufficientBalance)
+        );
+        let (value, _) = data.transfer_all(account(3), account(4), vec![]).unwrap();
+        assert_eq!(value, 10);
+        let (swept, _) = data
+            .sweep_dust(vec![account(2), account(4)], 50, account(1))
+            .unwrap();
+        assert_eq!(swept, 10);
+        assert_eq!(data.balance_of(account(1)), 910);
+        assert_eq!(data.balance_of(account(2)), 90);
+        assert_eq!(data.balance_of(account(4)), 0);
+
+        assert!(data.approve(account(1), account(5), 100).is_ok());
+        assert!(data
+            .transfer_from(account(5), account(1), account(6), 60)
+            .is_ok());
+        assert!(data
+            .transfer_from_with_data(account(5), account(1), account(6), 40, vec![1])
+            .is_ok());
+        assert_eq!(
+            data.transfer_from(account(5), account(1), account(6), 1)
+                .err(),
+            Some(PSP22Error::InsufficientAllowance)
+        );
+        assert_eq!(data.balance_of(account(6)), 100);
+        assert_eq!(data.total_supply(), 1000);
+        data.assert_invariants();
+    }
+
+    #[ink::test]
+    fn allowances() {
+        let (mut data, _) = PSP22Data::new(1000, account(1));
+
+        assert!(data.approve(account(1), account(2), 100).is_ok());
+        assert!(data.increase_allowance(account(1), account(2), 50).is_ok());
+        assert!(data.decrease_allowance(account(1), account(2), 30).is_ok());
+        assert_eq!(data.allowance(account(1), account(2)), 120);
+        assert_eq!(
+            data.approve_checked(account(1), account(2), 100, 0).err(),
+            Some(PSP22Error::AllowanceMismatch)
+        );
+        assert!(data.approve_checked(account(1), account(2), 120, 0).is_ok());
+        assert_eq!(data.allowance(account(1), account(2)), 0);
+
+        data.set_max_allowance(Some(10));
+        assert_eq!(
+            data.approve(account(1), account(2), 11).err(),
+            Some(PSP22Error::AllowanceCapExceeded)
+        );
+
+        data.enable_allowance_index();
+        assert!(data.approve(account(1), account(3), 10).is_ok());
+        assert_eq!(data.spenders_of(account(1)), [account(3)]);
+        assert_eq!(data.approvals_of(account(1)), [(account(3), 10)]);
+        let (left, events) = data.revoke_all_approvals(account(1));
+        assert_eq!((left, events.len()), (0, 1));
+        assert_eq!(data.allowance(account(1), account(3)), 0);
+    }
+
+    #[ink::test]
+    fn supply_changes() {
+        let (mut data, _) = PSP22Data::new(1000, account(1));
+
+        assert!(data.mint(account(2), 100).is_ok());
+        assert_eq!(
+            data.mint_with_data(account(2), 100, vec![1]).unwrap().len(),
+            2
+        );
+        assert_eq!(
+            data.mint_capped(account(2), 1, 1200).err(),
+            Some(PSP22Error::CapExceeded)
+        );
+        assert!(data.burn(account(2), 50).is_ok());
+        assert_eq!(
+            data.burn_with_data(account(2), 50, vec![1]).unwrap().len(),
+            2
+        );
+        assert_eq!(
+            data.burn(account(2), 101).err(),
+            Some(PSP22Error::InsufficientBalance)
+        );
+        assert_eq!(data.total_supply(), 1100);
+        assert_eq!(data.total_minted(), 1200);
+        assert_eq!(data.total_burned(), 100);
+        data.assert_invariants();
+    }
+
+    #[ink::test]
+    fn pausing_and_self_transfers() {
+        let (mut data, _) = PSP22Data::new(1000, account(1));
+
+        assert!(data
+            .transfer(account(1), account(1), 10)
+            .unwrap()
+            .is_empty());
+        data.enable_self_transfer_events();
+        assert!(data.is_self_transfer_events_enabled());
+        assert_eq!(data.transfer(account(1), account(1), 10).unwrap().len(), 1);
+
+        data.pause();
+        assert!(data.is_paused());
+        assert_eq!(
+            data.verify_transfer(account(1), account(2), 10),
+            Err(PSP22Error::Paused)
+        );
+        assert_eq!(data.mint(account(1), 10).err(), Some(PSP22Error::Paused));
+        data.unpause();
+        assert!(data.transfer(account(1), account(2), 10).is_ok());
+    }
+}
+
+// Boundary tests of the arithmetic on balances, allowances and the total supply.
+#[cfg(test)]
+mod arithmetic_tests {
+    use super::*;
+    use ink::env::{test::default_accounts, DefaultEnvironment as E};
+
+    const MAX: u128 = u128::MAX;
+
+    #[ink::test]
+    fn balances_at_max_supply() {
+        let acc = default_accounts::<E>();
+        let (mut data, _) = PSP22Data::new(MAX, acc.alice);
+
+        assert!(data.transfer(acc.alice, acc.bob, MAX - 1).is_ok());
+        assert_eq!(data.balance_of(acc.alice), 1);
+        assert_eq!(data.balance_of(acc.bob), MAX - 1);
+        assert!(data.transfer(acc.alice, acc.bob, 1).is_ok());
+        assert_eq!(data.balance_of(acc.alice), 0);
+        assert_eq!(data.balance_of(acc.bob), MAX);
+
+        assert_eq!(
+            data.transfer(acc.alice, acc.bob, 1).err(),
+            Some(PSP22Error::InsufficientBalance)
+        );
+        assert!(data.mint(acc.alice, 1).is_err());
+        assert_eq!(data.balance_of(acc.alice), 0);
+        assert_eq!(data.total_supply(), MAX);
+
+        assert!(data.burn(acc.bob, MAX).is_ok());
+        assert_eq!(data.total_supply(), 0);
+    }
+
+    #[ink::test]
+    fn recipient_balance_reaches_max_without_overflow() {
+        let acc = default_accounts::<E>();
+        let (mut data, _) = PSP22Data::new(MAX - 1, acc.alice);
+        assert!(data.mint(acc.bob, 1).is_ok());
+
+        assert!(data.transfer(acc.bob, acc.alice, 1).is_ok());
+        assert_eq!(data.balance_of(acc.alice), MAX);
+        assert_eq!(data.total_supply(), MAX);
+    }
+
+    #[test]
+    #[should_panic(expected = "balance exceeds u128::MAX")]
+    fn credit_detects_broken_supply_invariant() {
+        credit(MAX, 1);
+    }
+
+    #[test]
+    fn debit_reports_insufficient_amount() {
+        assert_eq!(debit(MAX, MAX, PSP22Error::InsufficientBalance), Ok(0));
+        assert_eq!(
+            debit(MAX - 1, MAX, PSP22Error::InsufficientAllowance),
+            Err(PSP22Error::InsufficientAllowance)
+        );
+        assert_eq!(credit(MAX - 1, 1), MAX);
+    }
+
+    #[ink::test]
+    fn overflowing_increase_fails_or_saturates() {
+        let acc = default_accounts::<E>();
+        let (mut data, _) = PSP22Data::new(1000, acc.alice);
+        assert!(data.approve(acc.alice, acc.bob, MAX - 10).is_ok());
+
+        // Checked
+        assert_eq!(
+            data.increase_allowance(acc.alice, acc.bob, 11).err(),
+            Some(PSP22Error::AllowanceOverflow)
+        );
+        assert_eq!(data.allowance(acc.alice, acc.bob), MAX - 10);
+
+        // Saturating
+        let events = data
+            .increase_allowance_saturating(acc.alice, acc.bob, 11)
+            .unwrap();
+        assert!(matches!(
+            &events[..],
+            [PSP22Event::Approval(e)] if e.owner == acc.alice && e.spender == acc.bob && e.amount == MAX
+        ));
+        assert_eq!(data.allowance(acc.alice, acc.bob), MAX);
+        assert!(data
+            .increase_allowance_saturating(acc.alice, acc.bob, 1)
+            .unwrap()
+            .is_empty());
+        assert_eq!(data.allowance(acc.alice, acc.bob), MAX);
+        // Below the limit both work the same
+        let events = data
+            .increase_allowance_saturating(acc.alice, acc.charlie, 100)
+            .unwrap();
+        assert!(matches!(&events[..], [PSP22Event::Approval(e)] if e.amount == 100));
+    }
+
+    #[ink::test]
+    fn allowances_at_max() {
+        let acc = default_accounts::<E>();
+        let (mut data, _) = PSP22Data::new(MAX, acc.alice);
+
+        assert!(data.approve(acc.alice, acc.bob, MAX).is_ok());
+        assert!(data
+            .transfer_from(acc.bob, acc.alice, acc.charlie, MAX - 1)
+            .is_ok());
+        assert_eq!(data.allowance(acc.alice, acc.bob), 1);
+        assert_eq!(data.balance_of(acc.charlie), MAX - 1);
+
+        // Increasing the allowance up to u128::MAX works, beyond it fails
+        assert!(data.increase_allowance(acc.alice, acc.bob, MAX - 1).is_ok());
+        assert_eq!(data.allowance(acc.alice, acc.bob), MAX);
+        assert_eq!(
+            data.increase_allowance(acc.alice, acc.bob, 1).err(),
+            Some(PSP22Error::AllowanceOverflow)
+        );
+        assert_eq!(data.allowance(acc.alice, acc.bob), MAX);
+        assert!(data.decrease_allowance(acc.alice, acc.bob, MAX - 1).is_ok());
+        assert_eq!(
+            data.increase_allowance(acc.alice, acc.bob, MAX).err(),
+            Some(PSP22Error::AllowanceOverflow)
+        );
+        assert_eq!(data.allowance(acc.alice, acc.bob), 1);
+        assert!(data.increase_allowance(acc.alice, acc.bob, MAX - 1).is_ok());
+
+        assert!(data.decrease_allowance(acc.alice, acc.bob, MAX).is_ok());
+        assert_eq!(data.allowance(acc.alice, acc.bob), 0);
+        assert_eq!(
+            data.decrease_allowance(acc.alice, acc.bob, 1).err(),
+            Some(PSP22Error::InsufficientAllowance)
+        );
+    }
+}
+
+#[cfg(test)]
+mod allowance_index_tests {
+    use super::*;
+    use ink::env::{test::default_accounts, DefaultEnvironment as E};
+
+    fn sorted(mut approvals: Vec<(AccountId, u128)>) -> Vec<(AccountId, u128)> {
+        approvals.sort();
+        approvals
+    }
+
+    #[ink::test]
+    fn index_is_empty_unless_enabled() {
+        let acc = default_accounts::<E>();
+        let (mut data, _) = PSP22Data::new(1000, acc.alice);
+        assert!(data.approve(acc.alice, acc.bob, 100).is_ok());
+
+        assert!(!data.is_allowance_index_enabled());
+        assert!(data.spenders_of(acc.alice).is_empty());
+        assert!(data.approvals_of(acc.alice).is_empty());
+    }
+
+    #[ink::test]
+    fn spenders_are_added_and_removed() {
+        let acc = default_accounts::<E>();
+        let (mut data, _) = PSP22Data::new(1000, acc.alice);
+        data.enable_allowance_index();
+
+        assert!(data.approve(acc.alice, acc.bob, 100).is_ok());
+        assert!(data.increase_allowance(acc.alice, acc.charlie, 200).is_ok());
+        assert!(data.approve(acc.alice, acc.django, 300).is_ok());
+        // Changing an existing allowance does not duplicate the spender
+        assert!(data.increase_allowance(acc.alice, acc.bob, 50).is_ok());
+        assert_eq!(
+            sorted(data.approvals_of(acc.alice)),
+            sorted(vec![(acc.bob, 150), (acc.charlie, 200), (acc.django, 300)])
+        );
+
+        // transfer_from spending the whole allowance
+        assert!(data.transfer_from(acc.bob, acc.alice, acc.eve, 150).is_ok());
+        assert_eq!(
+            sorted(data.approvals_of(acc.alice)),
+            sorted(vec![(acc.charlie, 200), (acc.django, 300)])
+        );
+        // decrease_allowance to zero
+        assert!(data.decrease_allowance(acc.alice, acc.charlie, 200).is_ok());
+        assert_eq!(data.approvals_of(acc.alice), [(acc.django, 300)]);
+        // approve(0)
+        assert!(data.approve(acc.alice, acc.django, 0).is_ok());
+        assert!(data.spenders_of(acc.alice).is_empty());
+        // Removing an allowance which does not exist
+        assert!(data.approve(acc.alice, acc.django, 0).is_ok());
+        assert!(data.spenders_of(acc.alice).is_empty());
+    }
+
+    #[ink::test]
+    fn partial_spending_keeps_spender() {
+        let acc = default_accounts::<E>();
+        let (mut data, _) = PSP22Data::new(1000, acc.alice);
+        data.enable_allowance_index();
+        assert!(data.approve(acc.alice, acc.bob, 100).is_ok());
+
+        assert!(data.transfer_from(acc.bob, acc.alice, acc.bob, 60).is_ok());
+        assert!(data.decrease_allowance(acc.alice, acc.bob, 20).is_ok());
+        assert_eq!(data.approvals_of(acc.alice), [(acc.bob, 20)]);
+    }
+
+    #[ink::test]
+    fn number_of_spenders_is_bounded() {
+        let acc = default_accounts::<E>();
+        let (mut data, _) = PSP22Data::new(1000, acc.alice);
+        data.enable_allowance_index();
+        for i in 0..MAX_SPENDERS {
+            let spender = AccountId::from([i as u8 + 1; 32]);
+            assert!(data.approve(acc.alice, spender, 1).is_ok());
+        }
+        let first = AccountId::from([1; 32]);
+
+        let extra = AccountId::from([0xff; 32]);
+        assert_eq!(
+            data.approve(acc.alice, extra, 1).err(),
+            Some(PSP22Error::custom("too many spenders"))
+        );
+        assert_eq!(
+            data.increase_allowance(acc.alice, extra, 1).err(),
+            Some(PSP22Error::custom("too many spenders"))
+        );
+        assert_eq!(data.allowance(acc.alice, extra), 0);
+        // Existing allowances can still be changed, and removing one makes room
+        assert!(data.increase_allowance(acc.alice, first, 1).is_ok());
+        assert!(data.approve(acc.alice, first, 0).is_ok());
+        assert!(data.approve(acc.alice, extra, 1).is_ok());
+        assert_eq!(data.spenders_of(acc.alice).len(), MAX_SPENDERS);
+    }
+
+    #[ink::test]
+    fn revoking_all_approvals_takes_multiple_calls() {
+        let acc = default_accounts::<E>();
+        let (mut data, _) = PSP22Data::new(1000, acc.alice);
+        data.enable_allowance_index();
+        let spenders: Vec<AccountId> = (0..MAX_SPENDERS)
+            .map(|i| AccountId::from([i as u8 + 1; 32]))
+            .collect();
+        for spender in &spenders {
+            assert!(data.approve(acc.alice, *spender, 10).is_ok());
+        }
+        assert!(data.approve(acc.bob, acc.charlie, 10).is_ok());
+
+        let mut revoked = Vec::new();
+        let mut calls = 0;
+        loop {
+            let (left, events) = data.revoke_all_approvals(acc.alice);
+            calls += 1;
+            assert!(events.len() <= MAX_REVOKED_PER_CALL);
+            for event in events {
+                match event {
+                    PSP22Event::Approval(e) => {
+                        assert_eq!((e.owner, e.amount), (acc.alice, 0));
+                        revoked.push(e.spender);
+                    }
+                    _ => panic!("unexpected event"),
+                }
+            }
+            assert_eq!(left as usize, MAX_SPENDERS - revoked.len());
+            assert_eq!(data.spenders_of(acc.alice).len(), left as usize);
+            if left == 0 {
+                break;
+            }
+        }
+        assert_eq!(calls, MAX_SPENDERS.div_ceil(MAX_REVOKED_PER_CALL));
+        revoked.sort();
+        assert_eq!(revoked, spenders);
+        for spender in spenders {
+            assert_eq!(data.allowance(acc.alice, spender), 0);
+        }
+        // Other owners are not affected, and further calls are no-ops
+        assert_eq!(data.allowance(acc.bob, acc.charlie), 10);
+        let (left, events) = data.revoke_all_approvals(acc.alice);
+        assert_eq!(left, 0);
+        assert!(events.is_empty());
+    }
+
+    #[ink::test]
+    fn revoke_all_clears_every_allowance_at_once() {
+        let acc = default_accounts::<E>();
+        let (mut data, _) = PSP22Data::new(1000, acc.alice);
+        assert_eq!(
+            data.revoke_all(acc.alice).err(),
+            Some(PSP22Error::custom("allowance index disabled"))
+        );
+        data.enable_allowance_index();
+        let spenders: Vec<AccountId> = (0..MAX_SPENDERS)
+            .map(|i| AccountId::from([i as u8 + 1; 32]))
+            .collect();
+        for spender in &spenders {
+            assert!(data.approve(acc.alice, *spender, 10).is_ok());
+        }
+        assert!(data.approve(acc.bob, acc.charlie, 10).is_ok());
+
+        let events = data.revoke_all(acc.alice).unwrap();
+        assert_eq!(events.len(), MAX_SPENDERS);
+        let mut revoked: Vec<AccountId> = events
+            .into_iter()
+            .map(|event| match event {
+                PSP22Event::Approval(e) => {
+                    assert_eq!((e.owner, e.amount), (acc.alice, 0));
+                    e.spender
+                }
+                _ => panic!("unexpected event"),
+            })
+            .collect();
+        revoked.sort();
+        assert_eq!(revoked, spenders);
+        for spender in spenders {
+            assert_eq!(data.allowance(acc.alice, spender), 0);
+        }
+        assert!(data.spenders_of(acc.alice).is_empty());
+        // Other owners are not affected, and further calls are no-ops
+        assert_eq!(data.allowance(acc.bob, acc.charlie), 10);
+        assert!(data.revoke_all(acc.alice).unwrap().is_empty());
+    }
+}
+
+#[cfg(test)]
+mod layout_tests {
+    use super::*;
+    use ink::env::hash::{Blake2x256, HashOutput};
+    use ink::metadata::layout::Layout;
+    use ink::storage::traits::StorageLayout;
+
+    #[test]
+    fn storage_keys_match_hashes() {
+        let cases = [
+            ("PSP22Data::total_supply", TOTAL_SUPPLY_KEY),
+            ("PSP22Data::balances", BALANCES_KEY),
+            ("PSP22Data::allowances", ALLOWANCES_KEY),
+            ("PSP22Data::total_minted", TOTAL_MINTED_KEY),
+            ("PSP22Data::total_burned", TOTAL_BURNED_KEY),
+            ("PSP22Data::max_allowance", MAX_ALLOWANCE_KEY),
+            ("PSP22Data::allowance_index", ALLOWANCE_INDEX_KEY),
+            ("PSP22Data::spenders", SPENDERS_KEY),
+            ("PSP22Data::self_transfer_events", SELF_TRANSFER_EVENTS_KEY),
+            ("PSP22Data::version", STORAGE_VERSION_KEY),
+            ("PSP22Data::min_supply", MIN_SUPPLY_KEY),
+        ];
+        for (name, key) in cases {
+            let mut output = <Blake2x256 as HashOutput>::Type::default();
+            ink::env::hash_bytes::<Blake2x256>(name.as_bytes(), &mut output);
+            assert_eq!(
+                u32::from_be_bytes([output[0], output[1], output[2], output[3]]),
+                key,
+                "{name}"
+            );
+        }
+    }
+
+    // Balances must not move, wherever `PSP22Data` is placed in the contract storage.
+    #[test]
+    fn storage_keys_do_not_depend_on_parent_key() {
+        for parent in [0, 0x12345678, u32::MAX] {
+            let Layout::Struct(layout) = <PSP22Data as StorageLayout>::layout(&parent) else {
+                panic!("PSP22Data layout is not a struct");
+            };
+            let keys: Vec<_> = layout
+                .fields()
+                .iter()
+                .filter_map(|field| match field.layout() {
+                    Layout::Root(root) => Some((*field.name(), *root.root_key().key())),
+                    _ => None,
+                })
+                .collect();
+            assert_eq!(
+                keys,
+                [
+                    ("total_supply", TOTAL_SUPPLY_KEY),
+                    ("balances", BALANCES_KEY),
+                    ("allowances", ALLOWANCES_KEY),
+                    ("total_minted", TOTAL_MINTED_KEY),
+                    ("total_burned", TOTAL_BURNED_KEY),
+                    ("max_allowance", MAX_ALLOWANCE_KEY),
+                    ("allowance_index", ALLOWANCE_INDEX_KEY),
+                    ("spenders", SPENDERS_KEY),
+                    ("self_transfer_events", SELF_TRANSFER_EVENTS_KEY),
+                    ("version", STORAGE_VERSION_KEY),
+                    ("min_supply", MIN_SUPPLY_KEY),
+                ]
+            );
         }
-        self.total_supply = self.total_supply.saturating_sub(value);
-        Ok(vec![transfer_event(Some(from), None, value)])
     }
 }
@@ -0,0 +1,232 @@
+use crate::data::{PSP22Data, PSP22Event};
+use crate::errors::PSP22Error;
+use crate::events::AirdropClaimed;
+use ink::{
+    env::hash::{Blake2x256, HashOutput},
+    prelude::vec::Vec,
+    primitives::AccountId,
+    scale::Encode,
+    storage::Mapping,
+};
+
+/// A class implementing the internal logic of the PSP22Airdrop extension.
+///
+/// Eligible accounts and amounts are the leaves of a Merkle tree, of which only the root
+/// is stored. A leaf is `blake2b_256` of the SCALE-encoded `(account, amount)` pair
+/// (see `leaf_hash`), an inner node is `blake2b_256` of its two children concatenated
+/// in ascending order, so proofs do not need to tell left and right siblings apart.
+/// Leaves (48 bytes hashed) cannot be confused with inner nodes (64 bytes hashed).
+///
+/// Each account can claim only once, also if the root is changed afterwards.
+/// Claimed tokens are minted to the account.
+#[ink::storage_item]
+#[derive(Debug, Default)]
+pub struct PSP22AirdropData {
+    merkle_root: [u8; 32],
+    claimed: Mapping<AccountId, bool>,
+}
+
+impl PSP22AirdropData {
+    /// Returns the current Merkle root of the airdrop (all zeros if not set).
+    pub fn merkle_root(&self) -> [u8; 32] {
+        self.merkle_root
+    }
+
+    /// Sets the Merkle root of the airdrop. Access control is left to the contract.
+    pub fn set_merkle_root(&mut self, root: [u8; 32]) {
+        self.merkle_root = root;
+    }
+
+    /// Returns `true` if `account` has already claimed its tokens.
+    pub fn is_claimed(&self, account: AccountId) -> bool {
+        self.claimed.get(account).unwrap_or_default()
+    }
+
+    /// Returns the leaf of the Merkle tree for `account` eligible for `amount` tokens.
+    pub fn leaf_hash(account: AccountId, amount: u128) -> [u8; 32] {
+        hash(&(account, amount).encode())
+    }
+
+    /// Returns `true` if `proof` proves that `leaf` is in the tree with the current root.
+    pub fn verify(&self, leaf: [u8; 32], proof: &[[u8; 32]]) -> bool {
+        let root = proof
+            .iter()
+            .fold(leaf, |node, sibling| hash_pair(node, *sibling));
+        root == self.merkle_root
+    }
+
+    /// Mints `amount` tokens to `account` in `data`, provided that `proof` proves
+    /// `account` is eligible for exactly `amount` tokens.
+    ///
+    /// Returns `AlreadyClaimed` error if `account` has already claimed its tokens,
+    /// `ZeroAmount` error if `amount` is zero (there is nothing to claim, so a zero leaf
+    /// is never marked as claimed) and `InvalidProof` error if the proof does not match
+    /// the current root.
+    pub fn claim(
+        &mut self,
+        data: &mut PSP22Data,
+        account: AccountId,
+        amount: u128,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<Vec<PSP22Event>, PSP22Error> {
+        if self.is_claimed(account) {
+            return Err(PSP22Error::AlreadyClaimed);
+        }
+        if amount == 0 {
+            return Err(PSP22Error::ZeroAmount);
+        }
+        if !self.verify(Self::leaf_hash(account, amount), &proof) {
+            return Err(PSP22Error::InvalidProof);
+        }
+        let mut events = data.mint(account, amount)?;
+        self.claimed.insert(account, &true);
+        events.push(PSP22Event::AirdropClaimed(AirdropClaimed {
+            account,
+            amount,
+        }));
+        Ok(events)
+    }
+}
+
+fn hash(input: &[u8]) -> [u8; 32] {
+    let mut output = <Blake2x256 as HashOutput>::Type::default();
+    ink::env::hash_bytes::<Blake2x256>(input, &mut output);
+    output
+}
+
+fn hash_pair(a: [u8; 32], b: [u8; 32]) -> [u8; 32] {
+    let (low, high) = if a <= b { (a, b) } else { (b, a) };
+    hash(&[low, high].concat())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink::env::{test::default_accounts, DefaultEnvironment as E};
+
+    // A tree with 3 leaves: root = H(H(l0, l1), l2)
+    fn setup() -> (PSP22Data, PSP22AirdropData, [[u8; 32]; 3]) {
+        let acc = default_accounts::<E>();
+        let (data, _) = PSP22Data::new(1000, acc.alice);
+        let leaves = [
+            PSP22AirdropData::leaf_hash(acc.bob, 100),
+            PSP22AirdropData::leaf_hash(acc.charlie, 200),
+            PSP22AirdropData::leaf_hash(acc.django, 300),
+        ];
+        let mut airdrop = PSP22AirdropData::default();
+        airdrop.set_merkle_root(hash_pair(hash_pair(leaves[0], leaves[1]), leaves[2]));
+        (data, airdrop, leaves)
+    }
+
+    #[ink::test]
+    fn eligible_accounts_can_claim() {
+        let acc = default_accounts::<E>();
+        let (mut data, mut airdrop, leaves) = setup();
+
+        let events = airdrop
+            .claim(&mut data, acc.bob, 100, vec![leaves[1], leaves[2]])
+            .unwrap();
+        assert!(matches!(
+            &events[..],
+            [PSP22Event::Minted { to, value: 100 }, PSP22Event::AirdropClaimed(e)]
+                if *to == acc.bob && e.account == acc.bob && e.amount == 100
+        ));
+        let proof = vec![hash_pair(leaves[0], leaves[1])];
+        assert!(airdrop.claim(&mut data, acc.django, 300, proof).is_ok());
+
+        assert!(airdrop.is_claimed(acc.bob));
+        assert!(!airdrop.is_claimed(acc.charlie));
+        assert_eq!(data.balance_of(acc.bob), 100);
+        assert_eq!(data.balance_of(acc.django), 300);
+        assert_eq!(data.total_supply(), 1400);
+    }
+
+    #[ink::test]
+    fn claim_of_other_amount_or_account_fails() {
+        let acc = default_accounts::<E>();
+        let (mut data, mut airdrop, leaves) = setup();
+
+        let proof = vec![leaves[0], leaves[2]];
+        assert_eq!(
+            airdrop
+                .claim(&mut data, acc.charlie, 201, proof.clone())
+                .err(),
+            Some(PSP22Error::InvalidProof)
+        );
+        assert_eq!(
+            airdrop.claim(&mut data, acc.eve, 200, proof.clone()).err(),
+            Some(PSP22Error::InvalidProof)
+        );
+        assert!(!airdrop.is_claimed(acc.charlie));
+        assert!(airdrop.claim(&mut data, acc.charlie, 200, proof).is_ok());
+        assert_eq!(data.total_supply(), 1200);
+    }
+
+    #[ink::test]
+    fn second_claim_fails() {
+        let acc = default_accounts::<E>();
+        let (mut data, mut airdrop, leaves) = setup();
+        let proof = vec![leaves[1], leaves[2]];
+
+        assert!(airdrop
+            .claim(&mut data, acc.bob, 100, proof.clone())
+            .is_ok());
+        assert_eq!(
+            airdrop.claim(&mut data, acc.bob, 100, proof).err(),
+            Some(PSP22Error::AlreadyClaimed)
+        );
+        assert_eq!(data.balance_of(acc.bob), 100);
+        assert_eq!(data.total_supply(), 1100);
+    }
+
+    #[ink::test]
+    fn claim_with_corrupted_proof_fails() {
+        let acc = default_accounts::<E>();
+        let (mut data, mut airdrop, leaves) = setup();
+        let mut corrupted = leaves[1];
+        corrupted[0] ^= 1;
+
+        assert_eq!(
+            airdrop
+                .claim(&mut data, acc.bob, 100, vec![corrupted, leaves[2]])
+                .err(),
+            Some(PSP22Error::InvalidProof)
+        );
+        assert_eq!(
+            airdrop
+                .claim(&mut data, acc.bob, 100, vec![leaves[1]])
+                .err(),
+            Some(PSP22Error::InvalidProof)
+        );
+        assert!(!airdrop.is_claimed(acc.bob));
+        assert_eq!(data.total_supply(), 1000);
+    }
+
+    #[ink::test]
+    fn zero_claim_fails() {
+        let acc = default_accounts::<E>();
+        let (mut data, _) = PSP22Data::new(1000, acc.alice);
+        let mut airdrop = PSP22AirdropData::default();
+        let leaf = PSP22AirdropData::leaf_hash(acc.bob, 0);
+        airdrop.set_merkle_root(leaf);
+
+        assert!(airdrop.verify(leaf, &[]));
+        assert_eq!(
+            airdrop.claim(&mut data, acc.bob, 0, Vec::new()).err(),
+            Some(PSP22Error::ZeroAmount)
+        );
+        assert!(!airdrop.is_claimed(acc.bob));
+    }
+
+    #[ink::test]
+    fn claim_with_unset_root_fails() {
+        let acc = default_accounts::<E>();
+        let (mut data, _) = PSP22Data::new(1000, acc.alice);
+        let mut airdrop = PSP22AirdropData::default();
+
+        assert_eq!(
+            airdrop.claim(&mut data, acc.bob, 100, Vec::new()).err(),
+            Some(PSP22Error::InvalidProof)
+        );
+    }
+}
@@ -0,0 +1,155 @@
+use crate::data::PSP22Event;
+use crate::errors::PSP22Error;
+use crate::events::{AddedToBlacklist, RemovedFromBlacklist};
+use ink::{
+    prelude::{vec, vec::Vec},
+    primitives::AccountId,
+    storage::Mapping,
+};
+
+/// A class implementing the internal logic of the PSP22Blacklist extension.
+///
+/// Blacklisted accounts can neither send nor receive tokens, nor spend the tokens of others
+/// with `transfer_from`. The blacklist is independent of `PSP22WhitelistData`, a token can use
+/// both: a transfer then has to pass both checks.
+///
+/// Like other extensions, it has to be wired up by the contract:
+/// `check_transfer(caller, from, to)` should be called before every `transfer`
+/// and `transfer_from` on `PSP22Data`. As the check is not a part of `PSP22Data`, a contract
+/// using the blacklist must not implement `PSP22Multicall` with `impls::multicall`.
+/// Access control of modifying the blacklist is left to the contract as well.
+#[ink::storage_item]
+#[derive(Debug, Default)]
+pub struct PSP22BlacklistData {
+    blacklist: Mapping<AccountId, bool>,
+}
+
+impl PSP22BlacklistData {
+    /// Returns `true` if `account` is blacklisted.
+    pub fn is_blacklisted(&self, account: AccountId) -> bool {
+        self.blacklist.get(account).unwrap_or_default()
+    }
+
+    /// Adds `account` to the blacklist. Adding an account already blacklisted
+    /// is a no-op (no events).
+    pub fn add_to_blacklist(&mut self, account: AccountId) -> Vec<PSP22Event> {
+        if self.is_blacklisted(account) {
+            return vec![];
+        }
+        self.blacklist.insert(account, &true);
+        vec![PSP22Event::AddedToBlacklist(AddedToBlacklist { account })]
+    }
+
+    /// Removes `account` from the blacklist. Removing an account not blacklisted
+    /// is a no-op (no events).
+    pub fn remove_from_blacklist(&mut self, account: AccountId) -> Vec<PSP22Event> {
+        if !self.is_blacklisted(account) {
+            return vec![];
+        }
+        self.blacklist.remove(account);
+        vec![PSP22Event::RemovedFromBlacklist(RemovedFromBlacklist {
+            account,
+        })]
+    }
+
+    /// Returns `Blacklisted` error if `caller`, `from` or `to` is blacklisted.
+    /// `caller` is the account initiating the transfer: `from` itself for `transfer`,
+    /// the spender for `transfer_from`.
+    pub fn check_transfer(
+        &self,
+        caller: AccountId,
+        from: AccountId,
+        to: AccountId,
+    ) -> Result<(), PSP22Error> {
+        if [caller, from, to]
+            .into_iter()
+            .any(|account| self.is_blacklisted(account))
+        {
+            return Err(PSP22Error::Blacklisted);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::PSP22Data;
+    use crate::data_whitelist::PSP22WhitelistData;
+    use ink::env::{test::default_accounts, DefaultEnvironment as E};
+
+    #[ink::test]
+    fn blacklisted_accounts_cannot_send_or_receive() {
+        let acc = default_accounts::<E>();
+        let (mut data, _) = PSP22Data::new(1000, acc.alice);
+        let mut blacklist = PSP22BlacklistData::default();
+        assert!(data.transfer(acc.alice, acc.bob, 100).is_ok());
+
+        assert!(matches!(
+            &blacklist.add_to_blacklist(acc.bob)[..],
+            [PSP22Event::AddedToBlacklist(e)] if e.account == acc.bob
+        ));
+        assert!(blacklist.add_to_blacklist(acc.bob).is_empty());
+        for (from, to) in [(acc.alice, acc.bob), (acc.bob, acc.alice)] {
+            assert_eq!(
+                blacklist.check_transfer(from, from, to),
+                Err(PSP22Error::Blacklisted)
+            );
+        }
+        assert!(blacklist
+            .check_transfer(acc.alice, acc.alice, acc.charlie)
+            .is_ok());
+
+        assert!(matches!(
+            &blacklist.remove_from_blacklist(acc.bob)[..],
+            [PSP22Event::RemovedFromBlacklist(e)] if e.account == acc.bob
+        ));
+        assert!(blacklist.remove_from_blacklist(acc.bob).is_empty());
+        assert!(blacklist
+            .check_transfer(acc.bob, acc.bob, acc.alice)
+            .is_ok());
+    }
+
+    #[ink::test]
+    fn blacklisted_spender_cannot_transfer_from() {
+        let acc = default_accounts::<E>();
+        let (mut data, _) = PSP22Data::new(1000, acc.alice);
+        let mut blacklist = PSP22BlacklistData::default();
+        assert!(data.approve(acc.alice, acc.bob, 100).is_ok());
+        blacklist.add_to_blacklist(acc.bob);
+
+        assert_eq!(
+            blacklist.check_transfer(acc.bob, acc.alice, acc.charlie),
+            Err(PSP22Error::Blacklisted)
+        );
+        // Other spenders of the same owner are not affected
+        assert!(blacklist
+            .check_transfer(acc.django, acc.alice, acc.charlie)
+            .is_ok());
+    }
+
+    #[ink::test]
+    fn blacklist_and_whitelist_coexist() {
+        let acc = default_accounts::<E>();
+        let mut whitelist = PSP22WhitelistData::default();
+        let mut blacklist = PSP22BlacklistData::default();
+        whitelist.add_to_whitelist(acc.alice);
+        whitelist.add_to_whitelist(acc.bob);
+        whitelist.set_whitelist_enabled(true);
+        blacklist.add_to_blacklist(acc.bob);
+        let check = |from, to| -> Result<(), PSP22Error> {
+            whitelist.check_transfer(from, to, None)?;
+            blacklist.check_transfer(from, from, to)
+        };
+
+        // Whitelisted, but blacklisted
+        assert_eq!(check(acc.alice, acc.bob), Err(PSP22Error::Blacklisted));
+        // Not blacklisted, but not whitelisted
+        assert_eq!(
+            check(acc.alice, acc.charlie),
+            Err(PSP22Error::transfer_restricted("not whitelisted"))
+        );
+        assert!(blacklist.is_blacklisted(acc.bob));
+        assert!(whitelist.is_whitelisted(acc.bob));
+    }
+}
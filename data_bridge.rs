@@ -0,0 +1,258 @@
+use crate::access_control::{AccessControlData, RoleType};
+use crate::data::{PSP22Data, PSP22Event};
+use crate::errors::PSP22Error;
+use crate::events::{BridgedIn, BridgedOut};
+use ink::{prelude::vec::Vec, primitives::AccountId, storage::Mapping};
+
+/// The role of `AccessControlData` allowed to mint and burn bridged tokens:
+/// first 4 bytes of `blake2b_256("PSP22Bridgeable::BRIDGE_ROLE")`.
+pub const BRIDGE_ROLE: RoleType = 0x8aca5c5c;
+
+/// A class implementing the internal logic of the PSP22Bridgeable extension.
+///
+/// Tokens are minted when they arrive from another chain and burned when they leave it,
+/// in both cases only by accounts with `BRIDGE_ROLE` in the contract's `AccessControlData`.
+/// The bridge passes an opaque reference of the transaction on the other chain with each
+/// mint, which can be processed only once, so that a message relayed twice does not mint
+/// twice. Like `transfer_from`, burning tokens of another account requires its allowance
+/// for the bridge.
+#[ink::storage_item]
+#[derive(Debug, Default)]
+pub struct PSP22BridgeData {
+    processed: Mapping<Vec<u8>, ()>,
+}
+
+impl PSP22BridgeData {
+    /// Returns `true` if a mint with `tx_ref` has already been processed.
+    pub fn is_processed(&self, tx_ref: &[u8]) -> bool {
+        self.processed.contains(tx_ref)
+    }
+
+    /// Mints `value` tokens to `to` in `data`, for tokens bridged in by the transaction
+    /// `tx_ref` on the other chain.
+    ///
+    /// Returns `Custom (caller is missing the role)` error if `caller` does not have
+    /// `BRIDGE_ROLE` in `roles` and `Custom (tx_ref already processed)` error if `tx_ref`
+    /// has been used before.
+    pub fn bridge_mint(
+        &mut self,
+        data: &mut PSP22Data,
+        roles: &AccessControlData,
+        caller: AccountId,
+        to: AccountId,
+        value: u128,
+        tx_ref: Vec<u8>,
+    ) -> Result<Vec<PSP22Event>, PSP22Error> {
+        roles.ensure_role(BRIDGE_ROLE, caller)?;
+        if self.is_processed(&tx_ref) {
+            return Err(PSP22Error::custom("tx_ref already processed"));
+        }
+        let mut events = data.mint(to, value)?;
+        self.processed.insert(&tx_ref, &());
+        events.push(PSP22Event::BridgedIn(BridgedIn { to, value, tx_ref }));
+        Ok(events)
+    }
+
+    /// Burns `value` tokens of `from` in `data`, for tokens bridged out to `dest`
+    /// (an address on the other chain, opaque to this contract). Unless `from` is `caller`,
+    /// the burned tokens are spent from the allowance given by `from` to `caller`.
+    ///
+    /// Returns `Custom (caller is missing the role)` error if `caller` does not have
+    /// `BRIDGE_ROLE` in `roles`, `InsufficientAllowance` error if the allowance is lower
+    /// than `value` and `InsufficientBalance` error if `from` holds less than `value` tokens.
+    pub fn bridge_burn(
+        &self,
+        data: &mut PSP22Data,
+        roles: &AccessControlData,
+        caller: AccountId,
+        from: AccountId,
+        value: u128,
+        dest: Vec<u8>,
+    ) -> Result<Vec<PSP22Event>, PSP22Error> {
+        roles.ensure_role(BRIDGE_ROLE, caller)?;
+        if from != caller && data.allowance(from, caller) < value {
+            return Err(PSP22Error::InsufficientAllowance);
+        }
+        let mut events = data.burn(from, value)?;
+        // Cannot fail, checked above
+        events.extend(data.decrease_allowance(from, caller, value)?);
+        events.push(PSP22Event::BridgedOut(BridgedOut { from, value, dest }));
+        Ok(events)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::AccessControlError;
+    use ink::env::{
+        test::{default_accounts, DefaultAccounts},
+        DefaultEnvironment as E,
+    };
+
+    // Alice holds the whole supply and is the admin, Django has the bridge role.
+    fn setup(acc: &DefaultAccounts<E>) -> (PSP22Data, AccessControlData, PSP22BridgeData) {
+        let (data, _) = PSP22Data::new(1000, acc.alice);
+        let (mut roles, _) = AccessControlData::new(acc.alice);
+        roles
+            .grant_role(acc.alice, BRIDGE_ROLE, acc.django)
+            .unwrap();
+        (data, roles, PSP22BridgeData::default())
+    }
+
+    #[ink::test]
+    fn bridge_mints_and_burns() {
+        let acc = default_accounts::<E>();
+        let (mut data, roles, mut bridge) = setup(&acc);
+
+        let events = bridge
+            .bridge_mint(&mut data, &roles, acc.django, acc.bob, 300, vec![1, 2, 3])
+            .unwrap();
+        assert!(matches!(
+            &events[..],
+            [PSP22Event::Minted { to, value: 300 }, PSP22Event::BridgedIn(e)]
+                if *to == acc.bob && e.to == acc.bob && e.value == 300 && e.tx_ref == [1, 2, 3]
+        ));
+        assert!(bridge.is_processed(&[1, 2, 3]));
+
+        data.approve(acc.bob, acc.django, 150).unwrap();
+        let events = bridge
+            .bridge_burn(&mut data, &roles, acc.django, acc.bob, 100, vec![9; 20])
+            .unwrap();
+        assert!(matches!(
+            &events[..],
+            [
+                PSP22Event::Burned { from, value: 100 },
+                PSP22Event::Approval(a),
+                PSP22Event::BridgedOut(e)
+            ] if *from == acc.bob && a.amount == 50 && e.from == acc.bob && e.value == 100
+                && e.dest == [9; 20]
+        ));
+        assert_eq!(data.balance_of(acc.bob), 200);
+        assert_eq!(data.allowance(acc.bob, acc.django), 50);
+        assert_eq!(data.total_supply(), 1200);
+    }
+
+    #[ink::test]
+    fn replayed_tx_ref_is_rejected() {
+        let acc = default_accounts::<E>();
+        let (mut data, roles, mut bridge) = setup(&acc);
+
+        assert!(bridge
+            .bridge_mint(&mut data, &roles, acc.django, acc.bob, 300, vec![1, 2, 3])
+            .is_ok());
+        for to in [acc.bob, acc.charlie] {
+            assert_eq!(
+                bridge
+                    .bridge_mint(&mut data, &roles, acc.django, to, 300, vec![1, 2, 3])
+                    .err(),
+                Some(PSP22Error::custom("tx_ref already processed"))
+            );
+        }
+        assert!(bridge
+            .bridge_mint(&mut data, &roles, acc.django, acc.bob, 300, vec![1, 2, 4])
+            .is_ok());
+        assert_eq!(data.balance_of(acc.bob), 600);
+    }
+
+    #[ink::test]
+    fn callers_without_bridge_role_are_rejected() {
+        let acc = default_accounts::<E>();
+        let (mut data, mut roles, mut bridge) = setup(&acc);
+        let unauthorized = Some(PSP22Error::from(AccessControlError::MissingRole));
+
+        assert_eq!(
+            bridge
+                .bridge_mint(&mut data, &roles, acc.alice, acc.alice, 300, vec![1])
+                .err(),
+            unauthorized
+        );
+        assert_eq!(
+            bridge
+                .bridge_burn(&mut data, &roles, acc.alice, acc.alice, 300, vec![1])
+                .err(),
+            unauthorized
+        );
+        assert!(!bridge.is_processed(&[1]));
+
+        roles
+            .revoke_role(acc.alice, BRIDGE_ROLE, acc.django)
+            .unwrap();
+        roles.grant_role(acc.alice, BRIDGE_ROLE, acc.eve).unwrap();
+        assert_eq!(
+            bridge
+                .bridge_mint(&mut data, &roles, acc.django, acc.alice, 300, vec![1])
+                .err(),
+            unauthorized
+        );
+        assert!(bridge
+            .bridge_mint(&mut data, &roles, acc.eve, acc.alice, 300, vec![1])
+            .is_ok());
+        roles.revoke_role(acc.alice, BRIDGE_ROLE, acc.eve).unwrap();
+        assert_eq!(
+            bridge
+                .bridge_burn(&mut data, &roles, acc.eve, acc.alice, 300, vec![1])
+                .err(),
+            unauthorized
+        );
+        assert_eq!(data.total_supply(), 1300);
+    }
+
+    #[ink::test]
+    fn unapproved_burn_fails() {
+        let acc = default_accounts::<E>();
+        let (mut data, roles, bridge) = setup(&acc);
+
+        assert_eq!(
+            bridge
+                .bridge_burn(&mut data, &roles, acc.django, acc.alice, 300, vec![1])
+                .err(),
+            Some(PSP22Error::InsufficientAllowance)
+        );
+        data.approve(acc.alice, acc.django, 299).unwrap();
+        assert_eq!(
+            bridge
+                .bridge_burn(&mut data, &roles, acc.django, acc.alice, 300, vec![1])
+                .err(),
+            Some(PSP22Error::InsufficientAllowance)
+        );
+        assert_eq!(data.balance_of(acc.alice), 1000);
+        assert_eq!(data.allowance(acc.alice, acc.django), 299);
+
+        // The bridge does not need an allowance for its own tokens
+        data.transfer(acc.alice, acc.django, 300).unwrap();
+        assert!(bridge
+            .bridge_burn(&mut data, &roles, acc.django, acc.django, 300, vec![1])
+            .is_ok());
+        assert_eq!(data.total_supply(), 700);
+    }
+
+    #[ink::test]
+    fn burn_exceeding_balance_fails() {
+        let acc = default_accounts::<E>();
+        let (mut data, roles, bridge) = setup(&acc);
+        data.approve(acc.alice, acc.django, 1001).unwrap();
+
+        assert_eq!(
+            bridge
+                .bridge_burn(&mut data, &roles, acc.django, acc.alice, 1001, vec![1])
+                .err(),
+            Some(PSP22Error::InsufficientBalance)
+        );
+        assert_eq!(data.balance_of(acc.alice), 1000);
+        assert_eq!(data.allowance(acc.alice, acc.django), 1001);
+        assert_eq!(data.total_supply(), 1000);
+    }
+
+    #[test]
+    fn bridge_role_matches_hash() {
+        use ink::env::hash::{Blake2x256, HashOutput};
+
+        let mut output = <Blake2x256 as HashOutput>::Type::default();
+        ink::env::hash_bytes::<Blake2x256>(b"PSP22Bridgeable::BRIDGE_ROLE", &mut output);
+        assert_eq!(
+            u32::from_be_bytes([output[0], output[1], output[2], output[3]]),
+            BRIDGE_ROLE
+        );
+    }
+}
@@ -0,0 +1,287 @@
+use crate::data::{EventSink, PSP22Data, PSP22Event};
+use crate::errors::PSP22Error;
+use crate::events::FlashLoan;
+use crate::flash::{CALLBACK_SUCCESS, ON_FLASH_LOAN_SELECTOR};
+use ink::{
+    env::{
+        call::{build_call, ExecutionInput, Selector},
+        CallFlags, DefaultEnvironment,
+    },
+    prelude::vec::Vec,
+    primitives::AccountId,
+};
+
+/// Denominator of fees expressed in basis points.
+const BPS_DENOMINATOR: u128 = 10_000;
+
+/// A class implementing the internal logic of the PSP22FlashLender extension.
+///
+/// Flash loans are made in the token itself: the lent tokens are minted to the borrower
+/// and burned, together with the fee, when the loan is repaid in the same transaction.
+/// Because of that, the maximal loan is the amount which can still be minted.
+/// The lent tokens are not counted in `PSP22Data::total_minted` and `total_burned`,
+/// only the burned fee is.
+#[ink::storage_item]
+#[derive(Debug, Default)]
+pub struct PSP22FlashFeeData {
+    fee_bps: u16,
+}
+
+impl PSP22FlashFeeData {
+    /// Creates a flash loan configuration with the fee of `fee_bps` basis points
+    /// (hundredths of a percent) of the loan.
+    ///
+    /// Returns `Custom` error if the fee exceeds 100% (`10_000` basis points).
+    pub fn new(fee_bps: u16) -> Result<PSP22FlashFeeData, PSP22Error> {
+        if u128::from(fee_bps) > BPS_DENOMINATOR {
+            return Err(PSP22Error::custom("flash fee exceeds 100%"));
+        }
+        Ok(PSP22FlashFeeData { fee_bps })
+    }
+
+    /// Returns the fee in basis points.
+    pub fn fee_bps(&self) -> u16 {
+        self.fee_bps
+    }
+
+    /// Returns the maximal amount of `token` available for a flash loan:
+    /// the amount which can still be minted if `token` is this contract, `0` otherwise.
+    pub fn max_flash_loan(&self, data: &PSP22Data, token: AccountId) -> u128 {
+        if token != ink::env::account_id::<DefaultEnvironment>() {
+            return 0;
+        }
+        u128::MAX.saturating_sub(data.total_supply())
+    }
+
+    /// Returns the fee for a flash loan of `amount` of `token`, rounded down.
+    ///
+    /// Returns `Custom` error if `token` is not this contract.
+    pub fn flash_fee(&self, token: AccountId, amount: u128) -> Result<u128, PSP22Error> {
+        if token != ink::env::account_id::<DefaultEnvironment>() {
+            return Err(PSP22Error::custom("unsupported token"));
+        }
+        // Split to avoid overflow of `amount * fee_bps`. As `fee_bps <= BPS_DENOMINATOR`,
+        // the fee never exceeds `amount`.
+        let bps = u128::from(self.fee_bps);
+        let whole = (amount / BPS_DENOMINATOR).saturating_mul(bps);
+        let rest = (amount % BPS_DENOMINATOR).saturating_mul(bps) / BPS_DENOMINATOR;
+        Ok(whole.saturating_add(rest))
+    }
+
+    /// Makes a flash loan of `amount` tokens to `receiver`, initiated by `initiator`.
+    ///
+    /// Mints `amount` tokens to `receiver` and calls its `PSP22FlashBorrower::on_flash_loan`
+    /// with `data`. Afterwards, `amount` plus the fee is burned from `receiver`, using
+    /// the allowance given by `receiver` to this contract. Events are pushed to `sink`
+    /// as they happen, so that the minting `Transfer` precedes the events emitted during
+    /// the callback.
+    ///
+    /// Returns `Custom` error if `token` is not this contract or `amount` exceeds
+    /// `max_flash_loan`. Returns `FlashLoanFailed` error if the callback reverts or does
+    /// not return `CALLBACK_SUCCESS`, or if the loan is not repaid. As the loaned tokens
+    /// are already minted at that point, the contract must revert on error, which ink!
+    /// does for messages returning `Err`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn flash_loan(
+        &self,
+        data: &mut PSP22Data,
+        initiator: AccountId,
+        receiver: AccountId,
+        token: AccountId,
+        amount: u128,
+        payload: Vec<u8>,
+        sink: &mut impl EventSink,
+    ) -> Result<(), PSP22Error> {
+        let fee = self.flash_fee(token, amount)?;
+        if amount > self.max_flash_loan(data, token) {
+            return Err(PSP22Error::custom("flash loan exceeds max_flash_loan"));
+        }
+        // Overflows only for loans which could never be repaid anyway
+        if amount.checked_add(fee).is_none() {
+            return Err(PSP22Error::custom("flash loan exceeds max_flash_loan"));
+        }
+
+        data.flash_mint_with_sink(receiver, amount, sink)?;
+        let result = build_call::<DefaultEnvironment>()
+            .call(receiver)
+            // The borrower needs to call this contract back to approve the repayment
+            .call_flags(CallFlags::default().set_allow_reentry(true))
+            .exec_input(
+                ExecutionInput::new(Selector::new(ON_FLASH_LOAN_SELECTOR))
+                    .push_arg(initiator)
+                    .push_arg(token)
+                    .push_arg(amount)
+                    .push_arg(fee)
+                    .push_arg(payload),
+            )
+            .returns::<Result<[u8; 4], PSP22Error>>()
+            .try_invoke();
+        match result {
+            Ok(Ok(Ok(CALLBACK_SUCCESS))) => {}
+            Ok(Ok(Ok(_))) => {
+                return Err(PSP22Error::flash_loan_failed(
+                    "callback returned wrong selector",
+                ))
+            }
+            _ => return Err(PSP22Error::flash_loan_failed("callback failed")),
+        }
+
+        repay(data, receiver, amount, fee, sink)?;
+        sink.emit(PSP22Event::FlashLoan(FlashLoan {
+            receiver,
+            token,
+            amount,
+            fee,
+        }));
+        Ok(())
+    }
+}
+
+// Takes back `amount` plus `fee` from `receiver`, using its allowance for this contract.
+fn repay(
+    data: &mut PSP22Data,
+    receiver: AccountId,
+    amount: u128,
+    fee: u128,
+    sink: &mut impl EventSink,
+) -> Result<(), PSP22Error> {
+    let lender = ink::env::account_id::<DefaultEnvironment>();
+    amount
+        .checked_add(fee)
+        .ok_or(PSP22Error::InsufficientAllowance)
+        .and_then(|repayment| data.decrease_allowance_with_sink(receiver, lender, repayment, sink))
+        .and_then(|_| data.flash_burn_with_sink(receiver, amount, fee, sink))
+        .map(|_| ())
+        .map_err(|_| PSP22Error::flash_loan_failed("repayment transfer failed"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink::env::test::{callee, default_accounts};
+
+    type E = DefaultEnvironment;
+
+    #[ink::test]
+    fn max_flash_loan_decreases_after_mint() {
+        let acc = default_accounts::<E>();
+        let token = callee::<E>();
+        let (mut data, _) = PSP22Data::new(1000, acc.alice);
+        let flash = PSP22FlashFeeData::default();
+
+        assert_eq!(flash.max_flash_loan(&data, token), u128::MAX - 1000);
+        assert!(data.mint(acc.bob, 500).is_ok());
+        assert_eq!(flash.max_flash_loan(&data, token), u128::MAX - 1500);
+        assert_eq!(flash.max_flash_loan(&data, acc.django), 0);
+    }
+
+    // The callback cannot be called off-chain, so the loan is lent and repaid directly.
+    #[ink::test]
+    fn max_fee_free_loan_does_not_block_minting() {
+        let acc = default_accounts::<E>();
+        let token = callee::<E>();
+        let (mut data, _) = PSP22Data::new(1000, acc.alice);
+        let flash = PSP22FlashFeeData::default();
+        let amount = flash.max_flash_loan(&data, token);
+        let mut events = Vec::new();
+
+        assert!(data
+            .flash_mint_with_sink(acc.bob, amount, &mut events)
+            .is_ok());
+        assert!(data.approve(acc.bob, token, amount).is_ok());
+        assert!(repay(&mut data, acc.bob, amount, 0, &mut events).is_ok());
+        assert!(data.mint(acc.charlie, 500).is_ok());
+        assert_eq!(data.total_supply(), 1500);
+        data.assert_invariants();
+    }
+
+    #[ink::test]
+    fn loan_does_not_change_lifetime_counters() {
+        let acc = default_accounts::<E>();
+        let token = callee::<E>();
+        let (mut data, _) = PSP22Data::new(1000, acc.alice);
+        let mut events = Vec::new();
+
+        for (bps, burned) in [(0, 0), (30, 3)] {
+            let flash = PSP22FlashFeeData::new(bps).unwrap();
+            let fee = flash.flash_fee(token, 1000).unwrap();
+            assert!(data
+                .flash_mint_with_sink(acc.bob, 1000, &mut events)
+                .is_ok());
+            assert!(data.transfer(acc.alice, acc.bob, fee).is_ok());
+            assert!(data.approve(acc.bob, token, 1000 + fee).is_ok());
+            assert!(repay(&mut data, acc.bob, 1000, fee, &mut events).is_ok());
+            assert_eq!(data.total_minted(), 1000);
+            assert_eq!(data.total_burned(), burned);
+            data.assert_invariants();
+        }
+    }
+
+    #[ink::test]
+    fn flash_fee_is_computed_in_basis_points() {
+        let token = callee::<E>();
+        let cases = [
+            (0, 1_000_000, 0),
+            (9, 1_000_000, 900),
+            (30, 1_000_000, 3_000),
+            (30, 333, 0),
+            (30, 334, 1),
+            (10_000, 12_345, 12_345),
+            (5_000, u128::MAX, u128::MAX / 2),
+            (10_000, u128::MAX, u128::MAX),
+        ];
+        for (bps, amount, fee) in cases {
+            let flash = PSP22FlashFeeData::new(bps).unwrap();
+            assert_eq!(flash.flash_fee(token, amount), Ok(fee));
+        }
+    }
+
+    #[ink::test]
+    fn flash_fee_of_other_token_fails() {
+        let acc = default_accounts::<E>();
+        let flash = PSP22FlashFeeData::new(30).unwrap();
+
+        assert_eq!(
+            flash.flash_fee(acc.django, 1000),
+            Err(PSP22Error::custom("unsupported token"))
+        );
+        assert!(PSP22FlashFeeData::new(10_001).is_err());
+    }
+
+    #[ink::test]
+    fn flash_loan_checks_token_and_amount_before_minting() {
+        let acc = default_accounts::<E>();
+        let token = callee::<E>();
+        let (mut data, _) = PSP22Data::new(1000, acc.alice);
+        let flash = PSP22FlashFeeData::new(30).unwrap();
+        let mut events = Vec::new();
+
+        assert_eq!(
+            flash.flash_loan(
+                &mut data,
+                acc.alice,
+                acc.bob,
+                acc.django,
+                100,
+                Vec::new(),
+                &mut events
+            ),
+            Err(PSP22Error::custom("unsupported token"))
+        );
+        assert_eq!(
+            flash.flash_loan(
+                &mut data,
+                acc.alice,
+                acc.bob,
+                token,
+                u128::MAX,
+                Vec::new(),
+                &mut events
+            ),
+            Err(PSP22Error::custom("flash loan exceeds max_flash_loan"))
+        );
+        assert!(events.is_empty());
+        assert_eq!(data.balance_of(acc.bob), 0);
+        assert_eq!(data.total_supply(), 1000);
+    }
+}
@@ -0,0 +1,181 @@
+use crate::errors::PSP22Error;
+use ink::{env::DefaultEnvironment, primitives::AccountId, storage::Mapping};
+
+/// A class implementing the internal logic of time-locked and frozen accounts.
+///
+/// An account locked until a timestamp cannot send tokens before that timestamp,
+/// e.g. for vesting or liquidity mining lockups. A frozen account cannot send tokens
+/// until it is unfrozen, which is up to the administrator of the token (access control
+/// of `set_frozen` is left to the contract). Receiving tokens is not restricted.
+///
+/// Like other extensions, it has to be wired up by the contract: `check_unlocked(from)`
+/// should be called before every operation on `PSP22Data` moving tokens out of `from`,
+/// i.e. `transfer`, `transfer_from` (with the owner of the tokens) and `burn`.
+#[ink::storage_item]
+#[derive(Debug, Default)]
+pub struct PSP22LockableData {
+    locked_until: Mapping<AccountId, u64>,
+    frozen: Mapping<AccountId, ()>,
+}
+
+impl PSP22LockableData {
+    /// Locks `account` until `timestamp` (in milliseconds, like the block timestamp).
+    ///
+    /// A lock can be extended, but not shortened: returns `Custom` error if `account`
+    /// is already locked until a later timestamp. A `timestamp` which is not in the future
+    /// is ignored.
+    pub fn lock_until(&mut self, account: AccountId, timestamp: u64) -> Result<(), PSP22Error> {
+        if timestamp <= now() {
+            return Ok(());
+        }
+        if timestamp < self.unlock_time(account) {
+            return Err(PSP22Error::custom("lock can only be extended"));
+        }
+        self.locked_until.insert(account, &timestamp);
+        Ok(())
+    }
+
+    /// Returns the timestamp until which `account` is locked (`0` if it has never been locked).
+    pub fn unlock_time(&self, account: AccountId) -> u64 {
+        self.locked_until.get(account).unwrap_or_default()
+    }
+
+    /// Returns `true` if `account` cannot send tokens at the current block.
+    pub fn is_locked(&self, account: AccountId) -> bool {
+        now() < self.unlock_time(account)
+    }
+
+    /// Freezes (`frozen == true`) or unfreezes `account`.
+    pub fn set_frozen(&mut self, account: AccountId, frozen: bool) {
+        if frozen {
+            self.frozen.insert(account, &());
+        } else {
+            self.frozen.remove(account);
+        }
+    }
+
+    /// Returns `true` if `account` is frozen.
+    pub fn is_frozen(&self, account: AccountId) -> bool {
+        self.frozen.contains(account)
+    }
+
+    /// Returns `AccountFrozen` error if `account` is frozen, or `AccountLocked` error
+    /// if `account` is locked.
+    pub fn check_unlocked(&self, account: AccountId) -> Result<(), PSP22Error> {
+        if self.is_frozen(account) {
+            return Err(PSP22Error::AccountFrozen);
+        }
+        if self.is_locked(account) {
+            return Err(PSP22Error::AccountLocked);
+        }
+        Ok(())
+    }
+}
+
+fn now() -> u64 {
+    ink::env::block_timestamp::<DefaultEnvironment>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::PSP22Data;
+    use ink::env::test::{default_accounts, set_block_timestamp};
+
+    type E = DefaultEnvironment;
+
+    // Transfers `value` from `from` to `to`, provided that `from` is not locked.
+    fn transfer(
+        data: &mut PSP22Data,
+        lockable: &PSP22LockableData,
+        from: AccountId,
+        to: AccountId,
+        value: u128,
+    ) -> Result<(), PSP22Error> {
+        lockable.check_unlocked(from)?;
+        data.transfer(from, to, value).map(|_| ())
+    }
+
+    #[ink::test]
+    fn lock_in_the_past_is_ignored() {
+        let acc = default_accounts::<E>();
+        let (mut data, _) = PSP22Data::new(1000, acc.alice);
+        let mut lockable = PSP22LockableData::default();
+        set_block_timestamp::<E>(1_000);
+
+        assert!(lockable.lock_until(acc.alice, 500).is_ok());
+        assert!(lockable.lock_until(acc.alice, 1_000).is_ok());
+        assert_eq!(lockable.unlock_time(acc.alice), 0);
+        assert!(!lockable.is_locked(acc.alice));
+        assert!(transfer(&mut data, &lockable, acc.alice, acc.bob, 100).is_ok());
+    }
+
+    #[ink::test]
+    fn lock_in_the_future_blocks_transfer_until_it_expires() {
+        let acc = default_accounts::<E>();
+        let (mut data, _) = PSP22Data::new(1000, acc.alice);
+        let mut lockable = PSP22LockableData::default();
+        set_block_timestamp::<E>(1_000);
+
+        assert!(lockable.lock_until(acc.alice, 2_000).is_ok());
+        assert!(lockable.is_locked(acc.alice));
+        assert_eq!(
+            transfer(&mut data, &lockable, acc.alice, acc.bob, 100),
+            Err(PSP22Error::AccountLocked)
+        );
+        // Receiving tokens is not restricted
+        assert!(data.mint(acc.alice, 100).is_ok());
+        assert_eq!(data.balance_of(acc.alice), 1100);
+
+        set_block_timestamp::<E>(1_999);
+        assert!(lockable.is_locked(acc.alice));
+        set_block_timestamp::<E>(2_000);
+        assert!(!lockable.is_locked(acc.alice));
+        assert!(transfer(&mut data, &lockable, acc.alice, acc.bob, 100).is_ok());
+        assert_eq!(data.balance_of(acc.bob), 100);
+    }
+
+    #[ink::test]
+    fn lock_can_be_extended_but_not_shortened() {
+        let acc = default_accounts::<E>();
+        let mut lockable = PSP22LockableData::default();
+        set_block_timestamp::<E>(1_000);
+
+        assert!(lockable.lock_until(acc.alice, 2_000).is_ok());
+        assert!(lockable.lock_until(acc.alice, 3_000).is_ok());
+        assert_eq!(
+            lockable.lock_until(acc.alice, 2_500),
+            Err(PSP22Error::custom("lock can only be extended"))
+        );
+        assert_eq!(lockable.unlock_time(acc.alice), 3_000);
+    }
+
+    #[ink::test]
+    fn frozen_account_is_reported_before_lock() {
+        let acc = default_accounts::<E>();
+        let (mut data, _) = PSP22Data::new(1000, acc.alice);
+        let mut lockable = PSP22LockableData::default();
+        set_block_timestamp::<E>(1_000);
+
+        lockable.set_frozen(acc.alice, true);
+        assert!(lockable.is_frozen(acc.alice));
+        assert_eq!(
+            transfer(&mut data, &lockable, acc.alice, acc.bob, 100),
+            Err(PSP22Error::AccountFrozen)
+        );
+        assert!(lockable.lock_until(acc.alice, 2_000).is_ok());
+        assert_eq!(
+            transfer(&mut data, &lockable, acc.alice, acc.bob, 100),
+            Err(PSP22Error::AccountFrozen)
+        );
+
+        lockable.set_frozen(acc.alice, false);
+        assert!(!lockable.is_frozen(acc.alice));
+        assert_eq!(
+            transfer(&mut data, &lockable, acc.alice, acc.bob, 100),
+            Err(PSP22Error::AccountLocked)
+        );
+        set_block_timestamp::<E>(2_000);
+        assert!(transfer(&mut data, &lockable, acc.alice, acc.bob, 100).is_ok());
+    }
+}
@@ -0,0 +1,334 @@
+use crate::data::{PSP22Data, PSP22Event};
+use crate::domain::DomainData;
+use crate::errors::PSP22Error;
+use crate::nonces::NoncesData;
+use ink::{
+    env::{
+        hash::{Blake2x256, HashOutput},
+        DefaultEnvironment,
+    },
+    prelude::vec::Vec,
+    primitives::AccountId,
+};
+
+type Hash = <Blake2x256 as HashOutput>::Type;
+
+/// A class implementing the internal logic of the PSP22MetaTx extension.
+///
+/// A meta-transaction allows an `owner` to transfer tokens by signing a message off-chain
+/// and letting a relayer submit it, so that `owner` does not need native tokens to pay for gas.
+/// The relayer is paid for the gas with a `fee` in tokens, set in the signed message.
+///
+/// Holds the meta-transaction nonces of all accounts in `NoncesData`, separate from the permit
+/// nonces. Signatures are verified with sr25519, so `owner` must be an account whose
+/// `AccountId` is its sr25519 public key.
+#[ink::storage_item]
+#[derive(Debug, Default)]
+pub struct PSP22MetaTxData {
+    nonces: NoncesData,
+}
+
+impl PSP22MetaTxData {
+    /// Returns the nonce which must be signed in the next meta-transaction of `owner`.
+    pub fn nonce_of(&self, owner: AccountId) -> u64 {
+        self.nonces.nonce_of(owner)
+    }
+
+    /// Returns the payload which `owner` has to sign to transfer `value` tokens to `to`
+    /// and `fee` tokens to the relayer until `deadline`, using the given `nonce`,
+    /// in the `domain` of the contract.
+    #[allow(clippy::too_many_arguments)]
+    pub fn transfer_payload(
+        &self,
+        domain: &DomainData,
+        owner: AccountId,
+        to: AccountId,
+        value: u128,
+        fee: u128,
+        nonce: u64,
+        deadline: u64,
+    ) -> Hash {
+        let mut output = Hash::default();
+        ink::env::hash_encoded::<Blake2x256, _>(
+            &(
+                domain.domain_separator(),
+                b"PSP22MetaTx",
+                owner,
+                to,
+                value,
+                fee,
+                nonce,
+                deadline,
+            ),
+            &mut output,
+        );
+        output
+    }
+
+    /// Transfers `value` tokens from `owner` to `to` and `fee` tokens from `owner`
+    /// to `relayer` in `data`, if `signature` is a valid signature of `owner` for the transfer
+    /// payload. Either both transfers are made or none of them.
+    ///
+    /// Returns `DeadlineExpired` error if the current block timestamp is greater than `deadline`,
+    /// `InvalidSignature` error if the signature is not valid, `NonceMismatch` error if `nonce`
+    /// is not the current nonce of `owner` (e.g. the meta-transaction was already executed)
+    /// and `InsufficientBalance` error if `owner` holds less than `value + fee` tokens.
+    #[allow(clippy::too_many_arguments)]
+    pub fn transfer_with_signature(
+        &mut self,
+        data: &mut PSP22Data,
+        domain: &DomainData,
+        relayer: AccountId,
+        owner: AccountId,
+        to: AccountId,
+        value: u128,
+        fee: u128,
+        deadline: u64,
+        nonce: u64,
+        signature: Vec<u8>,
+    ) -> Result<Vec<PSP22Event>, PSP22Error> {
+        if ink::env::block_timestamp::<DefaultEnvironment>() > deadline {
+            return Err(PSP22Error::DeadlineExpired);
+        }
+        let payload = self.transfer_payload(domain, owner, to, value, fee, nonce, deadline);
+        let signature: [u8; 64] = signature
+            .as_slice()
+            .try_into()
+            .map_err(|_| PSP22Error::InvalidSignature)?;
+        ink::env::sr25519_verify(&signature, &payload, owner.as_ref())
+            .map_err(|_| PSP22Error::InvalidSignature)?;
+
+        let total = value
+            .checked_add(fee)
+            .ok_or(PSP22Error::InsufficientBalance)?;
+        if data.balance_of(owner) < total {
+            return Err(PSP22Error::InsufficientBalance);
+        }
+        self.nonces.use_checked_nonce(owner, nonce)?;
+        let mut events = data.transfer(owner, to, value)?;
+        events.extend(data.transfer(owner, relayer, fee)?);
+        Ok(events)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink::env::test::{default_accounts, set_block_timestamp, set_callee};
+    use schnorrkel::{signing_context, ExpansionMode, Keypair, MiniSecretKey};
+
+    type E = DefaultEnvironment;
+
+    fn keypair(seed: u8) -> Keypair {
+        MiniSecretKey::from_bytes(&[seed; 32])
+            .unwrap()
+            .expand_to_keypair(ExpansionMode::Ed25519)
+    }
+
+    fn sign(keypair: &Keypair, payload: &Hash) -> Vec<u8> {
+        keypair
+            .sign(signing_context(b"substrate").bytes(payload))
+            .to_bytes()
+            .to_vec()
+    }
+
+    fn setup(supply: u128) -> (PSP22Data, PSP22MetaTxData, DomainData, Keypair, AccountId) {
+        let signer = keypair(1);
+        let owner = AccountId::from(signer.public.to_bytes());
+        let (data, _) = PSP22Data::new(supply, owner);
+        let domain = DomainData::new("Token".into(), "1".into(), 0);
+        (data, PSP22MetaTxData::default(), domain, signer, owner)
+    }
+
+    #[ink::test]
+    fn relayer_receives_fee() {
+        let acc = default_accounts::<E>();
+        let (mut data, mut metatx, domain, signer, owner) = setup(1000);
+        let payload = metatx.transfer_payload(&domain, owner, acc.bob, 100, 5, 0, 10);
+        let signature = sign(&signer, &payload);
+
+        let events = metatx
+            .transfer_with_signature(
+                &mut data,
+                &domain,
+                acc.charlie,
+                owner,
+                acc.bob,
+                100,
+                5,
+                10,
+                0,
+                signature,
+            )
+            .unwrap();
+        assert!(matches!(
+            &events[..],
+            [PSP22Event::Transfer(t1), PSP22Event::Transfer(t2)]
+                if t1.from == Some(owner) && t1.to == Some(acc.bob) && t1.value == 100
+                    && t2.from == Some(owner) && t2.to == Some(acc.charlie) && t2.value == 5
+        ));
+        assert_eq!(data.balance_of(owner), 895);
+        assert_eq!(data.balance_of(acc.bob), 100);
+        assert_eq!(data.balance_of(acc.charlie), 5);
+        assert_eq!(metatx.nonce_of(owner), 1);
+    }
+
+    #[ink::test]
+    fn transfer_signed_by_other_account_fails() {
+        let acc = default_accounts::<E>();
+        let (mut data, mut metatx, domain, _, owner) = setup(1000);
+        let payload = metatx.transfer_payload(&domain, owner, acc.bob, 100, 5, 0, 10);
+        let signature = sign(&keypair(2), &payload);
+
+        assert_eq!(
+            metatx
+                .transfer_with_signature(
+                    &mut data,
+                    &domain,
+                    acc.charlie,
+                    owner,
+                    acc.bob,
+                    100,
+                    5,
+                    10,
+                    0,
+                    signature,
+                )
+                .err(),
+            Some(PSP22Error::InvalidSignature)
+        );
+        assert_eq!(data.balance_of(owner), 1000);
+        assert_eq!(metatx.nonce_of(owner), 0);
+    }
+
+    #[ink::test]
+    fn transfer_signed_for_other_contract_fails() {
+        let acc = default_accounts::<E>();
+        set_callee::<E>(acc.django);
+        let (_, metatx, domain, signer, owner) = setup(1000);
+        let payload = metatx.transfer_payload(&domain, owner, acc.bob, 100, 5, 0, 10);
+        let signature = sign(&signer, &payload);
+
+        // Contract B with the same name, version, chain id and state
+        set_callee::<E>(acc.eve);
+        let (mut data, mut metatx, domain, _, _) = setup(1000);
+        assert_eq!(
+            metatx
+                .transfer_with_signature(
+                    &mut data,
+                    &domain,
+                    acc.charlie,
+                    owner,
+                    acc.bob,
+                    100,
+                    5,
+                    10,
+                    0,
+                    signature,
+                )
+                .err(),
+            Some(PSP22Error::InvalidSignature)
+        );
+        assert_eq!(data.balance_of(owner), 1000);
+    }
+
+    #[ink::test]
+    fn transfer_after_deadline_fails() {
+        let acc = default_accounts::<E>();
+        let (mut data, mut metatx, domain, signer, owner) = setup(1000);
+        let payload = metatx.transfer_payload(&domain, owner, acc.bob, 100, 5, 0, 10);
+        let signature = sign(&signer, &payload);
+
+        set_block_timestamp::<E>(11);
+        assert_eq!(
+            metatx
+                .transfer_with_signature(
+                    &mut data,
+                    &domain,
+                    acc.charlie,
+                    owner,
+                    acc.bob,
+                    100,
+                    5,
+                    10,
+                    0,
+                    signature,
+                )
+                .err(),
+            Some(PSP22Error::DeadlineExpired)
+        );
+        assert_eq!(data.balance_of(owner), 1000);
+    }
+
+    #[ink::test]
+    fn replayed_transfer_fails() {
+        let acc = default_accounts::<E>();
+        let (mut data, mut metatx, domain, signer, owner) = setup(1000);
+        let payload = metatx.transfer_payload(&domain, owner, acc.bob, 100, 5, 0, 10);
+        let signature = sign(&signer, &payload);
+
+        assert!(metatx
+            .transfer_with_signature(
+                &mut data,
+                &domain,
+                acc.charlie,
+                owner,
+                acc.bob,
+                100,
+                5,
+                10,
+                0,
+                signature.clone(),
+            )
+            .is_ok());
+        assert_eq!(
+            metatx
+                .transfer_with_signature(
+                    &mut data,
+                    &domain,
+                    acc.charlie,
+                    owner,
+                    acc.bob,
+                    100,
+                    5,
+                    10,
+                    0,
+                    signature,
+                )
+                .err(),
+            Some(PSP22Error::NonceMismatch)
+        );
+        assert_eq!(data.balance_of(acc.bob), 100);
+        assert_eq!(data.balance_of(acc.charlie), 5);
+    }
+
+    #[ink::test]
+    fn fee_exceeding_balance_fails() {
+        let acc = default_accounts::<E>();
+        let (mut data, mut metatx, domain, signer, owner) = setup(1000);
+        let payload = metatx.transfer_payload(&domain, owner, acc.bob, 999, 2, 0, 10);
+        let signature = sign(&signer, &payload);
+
+        assert_eq!(
+            metatx
+                .transfer_with_signature(
+                    &mut data,
+                    &domain,
+                    acc.charlie,
+                    owner,
+                    acc.bob,
+                    999,
+                    2,
+                    10,
+                    0,
+                    signature,
+                )
+                .err(),
+            Some(PSP22Error::InsufficientBalance)
+        );
+        // Neither the transfer nor the fee is paid and the nonce is not consumed
+        assert_eq!(data.balance_of(owner), 1000);
+        assert_eq!(data.balance_of(acc.charlie), 0);
+        assert_eq!(metatx.nonce_of(owner), 0);
+    }
+}
@@ -0,0 +1,296 @@
+use crate::data::{PSP22Data, PSP22Event};
+use crate::domain::DomainData;
+use crate::errors::PSP22Error;
+use crate::events::PermitUsed;
+use crate::nonces::NoncesData;
+use ink::{
+    env::{
+        hash::{Blake2x256, HashOutput},
+        DefaultEnvironment,
+    },
+    prelude::vec::Vec,
+    primitives::AccountId,
+};
+
+type Hash = <Blake2x256 as HashOutput>::Type;
+
+/// A class implementing the internal logic of the PSP22Permit extension.
+///
+/// Holds the permit nonces of all accounts in `NoncesData`. A permit allows an `owner` to grant
+/// an allowance by signing a message off-chain and letting anyone (e.g. the `spender`)
+/// submit it, so that `owner` does not need to send a transaction.
+///
+/// Signatures are verified with sr25519, so `owner` must be an account whose
+/// `AccountId` is its sr25519 public key.
+#[ink::storage_item]
+#[derive(Debug, Default)]
+pub struct PSP22PermitData {
+    nonces: NoncesData,
+}
+
+impl PSP22PermitData {
+    /// Returns the nonce which must be signed in the next permit of `owner`.
+    pub fn nonces(&self, owner: AccountId) -> u64 {
+        self.nonces.nonce_of(owner)
+    }
+
+    /// Consumes the nonce of `owner`, provided that it is equal to `nonce`,
+    /// see `NoncesData::use_checked_nonce`.
+    pub fn use_checked_nonce(&mut self, owner: AccountId, nonce: u64) -> Result<(), PSP22Error> {
+        self.nonces.use_checked_nonce(owner, nonce)
+    }
+
+    /// Advances the nonce of `owner` to `new_nonce`, invalidating all permits
+    /// signed by `owner` but not submitted yet (e.g. after a key compromise).
+    ///
+    /// Returns `Custom` error if `new_nonce` is not greater than the current nonce.
+    pub fn invalidate_nonces(
+        &mut self,
+        owner: AccountId,
+        new_nonce: u64,
+    ) -> Result<(), PSP22Error> {
+        self.nonces.invalidate_nonces(owner, new_nonce)
+    }
+
+    /// Returns the payload which `owner` has to sign to permit `spender`
+    /// to spend `value` tokens until `deadline`, using the given `nonce`,
+    /// in the `domain` of the contract.
+    pub fn permit_payload(
+        &self,
+        domain: &DomainData,
+        owner: AccountId,
+        spender: AccountId,
+        value: u128,
+        nonce: u64,
+        deadline: u64,
+    ) -> Hash {
+        let mut output = Hash::default();
+        ink::env::hash_encoded::<Blake2x256, _>(
+            &(
+                domain.domain_separator(),
+                b"PSP22Permit",
+                owner,
+                spender,
+                value,
+                nonce,
+                deadline,
+            ),
+            &mut output,
+        );
+        output
+    }
+
+    /// Sets the allowance granted by `owner` to `spender` in `data` to `value`,
+    /// if `signature` is a valid signature of `owner` for the permit payload with `nonce`.
+    ///
+    /// Returns `DeadlineExpired` error if the block timestamp is greater than `deadline`,
+    /// `NonceMismatch` error if `nonce` is not the current nonce of `owner` (e.g. the permit
+    /// has already been used) and `InvalidSignature` error if the signature does not match.
+    #[allow(clippy::too_many_arguments)]
+    pub fn permit(
+        &mut self,
+        data: &mut PSP22Data,
+        domain: &DomainData,
+        owner: AccountId,
+        spender: AccountId,
+        value: u128,
+        nonce: u64,
+        deadline: u64,
+        signature: Vec<u8>,
+    ) -> Result<Vec<PSP22Event>, PSP22Error> {
+        if ink::env::block_timestamp::<DefaultEnvironment>() > deadline {
+            return Err(PSP22Error::DeadlineExpired);
+        }
+        // Checked before the signature, which is costly to verify
+        if nonce != self.nonces(owner) {
+            return Err(PSP22Error::NonceMismatch);
+        }
+        let payload = self.permit_payload(domain, owner, spender, value, nonce, deadline);
+        let signature: [u8; 64] = signature
+            .as_slice()
+            .try_into()
+            .map_err(|_| PSP22Error::InvalidSignature)?;
+        ink::env::sr25519_verify(&signature, &payload, owner.as_ref())
+            .map_err(|_| PSP22Error::InvalidSignature)?;
+
+        self.use_checked_nonce(owner, nonce)?;
+        let mut events = data.approve(owner, spender, value)?;
+        events.push(PSP22Event::PermitUsed(PermitUsed {
+            owner,
+            spender,
+            value,
+            nonce,
+        }));
+        Ok(events)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink::env::test::{default_accounts, set_block_timestamp};
+    use schnorrkel::{signing_context, ExpansionMode, Keypair, MiniSecretKey};
+
+    type E = DefaultEnvironment;
+
+    fn keypair(seed: u8) -> Keypair {
+        MiniSecretKey::from_bytes(&[seed; 32])
+            .unwrap()
+            .expand_to_keypair(ExpansionMode::Ed25519)
+    }
+
+    fn sign(keypair: &Keypair, payload: &Hash) -> Vec<u8> {
+        keypair
+            .sign(signing_context(b"substrate").bytes(payload))
+            .to_bytes()
+            .to_vec()
+    }
+
+    fn setup(supply: u128) -> (PSP22Data, PSP22PermitData, DomainData, Keypair, AccountId) {
+        let signer = keypair(1);
+        let owner = AccountId::from(signer.public.to_bytes());
+        let (data, _) = PSP22Data::new(supply, owner);
+        let domain = DomainData::new("Token".into(), "1".into(), 0);
+        (data, PSP22PermitData::default(), domain, signer, owner)
+    }
+
+    #[ink::test]
+    fn permit_sets_allowance_and_increments_nonce() {
+        let bob = default_accounts::<E>().bob;
+        let (mut data, mut permit, domain, signer, owner) = setup(1000);
+        let payload = permit.permit_payload(&domain, owner, bob, 100, 0, 10);
+        let signature = sign(&signer, &payload);
+
+        let events = permit
+            .permit(&mut data, &domain, owner, bob, 100, 0, 10, signature)
+            .unwrap();
+
+        assert_eq!(data.allowance(owner, bob), 100);
+        assert_eq!(permit.nonces(owner), 1);
+        assert_eq!(events.len(), 2);
+        assert!(matches!(
+            &events[1],
+            PSP22Event::PermitUsed(e) if e.owner == owner && e.spender == bob && e.value == 100 && e.nonce == 0
+        ));
+    }
+
+    #[ink::test]
+    fn permit_with_wrong_nonce_fails() {
+        let bob = default_accounts::<E>().bob;
+        let (mut data, mut permit, domain, signer, owner) = setup(1000);
+        let payload = permit.permit_payload(&domain, owner, bob, 100, 1, 10);
+        let signature = sign(&signer, &payload);
+
+        // Signed for a future nonce
+        assert_eq!(
+            permit
+                .permit(
+                    &mut data,
+                    &domain,
+                    owner,
+                    bob,
+                    100,
+                    1,
+                    10,
+                    signature.clone()
+                )
+                .err(),
+            Some(PSP22Error::NonceMismatch)
+        );
+        // The current nonce, but not the signed one
+        assert_eq!(
+            permit
+                .permit(&mut data, &domain, owner, bob, 100, 0, 10, signature)
+                .err(),
+            Some(PSP22Error::InvalidSignature)
+        );
+        assert_eq!(permit.nonces(owner), 0);
+        assert_eq!(data.allowance(owner, bob), 0);
+    }
+
+    #[ink::test]
+    fn permit_cannot_be_replayed() {
+        let bob = default_accounts::<E>().bob;
+        let (mut data, mut permit, domain, signer, owner) = setup(1000);
+        let payload = permit.permit_payload(&domain, owner, bob, 100, 0, 10);
+        let signature = sign(&signer, &payload);
+
+        assert!(permit
+            .permit(
+                &mut data,
+                &domain,
+                owner,
+                bob,
+                100,
+                0,
+                10,
+                signature.clone()
+            )
+            .is_ok());
+        assert!(data.approve(owner, bob, 0).is_ok());
+        assert_eq!(
+            permit
+                .permit(&mut data, &domain, owner, bob, 100, 0, 10, signature)
+                .err(),
+            Some(PSP22Error::NonceMismatch)
+        );
+        assert_eq!(data.allowance(owner, bob), 0);
+    }
+
+    #[ink::test]
+    fn permit_with_invalidated_nonce_fails() {
+        let bob = default_accounts::<E>().bob;
+        let (mut data, mut permit, domain, signer, owner) = setup(1000);
+        let payload = permit.permit_payload(&domain, owner, bob, 100, 0, 10);
+        let signature = sign(&signer, &payload);
+
+        assert_eq!(permit.invalidate_nonces(owner, 5), Ok(()));
+        assert_eq!(permit.nonces(owner), 5);
+        assert_eq!(
+            permit
+                .permit(&mut data, &domain, owner, bob, 100, 0, 10, signature)
+                .err(),
+            Some(PSP22Error::NonceMismatch)
+        );
+        assert_eq!(data.allowance(owner, bob), 0);
+    }
+
+    #[ink::test]
+    fn permit_after_deadline_fails() {
+        let bob = default_accounts::<E>().bob;
+        let (mut data, mut permit, domain, signer, owner) = setup(1000);
+        let payload = permit.permit_payload(&domain, owner, bob, 100, 0, 10);
+        let signature = sign(&signer, &payload);
+
+        set_block_timestamp::<E>(11);
+        assert_eq!(
+            permit
+                .permit(&mut data, &domain, owner, bob, 100, 0, 10, signature)
+                .err(),
+            Some(PSP22Error::DeadlineExpired)
+        );
+        assert_eq!(permit.nonces(owner), 0);
+    }
+
+    #[ink::test]
+    fn permit_signed_by_other_account_fails() {
+        let bob = default_accounts::<E>().bob;
+        let (mut data, mut permit, domain, _, owner) = setup(1000);
+        let payload = permit.permit_payload(&domain, owner, bob, 100, 0, 10);
+        let signature = sign(&keypair(2), &payload);
+
+        assert_eq!(
+            permit
+                .permit(&mut data, &domain, owner, bob, 100, 0, 10, signature)
+                .err(),
+            Some(PSP22Error::InvalidSignature)
+        );
+        assert_eq!(
+            permit
+                .permit(&mut data, &domain, owner, bob, 100, 0, 10, vec![0; 10])
+                .err(),
+            Some(PSP22Error::InvalidSignature)
+        );
+        assert_eq!(data.allowance(owner, bob), 0);
+    }
+}
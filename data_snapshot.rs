@@ -0,0 +1,432 @@
+use crate::clock::Clock;
+use crate::data::{PSP22Data, PSP22Event};
+use crate::errors::PSP22Error;
+use crate::events::SnapshotCreated;
+use ink::{
+    env::DefaultEnvironment,
+    prelude::{vec, vec::Vec},
+    primitives::AccountId,
+    storage::Mapping,
+};
+
+// Finds the value of the first checkpoint with snapshot id not lower than `id`,
+// using binary search over `count` checkpoints sorted by their snapshot ids.
+// Checkpoints are `(snapshot id, value)` pairs read with `get`.
+fn lookup(count: u32, id: u64, get: impl Fn(u32) -> (u64, u128)) -> Option<u128> {
+    let (mut low, mut high) = (0, count);
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if get(mid).0 < id {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+    if low < count {
+        Some(get(low).1)
+    } else {
+        None
+    }
+}
+
+/// A class implementing the internal logic of the PSP22Snapshot extension.
+///
+/// Keeps historical balances and total supply as of the moments when snapshots were taken.
+/// Values are checkpointed lazily: before a balance (or the total supply) is modified
+/// for the first time after a snapshot, its old value is recorded with the id of the
+/// most recent snapshot. Because of that, the contract must call `update` before every
+/// operation on `PSP22Data` which changes balances or the total supply.
+///
+/// Historical queries use binary search over the checkpoints of an account,
+/// so they are logarithmic in the number of checkpoints.
+///
+/// Snapshots are assigned timepoints of the `Clock` selected at construction
+/// (block numbers by default).
+#[ink::storage_item]
+#[derive(Debug, Default)]
+pub struct PSP22SnapshotData {
+    clock: Clock,
+    current_id: u64,
+    id_to_timepoint: Mapping<u64, u64>,
+    balance_snapshots: Mapping<(AccountId, u32), (u64, u128)>,
+    balance_snapshot_count: Mapping<AccountId, u32>,
+    supply_snapshots: Mapping<u32, (u64, u128)>,
+    supply_snapshot_count: u32,
+}
+
+impl PSP22SnapshotData {
+    /// Creates an empty snapshot registry using the given `clock`.
+    pub fn new(clock: Clock) -> PSP22SnapshotData {
+        PSP22SnapshotData {
+            clock,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the current timepoint of the configured clock.
+    pub fn clock(&self) -> u64 {
+        self.clock.clock()
+    }
+
+    /// Returns the `CLOCK_MODE` string describing the configured clock.
+    pub fn clock_mode(&self) -> &'static str {
+        self.clock.clock_mode()
+    }
+
+    /// Returns the id of the most recent snapshot, `0` if no snapshot has been taken.
+    pub fn current_snapshot_id(&self) -> u64 {
+        self.current_id
+    }
+
+    /// Returns the timepoint at which snapshot `id` was taken.
+    pub fn snapshot_timepoint(&self, id: u64) -> Option<u64> {
+        self.id_to_timepoint.get(id)
+    }
+
+    /// Takes a new snapshot and returns its id.
+    pub fn snapshot(&mut self) -> (u64, Vec<PSP22Event>) {
+        // Snapshot ids cannot realistically reach u64.MAX
+        let id = self.current_id.saturating_add(1);
+        let block_number = ink::env::block_number::<DefaultEnvironment>();
+        self.current_id = id;
+        self.id_to_timepoint.insert(id, &self.clock());
+        (
+            id,
+            vec![PSP22Event::SnapshotCreated(SnapshotCreated {
+                id,
+                block_number,
+            })],
+        )
+    }
+
+    /// Records `balance` as the balance of `account` in the most recent snapshot,
+    /// unless it has already been recorded.
+    ///
+    /// Must be called with the balance from before the modification.
+    pub fn update_account_snapshot(&mut self, account: AccountId, balance: u128) {
+        if self.current_id == 0 {
+            return;
+        }
+        let count = self.balance_snapshot_count.get(account).unwrap_or_default();
+        let last_id = match count {
+            0 => 0,
+            _ => self
+                .balance_snapshots
+                .get((account, count - 1))
+                .map(|(id, _)| id)
+                .unwrap_or_default(),
+        };
+        if last_id < self.current_id {
+            self.balance_snapshots
+                .insert((account, count), &(self.current_id, balance));
+            self.balance_snapshot_count
+                .insert(account, &(count.saturating_add(1)));
+        }
+    }
+
+    /// Records `supply` as the total supply in the most recent snapshot,
+    /// unless it has already been recorded.
+    ///
+    /// Must be called with the total supply from before the modification.
+    pub fn update_total_supply_snapshot(&mut self, supply: u128) {
+        if self.current_id == 0 {
+            return;
+        }
+        let count = self.supply_snapshot_count;
+        let last_id = match count {
+            0 => 0,
+            _ => self
+                .supply_snapshots
+                .get(count - 1)
+                .map(|(id, _)| id)
+                .unwrap_or_default(),
+        };
+        if last_id < self.current_id {
+            self.supply_snapshots
+                .insert(count, &(self.current_id, supply));
+            self.supply_snapshot_count = count.saturating_add(1);
+        }
+    }
+
+    /// Checkpoints the balances of `from` and `to` held in `data` and, in case of
+    /// minting (`from` is `None`) or burning (`to` is `None`), the total supply.
+    ///
+    /// Should be called right before the corresponding operation on `data`.
+    pub fn update(&mut self, data: &PSP22Data, from: Option<AccountId>, to: Option<AccountId>) {
+        for account in [from, to].into_iter().flatten() {
+            self.update_account_snapshot(account, data.balance_of(account));
+        }
+        if from.is_none() || to.is_none() {
+            self.update_total_supply_snapshot(data.total_supply());
+        }
+    }
+
+    /// Returns the balance of `account` at the time snapshot `id` was taken.
+    ///
+    /// Returns `Custom` error if the snapshot `id` does not exist.
+    pub fn balance_of_at(
+        &self,
+        data: &PSP22Data,
+        account: AccountId,
+        id: u64,
+    ) -> Result<u128, PSP22Error> {
+        self.ensure_exists(id)?;
+        let count = self.balance_snapshot_count.get(account).unwrap_or_default();
+        Ok(lookup(count, id, |i| {
+            self.balance_snapshots.get((account, i)).unwrap_or_default()
+        })
+        .unwrap_or_else(|| data.balance_of(account)))
+    }
+
+    /// Returns the total supply at the time snapshot `id` was taken.
+    ///
+    /// Returns `Custom` error if the snapshot `id` does not exist.
+    pub fn total_supply_at(&self, data: &PSP22Data, id: u64) -> Result<u128, PSP22Error> {
+        self.ensure_exists(id)?;
+        Ok(lookup(self.supply_snapshot_count, id, |i| {
+            self.supply_snapshots.get(i).unwrap_or_default()
+        })
+        .unwrap_or_else(|| data.total_supply()))
+    }
+
+    /// Returns the total supply recorded by the most recent snapshot taken not later
+    /// than at `timepoint`, which makes it usable for quorum computations in governance.
+    ///
+    /// Returns `0` if no snapshot was taken until `timepoint` and the current total supply
+    /// if `timepoint` is the current one (or a later one).
+    pub fn get_past_total_supply(&self, data: &PSP22Data, timepoint: u64) -> u128 {
+        if timepoint >= self.clock() {
+            return data.total_supply();
+        }
+        // Binary search for the first snapshot taken after `timepoint`
+        let (mut low, mut high) = (1, self.current_id.saturating_add(1));
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if self.id_to_timepoint.get(mid).unwrap_or_default() <= timepoint {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+        match low - 1 {
+            0 => 0,
+            id => self.total_supply_at(data, id).unwrap_or_default(),
+        }
+    }
+
+    fn ensure_exists(&self, id: u64) -> Result<(), PSP22Error> {
+        if id == 0 || id > self.current_id {
+            return Err(PSP22Error::custom("snapshot does not exist"));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink::env::test::{advance_block, default_accounts, set_block_timestamp};
+
+    type E = DefaultEnvironment;
+
+    fn transfer(
+        data: &mut PSP22Data,
+        snapshots: &mut PSP22SnapshotData,
+        from: AccountId,
+        to: AccountId,
+        value: u128,
+    ) {
+        snapshots.update(data, Some(from), Some(to));
+        assert!(data.transfer(from, to, value).is_ok());
+    }
+
+    #[ink::test]
+    fn snapshot_records_id_and_block() {
+        let mut snapshots = PSP22SnapshotData::default();
+        assert_eq!(snapshots.current_snapshot_id(), 0);
+
+        let (first, _) = snapshots.snapshot();
+        advance_block::<E>();
+        let (second, _) = snapshots.snapshot();
+
+        assert_eq!((first, second), (1, 2));
+        assert_eq!(snapshots.current_snapshot_id(), 2);
+        assert_eq!(
+            snapshots.snapshot_timepoint(second),
+            Some(snapshots.snapshot_timepoint(first).unwrap() + 1)
+        );
+        assert_eq!(snapshots.snapshot_timepoint(3), None);
+    }
+
+    #[ink::test]
+    fn balances_before_and_after_transfer_are_kept() {
+        let acc = default_accounts::<E>();
+        let (mut data, _) = PSP22Data::new(1000, acc.alice);
+        let mut snapshots = PSP22SnapshotData::default();
+
+        let (before, _) = snapshots.snapshot();
+        transfer(&mut data, &mut snapshots, acc.alice, acc.bob, 100);
+        let (after, _) = snapshots.snapshot();
+        transfer(&mut data, &mut snapshots, acc.alice, acc.bob, 50);
+        transfer(&mut data, &mut snapshots, acc.bob, acc.charlie, 20);
+
+        let balance_at = |account, id| snapshots.balance_of_at(&data, account, id).unwrap();
+        assert_eq!(balance_at(acc.alice, before), 1000);
+        assert_eq!(balance_at(acc.bob, before), 0);
+        assert_eq!(balance_at(acc.charlie, before), 0);
+        assert_eq!(balance_at(acc.alice, after), 900);
+        assert_eq!(balance_at(acc.bob, after), 100);
+        assert_eq!(balance_at(acc.charlie, after), 0);
+        assert_eq!(data.balance_of(acc.alice), 850);
+        assert_eq!(data.balance_of(acc.bob), 130);
+        assert_eq!(data.balance_of(acc.charlie), 20);
+    }
+
+    #[ink::test]
+    fn balance_without_later_changes_is_current_balance() {
+        let acc = default_accounts::<E>();
+        let (mut data, _) = PSP22Data::new(1000, acc.alice);
+        let mut snapshots = PSP22SnapshotData::default();
+
+        transfer(&mut data, &mut snapshots, acc.alice, acc.bob, 100);
+        let (id, _) = snapshots.snapshot();
+
+        assert_eq!(snapshots.balance_of_at(&data, acc.alice, id), Ok(900));
+        assert_eq!(snapshots.balance_of_at(&data, acc.bob, id), Ok(100));
+    }
+
+    #[ink::test]
+    fn total_supply_is_kept_after_mints_and_burns() {
+        let acc = default_accounts::<E>();
+        let (mut data, _) = PSP22Data::new(1000, acc.alice);
+        let mut snapshots = PSP22SnapshotData::default();
+
+        let (first, _) = snapshots.snapshot();
+        snapshots.update(&data, None, Some(acc.bob));
+        assert!(data.mint(acc.bob, 500).is_ok());
+        let (second, _) = snapshots.snapshot();
+        snapshots.update(&data, Some(acc.alice), None);
+        assert!(data.burn(acc.alice, 300).is_ok());
+        snapshots.update(&data, None, Some(acc.alice));
+        assert!(data.mint(acc.alice, 10).is_ok());
+        let (third, _) = snapshots.snapshot();
+
+        assert_eq!(snapshots.total_supply_at(&data, first), Ok(1000));
+        assert_eq!(snapshots.total_supply_at(&data, second), Ok(1500));
+        assert_eq!(snapshots.total_supply_at(&data, third), Ok(1210));
+        assert_eq!(data.total_supply(), 1210);
+        assert_eq!(snapshots.balance_of_at(&data, acc.alice, second), Ok(1000));
+        assert_eq!(snapshots.balance_of_at(&data, acc.bob, first), Ok(0));
+        assert_eq!(snapshots.balance_of_at(&data, acc.bob, second), Ok(500));
+    }
+
+    #[ink::test]
+    fn past_total_supply_follows_snapshots_in_blocks() {
+        let acc = default_accounts::<E>();
+        let (mut data, _) = PSP22Data::new(1000, acc.alice);
+        let mut snapshots = PSP22SnapshotData::default();
+        let mint = |data: &mut PSP22Data, snapshots: &mut PSP22SnapshotData, value| {
+            snapshots.update(data, None, Some(acc.alice));
+            assert!(data.mint(acc.alice, value).is_ok());
+        };
+        let burn = |data: &mut PSP22Data, snapshots: &mut PSP22SnapshotData, value| {
+            snapshots.update(data, Some(acc.alice), None);
+            assert!(data.burn(acc.alice, value).is_ok());
+        };
+
+        // Block 0: no snapshot yet
+        advance_block::<E>();
+        // Block 1: supply 1000 recorded
+        snapshots.snapshot();
+        mint(&mut data, &mut snapshots, 500);
+        advance_block::<E>();
+        advance_block::<E>();
+        // Block 3: supply 1500 recorded
+        snapshots.snapshot();
+        burn(&mut data, &mut snapshots, 200);
+        advance_block::<E>();
+        // Block 4: supply 1300 recorded, then changed in the same block
+        snapshots.snapshot();
+        mint(&mut data, &mut snapshots, 50);
+        advance_block::<E>();
+        // Block 5: current
+        burn(&mut data, &mut snapshots, 350);
+
+        assert_eq!(snapshots.get_past_total_supply(&data, 0), 0);
+        assert_eq!(snapshots.get_past_total_supply(&data, 1), 1000);
+        assert_eq!(snapshots.get_past_total_supply(&data, 2), 1000);
+        assert_eq!(snapshots.get_past_total_supply(&data, 3), 1500);
+        assert_eq!(snapshots.get_past_total_supply(&data, 4), 1300);
+        assert_eq!(snapshots.get_past_total_supply(&data, 5), 1000);
+        assert_eq!(snapshots.get_past_total_supply(&data, 100), 1000);
+    }
+
+    #[ink::test]
+    fn past_total_supply_without_snapshots_is_zero() {
+        let acc = default_accounts::<E>();
+        let (data, _) = PSP22Data::new(1000, acc.alice);
+        let snapshots = PSP22SnapshotData::default();
+        advance_block::<E>();
+
+        assert_eq!(snapshots.get_past_total_supply(&data, 0), 0);
+        assert_eq!(snapshots.get_past_total_supply(&data, 1), 1000);
+    }
+
+    #[ink::test]
+    fn clock_mode_matches_clock() {
+        let blocks = PSP22SnapshotData::default();
+        let timestamps = PSP22SnapshotData::new(Clock::Timestamp);
+        advance_block::<E>();
+        set_block_timestamp::<E>(1_000);
+
+        assert_eq!(blocks.clock_mode(), "mode=blocknumber&from=default");
+        assert_eq!(blocks.clock(), 1);
+        assert_eq!(timestamps.clock_mode(), "mode=timestamp");
+        assert_eq!(timestamps.clock(), 1_000);
+    }
+
+    #[ink::test]
+    fn past_total_supply_follows_snapshots_in_time() {
+        let acc = default_accounts::<E>();
+        let (mut data, _) = PSP22Data::new(1000, acc.alice);
+        let mut snapshots = PSP22SnapshotData::new(Clock::Timestamp);
+
+        set_block_timestamp::<E>(1_000);
+        let (id, _) = snapshots.snapshot();
+        snapshots.update(&data, None, Some(acc.bob));
+        assert!(data.mint(acc.bob, 500).is_ok());
+        set_block_timestamp::<E>(7_000);
+        snapshots.snapshot();
+        snapshots.update(&data, Some(acc.bob), None);
+        assert!(data.burn(acc.bob, 100).is_ok());
+        set_block_timestamp::<E>(9_000);
+
+        assert_eq!(snapshots.snapshot_timepoint(id), Some(1_000));
+        assert_eq!(snapshots.get_past_total_supply(&data, 999), 0);
+        assert_eq!(snapshots.get_past_total_supply(&data, 1_000), 1000);
+        assert_eq!(snapshots.get_past_total_supply(&data, 6_999), 1000);
+        assert_eq!(snapshots.get_past_total_supply(&data, 7_000), 1500);
+        assert_eq!(snapshots.get_past_total_supply(&data, 9_000), 1400);
+    }
+
+    #[ink::test]
+    fn nonexistent_snapshot_fails() {
+        let acc = default_accounts::<E>();
+        let (data, _) = PSP22Data::new(1000, acc.alice);
+        let mut snapshots = PSP22SnapshotData::default();
+        snapshots.snapshot();
+
+        assert!(matches!(
+            snapshots.balance_of_at(&data, acc.alice, 0),
+            Err(PSP22Error::Custom(_))
+        ));
+        assert!(matches!(
+            snapshots.balance_of_at(&data, acc.alice, 2),
+            Err(PSP22Error::Custom(_))
+        ));
+        assert!(matches!(
+            snapshots.total_supply_at(&data, 2),
+            Err(PSP22Error::Custom(_))
+        ));
+    }
+}
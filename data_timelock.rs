@@ -0,0 +1,231 @@
+use crate::data::{PSP22Data, PSP22Event};
+use crate::errors::PSP22Error;
+use crate::events::{MintCancelled, MintQueued};
+use ink::{
+    env::DefaultEnvironment,
+    prelude::{vec, vec::Vec},
+    primitives::AccountId,
+    storage::Mapping,
+};
+
+/// Mint announced with `PSP22TimelockData::queue_mint`, waiting for execution.
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct QueuedMint {
+    /// Account receiving the tokens.
+    pub to: AccountId,
+    /// Amount of tokens to mint.
+    pub value: u128,
+    /// Block timestamp (in milliseconds) from which the mint can be executed.
+    pub execute_after: u64,
+}
+
+/// A class implementing the internal logic of the PSP22Timelock extension.
+///
+/// Mints are first queued, which announces them with a `MintQueued` event, and can be
+/// executed only once the block timestamp reaches their `execute_after`, at least `min_delay`
+/// after queueing. Until then they can be cancelled. Each queued mint can be executed
+/// at most once.
+///
+/// Like other extensions, it has to be wired up by the contract, which is also
+/// responsible for restricting who can queue and cancel mints.
+#[ink::storage_item]
+#[derive(Debug, Default)]
+pub struct PSP22TimelockData {
+    last_id: u64,
+    min_delay: u64,
+    queued: Mapping<u64, QueuedMint>,
+}
+
+impl PSP22TimelockData {
+    /// Creates a timelock requiring queued mints to wait at least `min_delay` milliseconds.
+    pub fn new(min_delay: u64) -> PSP22TimelockData {
+        PSP22TimelockData {
+            min_delay,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the minimal delay (in milliseconds) between queueing and executing a mint.
+    pub fn min_delay(&self) -> u64 {
+        self.min_delay
+    }
+
+    /// Returns the queued mint with `id`, if it has been neither executed nor cancelled.
+    pub fn queued_mint(&self, id: u64) -> Option<QueuedMint> {
+        self.queued.get(id)
+    }
+
+    /// Queues a mint of `value` tokens to `to`, executable from the `execute_after`
+    /// block timestamp on, and returns its id. Ids start from 1.
+    ///
+    /// Returns `Custom (delay below min_delay)` error if `execute_after` is earlier than
+    /// `min_delay` after the current block timestamp.
+    pub fn queue_mint(
+        &mut self,
+        to: AccountId,
+        value: u128,
+        execute_after: u64,
+    ) -> Result<(u64, Vec<PSP22Event>), PSP22Error> {
+        let now = ink::env::block_timestamp::<DefaultEnvironment>();
+        if execute_after < now.saturating_add(self.min_delay) {
+            return Err(PSP22Error::custom("delay below min_delay"));
+        }
+        // Ids cannot realistically reach u64.MAX
+        let id = self.last_id.saturating_add(1);
+        self.last_id = id;
+        let mint = QueuedMint {
+            to,
+            value,
+            execute_after,
+        };
+        self.queued.insert(id, &mint);
+        Ok((
+            id,
+            vec![PSP22Event::MintQueued(MintQueued {
+                id,
+                to,
+                value,
+                execute_after,
+            })],
+        ))
+    }
+
+    /// Executes the queued mint with `id` in `data`.
+    ///
+    /// Returns `Custom (unknown queued mint)` error if there is no such mint (also if it has
+    /// already been executed or cancelled) and `Custom (timelock not expired)` error if the
+    /// current block timestamp is lower than its `execute_after`.
+    pub fn execute_mint(
+        &mut self,
+        data: &mut PSP22Data,
+        id: u64,
+    ) -> Result<Vec<PSP22Event>, PSP22Error> {
+        let mint = self.get(id)?;
+        if ink::env::block_timestamp::<DefaultEnvironment>() < mint.execute_after {
+            return Err(PSP22Error::custom("timelock not expired"));
+        }
+        let events = data.mint(mint.to, mint.value)?;
+        self.queued.remove(id);
+        Ok(events)
+    }
+
+    /// Cancels the queued mint with `id`.
+    ///
+    /// Returns `Custom (unknown queued mint)` error if there is no such mint.
+    pub fn cancel_mint(&mut self, id: u64) -> Result<Vec<PSP22Event>, PSP22Error> {
+        self.get(id)?;
+        self.queued.remove(id);
+        Ok(vec![PSP22Event::MintCancelled(MintCancelled { id })])
+    }
+
+    fn get(&self, id: u64) -> Result<QueuedMint, PSP22Error> {
+        self.queued_mint(id)
+            .ok_or(PSP22Error::custom("unknown queued mint"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink::env::test::{default_accounts, set_block_timestamp};
+
+    type E = DefaultEnvironment;
+
+    #[ink::test]
+    fn early_execution_is_rejected() {
+        let acc = default_accounts::<E>();
+        let (mut data, _) = PSP22Data::new(1000, acc.alice);
+        let mut timelock = PSP22TimelockData::default();
+        set_block_timestamp::<E>(1_000);
+
+        let (id, events) = timelock.queue_mint(acc.bob, 100, 2_000).unwrap();
+        assert_eq!(id, 1);
+        assert!(matches!(
+            &events[..],
+            [PSP22Event::MintQueued(e)]
+                if e.id == 1 && e.to == acc.bob && e.value == 100 && e.execute_after == 2_000
+        ));
+        set_block_timestamp::<E>(1_999);
+        assert_eq!(
+            timelock.execute_mint(&mut data, id).err(),
+            Some(PSP22Error::custom("timelock not expired"))
+        );
+        assert_eq!(data.total_supply(), 1000);
+        assert!(timelock.queued_mint(id).is_some());
+    }
+
+    #[ink::test]
+    fn mint_cannot_be_queued_before_min_delay() {
+        let acc = default_accounts::<E>();
+        let (mut data, _) = PSP22Data::new(1000, acc.alice);
+        let mut timelock = PSP22TimelockData::new(500);
+        assert_eq!(timelock.min_delay(), 500);
+        set_block_timestamp::<E>(1_000);
+
+        for execute_after in [0, 1_000, 1_499] {
+            assert_eq!(
+                timelock.queue_mint(acc.bob, 100, execute_after).err(),
+                Some(PSP22Error::custom("delay below min_delay"))
+            );
+        }
+        let (id, _) = timelock.queue_mint(acc.bob, 100, 1_500).unwrap();
+        assert_eq!(id, 1);
+        assert_eq!(
+            timelock.execute_mint(&mut data, id).err(),
+            Some(PSP22Error::custom("timelock not expired"))
+        );
+        set_block_timestamp::<E>(1_500);
+        assert!(timelock.execute_mint(&mut data, id).is_ok());
+        assert_eq!(data.balance_of(acc.bob), 100);
+    }
+
+    #[ink::test]
+    fn mint_is_executed_on_time_once() {
+        let acc = default_accounts::<E>();
+        let (mut data, _) = PSP22Data::new(1000, acc.alice);
+        let mut timelock = PSP22TimelockData::default();
+        set_block_timestamp::<E>(1_000);
+        let (first, _) = timelock.queue_mint(acc.bob, 100, 2_000).unwrap();
+        let (second, _) = timelock.queue_mint(acc.charlie, 200, 1_500).unwrap();
+        assert_eq!(second, 2);
+
+        set_block_timestamp::<E>(2_000);
+        let events = timelock.execute_mint(&mut data, first).unwrap();
+        assert!(matches!(
+            &events[..],
+            [PSP22Event::Minted { to, value: 100 }] if *to == acc.bob
+        ));
+        assert_eq!(
+            timelock.execute_mint(&mut data, first).err(),
+            Some(PSP22Error::custom("unknown queued mint"))
+        );
+        assert!(timelock.execute_mint(&mut data, second).is_ok());
+        assert_eq!(data.balance_of(acc.bob), 100);
+        assert_eq!(data.balance_of(acc.charlie), 200);
+        assert_eq!(data.total_supply(), 1300);
+    }
+
+    #[ink::test]
+    fn cancelled_mint_cannot_be_executed() {
+        let acc = default_accounts::<E>();
+        let (mut data, _) = PSP22Data::new(1000, acc.alice);
+        let mut timelock = PSP22TimelockData::default();
+        set_block_timestamp::<E>(1_000);
+        let (id, _) = timelock.queue_mint(acc.bob, 100, 2_000).unwrap();
+
+        let events = timelock.cancel_mint(id).unwrap();
+        assert!(matches!(&events[..], [PSP22Event::MintCancelled(e)] if e.id == id));
+        assert_eq!(
+            timelock.cancel_mint(id).err(),
+            Some(PSP22Error::custom("unknown queued mint"))
+        );
+        set_block_timestamp::<E>(2_000);
+        assert_eq!(
+            timelock.execute_mint(&mut data, id).err(),
+            Some(PSP22Error::custom("unknown queued mint"))
+        );
+        assert_eq!(data.total_supply(), 1000);
+    }
+}
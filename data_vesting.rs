@@ -0,0 +1,286 @@
+use crate::data::{PSP22Data, PSP22Event};
+use crate::errors::PSP22Error;
+use crate::events::{TokensReleased, VestingScheduleCreated};
+use ink::{
+    env::DefaultEnvironment,
+    prelude::{vec, vec::Vec},
+    primitives::AccountId,
+    storage::Mapping,
+};
+
+/// Vesting schedule of a single beneficiary.
+///
+/// All times are block timestamps (in milliseconds). Tokens vest linearly from `start`
+/// to `start + duration`, but nothing can be released before `start + cliff`.
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct VestingSchedule {
+    /// Timestamp at which vesting starts.
+    pub start: u64,
+    /// Time after `start` before which no tokens are vested.
+    pub cliff: u64,
+    /// Time after `start` at which all tokens are vested.
+    pub duration: u64,
+    /// Total amount of tokens vested by the schedule.
+    pub total: u128,
+    /// Amount of tokens already released to the beneficiary.
+    pub released: u128,
+}
+
+impl VestingSchedule {
+    /// Returns the amount of tokens vested at `timestamp`, including the released ones.
+    pub fn vested_at(&self, timestamp: u64) -> u128 {
+        let elapsed = timestamp.saturating_sub(self.start);
+        if elapsed < self.cliff {
+            return 0;
+        }
+        if elapsed >= self.duration {
+            return self.total;
+        }
+        // total * elapsed / duration, without overflowing
+        let (elapsed, duration) = (u128::from(elapsed), u128::from(self.duration));
+        self.total / duration * elapsed + self.total % duration * elapsed / duration
+    }
+}
+
+/// A class implementing the internal logic of the PSP22Vesting extension.
+///
+/// Each beneficiary can have a single vesting schedule. Vested tokens are not held
+/// by the contract beforehand: they are minted to the beneficiary when released.
+///
+/// Like other extensions, it has to be wired up by the contract, which is also
+/// responsible for restricting who can create schedules.
+#[ink::storage_item]
+#[derive(Debug, Default)]
+pub struct PSP22VestingData {
+    schedules: Mapping<AccountId, VestingSchedule>,
+}
+
+impl PSP22VestingData {
+    /// Creates a vesting schedule of `amount` tokens for `beneficiary`.
+    ///
+    /// Returns `Custom` error if `beneficiary` already has a schedule, `duration` is zero,
+    /// `cliff` exceeds `duration` or `start + duration` exceeds `u64::MAX`.
+    pub fn create_vesting_schedule(
+        &mut self,
+        beneficiary: AccountId,
+        start: u64,
+        cliff: u64,
+        duration: u64,
+        amount: u128,
+    ) -> Result<Vec<PSP22Event>, PSP22Error> {
+        if self.schedules.contains(beneficiary) {
+            return Err(PSP22Error::custom("vesting schedule already exists"));
+        }
+        if duration == 0 || cliff > duration || start.checked_add(duration).is_none() {
+            return Err(PSP22Error::custom("invalid vesting schedule"));
+        }
+        let schedule = VestingSchedule {
+            start,
+            cliff,
+            duration,
+            total: amount,
+            released: 0,
+        };
+        self.schedules.insert(beneficiary, &schedule);
+        Ok(vec![PSP22Event::VestingScheduleCreated(
+            VestingScheduleCreated {
+                beneficiary,
+                start,
+                cliff,
+                duration,
+                amount,
+            },
+        )])
+    }
+
+    /// Returns the vesting schedule of `beneficiary`, if any.
+    pub fn schedule(&self, beneficiary: AccountId) -> Option<VestingSchedule> {
+        self.schedules.get(beneficiary)
+    }
+
+    /// Returns the amount of tokens vested for `beneficiary` at the current block,
+    /// including the released ones (`0` if it has no schedule).
+    pub fn vested_amount(&self, beneficiary: AccountId) -> u128 {
+        self.schedule(beneficiary)
+            .map(|schedule| schedule.vested_at(now()))
+            .unwrap_or_default()
+    }
+
+    /// Mints the vested, but not yet released tokens of `beneficiary` in `data`.
+    ///
+    /// Returns `ZeroAmount` error if there are no such tokens.
+    pub fn release(
+        &mut self,
+        data: &mut PSP22Data,
+        beneficiary: AccountId,
+    ) -> Result<Vec<PSP22Event>, PSP22Error> {
+        let mut schedule = self.schedule(beneficiary).unwrap_or_default();
+        let amount = schedule.vested_at(now()).saturating_sub(schedule.released);
+        if amount == 0 {
+            return Err(PSP22Error::ZeroAmount);
+        }
+        let mut events = data.mint(beneficiary, amount)?;
+        // Cannot exceed the total of the schedule
+        schedule.released = schedule.released.saturating_add(amount);
+        self.schedules.insert(beneficiary, &schedule);
+        events.push(PSP22Event::TokensReleased(TokensReleased {
+            beneficiary,
+            amount,
+        }));
+        Ok(events)
+    }
+}
+
+fn now() -> u64 {
+    ink::env::block_timestamp::<DefaultEnvironment>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink::env::test::{default_accounts, set_block_timestamp};
+
+    type E = DefaultEnvironment;
+
+    #[test]
+    fn vesting_is_linear_after_cliff() {
+        let schedule = VestingSchedule {
+            start: 1_000,
+            cliff: 100,
+            duration: 400,
+            total: 1000,
+            released: 0,
+        };
+        let cases = [
+            (0, 0),
+            (1_000, 0),
+            (1_099, 0),
+            (1_100, 250),
+            (1_200, 500),
+            (1_399, 997),
+            (1_400, 1000),
+            (u64::MAX, 1000),
+        ];
+        for (timestamp, vested) in cases {
+            assert_eq!(schedule.vested_at(timestamp), vested, "{timestamp}");
+        }
+    }
+
+    #[test]
+    fn vesting_of_max_amount_does_not_overflow() {
+        let schedule = VestingSchedule {
+            start: 0,
+            cliff: 0,
+            duration: u64::MAX,
+            total: u128::MAX,
+            released: 0,
+        };
+        // u128::MAX == u64::MAX * (u64::MAX + 2)
+        let half = u64::MAX / 2;
+        assert_eq!(
+            schedule.vested_at(half),
+            u128::from(half) * (u128::from(u64::MAX) + 2)
+        );
+        assert_eq!(schedule.vested_at(u64::MAX), u128::MAX);
+    }
+
+    #[ink::test]
+    fn invalid_schedules_are_rejected() {
+        let acc = default_accounts::<E>();
+        let mut vesting = PSP22VestingData::default();
+
+        for (start, cliff, duration) in [(0, 0, 0), (0, 200, 100), (u64::MAX, 0, 1)] {
+            assert_eq!(
+                vesting
+                    .create_vesting_schedule(acc.bob, start, cliff, duration, 100)
+                    .err(),
+                Some(PSP22Error::custom("invalid vesting schedule"))
+            );
+        }
+        assert!(vesting
+            .create_vesting_schedule(acc.bob, 0, 0, 100, 100)
+            .is_ok());
+        assert_eq!(
+            vesting
+                .create_vesting_schedule(acc.bob, 0, 0, 100, 100)
+                .err(),
+            Some(PSP22Error::custom("vesting schedule already exists"))
+        );
+    }
+
+    #[ink::test]
+    fn release_mints_vested_tokens() {
+        let acc = default_accounts::<E>();
+        let (mut data, _) = PSP22Data::new(1000, acc.alice);
+        let mut vesting = PSP22VestingData::default();
+        set_block_timestamp::<E>(1_000);
+
+        let events = vesting
+            .create_vesting_schedule(acc.bob, 1_000, 100, 400, 800)
+            .unwrap();
+        assert!(matches!(
+            &events[..],
+            [PSP22Event::VestingScheduleCreated(e)] if e.beneficiary == acc.bob
+                && e.start == 1_000 && e.cliff == 100 && e.duration == 400 && e.amount == 800
+        ));
+        assert_eq!(
+            vesting.release(&mut data, acc.bob).err(),
+            Some(PSP22Error::ZeroAmount)
+        );
+
+        set_block_timestamp::<E>(1_200);
+        assert_eq!(vesting.vested_amount(acc.bob), 400);
+        let events = vesting.release(&mut data, acc.bob).unwrap();
+        assert!(matches!(
+            &events[..],
+            [PSP22Event::Minted { to, value: 400 }, PSP22Event::TokensReleased(e)]
+                if *to == acc.bob && e.beneficiary == acc.bob && e.amount == 400
+        ));
+        assert_eq!(
+            vesting.release(&mut data, acc.bob).err(),
+            Some(PSP22Error::ZeroAmount)
+        );
+
+        set_block_timestamp::<E>(2_000);
+        assert!(vesting.release(&mut data, acc.bob).is_ok());
+        assert_eq!(data.balance_of(acc.bob), 800);
+        assert_eq!(data.total_supply(), 1800);
+        assert_eq!(vesting.schedule(acc.bob).map(|s| s.released), Some(800));
+        assert_eq!(
+            vesting.release(&mut data, acc.bob).err(),
+            Some(PSP22Error::ZeroAmount)
+        );
+        // No schedule at all
+        assert_eq!(vesting.vested_amount(acc.charlie), 0);
+        assert_eq!(
+            vesting.release(&mut data, acc.charlie).err(),
+            Some(PSP22Error::ZeroAmount)
+        );
+    }
+
+    #[ink::test]
+    fn release_emits_transfer_then_tokens_released() {
+        let acc = default_accounts::<E>();
+        let (mut data, _) = PSP22Data::new(1000, acc.alice);
+        let mut vesting = PSP22VestingData::default();
+        set_block_timestamp::<E>(0);
+        assert!(vesting
+            .create_vesting_schedule(acc.bob, 0, 0, 100, 500)
+            .is_ok());
+
+        set_block_timestamp::<E>(100);
+        let events = vesting.release(&mut data, acc.bob).unwrap();
+        assert_eq!(events.len(), 2);
+        let transfer = events[0].as_transfer().expect("Event is not a transfer");
+        assert_eq!(
+            (transfer.from, transfer.to, transfer.value),
+            (None, Some(acc.bob), 500)
+        );
+        assert!(matches!(
+            &events[1],
+            PSP22Event::TokensReleased(e) if e.beneficiary == acc.bob && e.amount == 500
+        ));
+    }
+}
@@ -0,0 +1,346 @@
+use crate::clock::Clock;
+use crate::data::{PSP22Data, PSP22Event};
+use crate::errors::PSP22Error;
+use crate::events::{DelegateChanged, DelegateVotesChanged};
+use ink::{
+    prelude::{vec, vec::Vec},
+    primitives::AccountId,
+    storage::Mapping,
+};
+
+/// A class implementing the internal logic of the PSP22Votes extension.
+///
+/// Every account delegates its voting power, equal to its balance, to a delegatee.
+/// An account which has never delegated is its own delegatee. The voting power of
+/// every delegatee is checkpointed at each change, keyed by the timepoint of the `Clock`
+/// selected at construction (block numbers by default), so that votes can be tallied
+/// at any past timepoint.
+///
+/// The contract must call `update` after every successful operation on `PSP22Data`
+/// which changes balances (including minting in the constructor).
+#[ink::storage_item]
+#[derive(Debug, Default)]
+pub struct PSP22VotesData {
+    clock: Clock,
+    delegates: Mapping<AccountId, AccountId>,
+    checkpoints: Mapping<(AccountId, u32), (u64, u128)>,
+    checkpoint_count: Mapping<AccountId, u32>,
+}
+
+impl PSP22VotesData {
+    /// Creates an empty votes registry using the given `clock`.
+    pub fn new(clock: Clock) -> PSP22VotesData {
+        PSP22VotesData {
+            clock,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the current timepoint of the configured clock.
+    pub fn clock(&self) -> u64 {
+        self.clock.clock()
+    }
+
+    /// Returns the `CLOCK_MODE` string describing the configured clock.
+    pub fn clock_mode(&self) -> &'static str {
+        self.clock.clock_mode()
+    }
+
+    /// Returns the delegatee of `account`.
+    pub fn delegates(&self, account: AccountId) -> AccountId {
+        self.delegates.get(account).unwrap_or(account)
+    }
+
+    /// Returns the current voting power of `account`.
+    pub fn get_votes(&self, account: AccountId) -> u128 {
+        match self.checkpoint_count.get(account).unwrap_or_default() {
+            0 => 0,
+            count => self.checkpoint(account, count - 1).1,
+        }
+    }
+
+    /// Returns the current voting power of each of `accounts`, in the same order.
+    pub fn get_votes_batch(&self, accounts: &[AccountId]) -> Vec<u128> {
+        accounts
+            .iter()
+            .map(|account| self.get_votes(*account))
+            .collect()
+    }
+
+    /// Returns the voting power of `account` at the end of `timepoint`.
+    ///
+    /// Returns `Custom` error if `timepoint` is the current one or a future one,
+    /// as its final voting power is not known yet.
+    pub fn get_past_votes(&self, account: AccountId, timepoint: u64) -> Result<u128, PSP22Error> {
+        if timepoint >= self.clock() {
+            return Err(PSP22Error::custom("block not yet mined"));
+        }
+        // Binary search for the first checkpoint after `timepoint`
+        let (mut low, mut high) = (0, self.checkpoint_count.get(account).unwrap_or_default());
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if self.checkpoint(account, mid).0 <= timepoint {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+        Ok(match low {
+            0 => 0,
+            _ => self.checkpoint(account, low - 1).1,
+        })
+    }
+
+    /// Changes the delegatee of `delegator` to `delegatee`, moving the voting power
+    /// of `delegator` (its balance in `data`) from the previous delegatee.
+    pub fn delegate(
+        &mut self,
+        data: &PSP22Data,
+        delegator: AccountId,
+        delegatee: AccountId,
+    ) -> Vec<PSP22Event> {
+        let from_delegate = self.delegates(delegator);
+        self.delegates.insert(delegator, &delegatee);
+        let mut events = vec![PSP22Event::DelegateChanged(DelegateChanged {
+            delegator,
+            from_delegate,
+            to_delegate: delegatee,
+        })];
+        events.extend(self._move_voting_power(
+            Some(from_delegate),
+            Some(delegatee),
+            data.balance_of(delegator),
+        ));
+        events
+    }
+
+    /// Moves `value` of voting power from the delegatee of `from` to the delegatee of `to`.
+    /// `None` stands for minting (`from`) or burning (`to`).
+    ///
+    /// Should be called right after the corresponding successful operation on `PSP22Data`.
+    pub fn update(
+        &mut self,
+        from: Option<AccountId>,
+        to: Option<AccountId>,
+        value: u128,
+    ) -> Vec<PSP22Event> {
+        self._move_voting_power(
+            from.map(|account| self.delegates(account)),
+            to.map(|account| self.delegates(account)),
+            value,
+        )
+    }
+
+    fn _move_voting_power(
+        &mut self,
+        from: Option<AccountId>,
+        to: Option<AccountId>,
+        value: u128,
+    ) -> Vec<PSP22Event> {
+        let mut events = Vec::new();
+        if from == to || value == 0 {
+            return events;
+        }
+        if let Some(from) = from {
+            let previous_votes = self.get_votes(from);
+            // Voting power of a delegatee is a sum of balances, so it is at least `value`
+            let new_votes = previous_votes.saturating_sub(value);
+            self._write_checkpoint(from, new_votes);
+            events.push(PSP22Event::DelegateVotesChanged(DelegateVotesChanged {
+                delegate: from,
+                previous_votes,
+                new_votes,
+            }));
+        }
+        if let Some(to) = to {
+            let previous_votes = self.get_votes(to);
+            // Voting power of a delegatee cannot exceed the total supply
+            let new_votes = previous_votes.saturating_add(value);
+            self._write_checkpoint(to, new_votes);
+            events.push(PSP22Event::DelegateVotesChanged(DelegateVotesChanged {
+                delegate: to,
+                previous_votes,
+                new_votes,
+            }));
+        }
+        events
+    }
+
+    // Records `votes` as the voting power of `account` at the current timepoint.
+    // Overwrites the last checkpoint if it was recorded at the same timepoint.
+    fn _write_checkpoint(&mut self, account: AccountId, votes: u128) {
+        let now = self.clock();
+        let count = self.checkpoint_count.get(account).unwrap_or_default();
+        if count > 0 && self.checkpoint(account, count - 1).0 == now {
+            self.checkpoints.insert((account, count - 1), &(now, votes));
+        } else {
+            self.checkpoints.insert((account, count), &(now, votes));
+            self.checkpoint_count
+                .insert(account, &(count.saturating_add(1)));
+        }
+    }
+
+    fn checkpoint(&self, account: AccountId, index: u32) -> (u64, u128) {
+        self.checkpoints.get((account, index)).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink::env::{
+        test::{advance_block, default_accounts},
+        DefaultEnvironment,
+    };
+
+    type E = DefaultEnvironment;
+
+    fn setup(supply: u128, owner: AccountId) -> (PSP22Data, PSP22VotesData) {
+        let (data, _) = PSP22Data::new(supply, owner);
+        let mut votes = PSP22VotesData::default();
+        votes.update(None, Some(owner), supply);
+        (data, votes)
+    }
+
+    fn transfer(
+        data: &mut PSP22Data,
+        votes: &mut PSP22VotesData,
+        from: AccountId,
+        to: AccountId,
+        value: u128,
+    ) -> Vec<PSP22Event> {
+        assert!(data.transfer(from, to, value).is_ok());
+        votes.update(Some(from), Some(to), value)
+    }
+
+    #[ink::test]
+    fn accounts_delegate_to_themselves_by_default() {
+        let acc = default_accounts::<E>();
+        let (mut data, mut votes) = setup(1000, acc.alice);
+        transfer(&mut data, &mut votes, acc.alice, acc.bob, 100);
+
+        assert_eq!(votes.delegates(acc.alice), acc.alice);
+        assert_eq!(votes.get_votes(acc.alice), 900);
+        assert_eq!(votes.get_votes(acc.bob), 100);
+        assert_eq!(votes.get_votes(acc.charlie), 0);
+    }
+
+    #[ink::test]
+    fn delegate_moves_voting_power() {
+        let acc = default_accounts::<E>();
+        let (mut data, mut votes) = setup(1000, acc.alice);
+
+        let events = votes.delegate(&data, acc.alice, acc.bob);
+        assert_eq!(votes.delegates(acc.alice), acc.bob);
+        assert_eq!(votes.get_votes(acc.alice), 0);
+        assert_eq!(votes.get_votes(acc.bob), 1000);
+        assert_eq!(events.len(), 3);
+        assert!(matches!(
+            &events[0],
+            PSP22Event::DelegateChanged(e) if e.delegator == acc.alice
+                && e.from_delegate == acc.alice && e.to_delegate == acc.bob
+        ));
+
+        transfer(&mut data, &mut votes, acc.alice, acc.charlie, 300);
+        assert_eq!(votes.get_votes(acc.bob), 700);
+        assert_eq!(votes.get_votes(acc.charlie), 300);
+    }
+
+    #[ink::test]
+    fn delegating_to_new_account_emits_both_events() {
+        let acc = default_accounts::<E>();
+        let (data, mut votes) = setup(1000, acc.alice);
+
+        let events = votes.delegate(&data, acc.alice, acc.eve);
+        assert!(matches!(
+            &events[..],
+            [
+                PSP22Event::DelegateChanged(changed),
+                PSP22Event::DelegateVotesChanged(from),
+                PSP22Event::DelegateVotesChanged(to),
+            ] if changed.delegator == acc.alice
+                && changed.from_delegate == acc.alice
+                && changed.to_delegate == acc.eve
+                && (from.delegate, from.previous_votes, from.new_votes) == (acc.alice, 1000, 0)
+                && (to.delegate, to.previous_votes, to.new_votes) == (acc.eve, 0, 1000)
+        ));
+    }
+
+    #[ink::test]
+    fn transfer_after_delegation_emits_votes_changed_for_both_delegatees() {
+        let acc = default_accounts::<E>();
+        let (mut data, mut votes) = setup(1000, acc.alice);
+        votes.delegate(&data, acc.alice, acc.charlie);
+        votes.delegate(&data, acc.bob, acc.django);
+
+        let events = transfer(&mut data, &mut votes, acc.alice, acc.bob, 300);
+
+        assert_eq!(events.len(), 2);
+        assert!(matches!(
+            &events[0],
+            PSP22Event::DelegateVotesChanged(e) if e.delegate == acc.charlie
+                && e.previous_votes == 1000 && e.new_votes == 700
+        ));
+        assert!(matches!(
+            &events[1],
+            PSP22Event::DelegateVotesChanged(e) if e.delegate == acc.django
+                && e.previous_votes == 0 && e.new_votes == 300
+        ));
+    }
+
+    #[ink::test]
+    fn batch_votes_reflect_delegation() {
+        let acc = default_accounts::<E>();
+        let (mut data, mut votes) = setup(1000, acc.alice);
+        transfer(&mut data, &mut votes, acc.alice, acc.bob, 300);
+        transfer(&mut data, &mut votes, acc.alice, acc.charlie, 200);
+        // alice delegates to herself explicitly, bob to django, charlie never delegates
+        votes.delegate(&data, acc.alice, acc.alice);
+        votes.delegate(&data, acc.bob, acc.django);
+
+        assert_eq!(
+            votes.get_votes_batch(&[acc.alice, acc.bob, acc.charlie, acc.django, acc.eve]),
+            [500, 0, 200, 300, 0]
+        );
+        assert!(votes.get_votes_batch(&[]).is_empty());
+    }
+
+    #[ink::test]
+    fn delegate_with_zero_balance_moves_nothing() {
+        let acc = default_accounts::<E>();
+        let (data, mut votes) = setup(1000, acc.alice);
+
+        let events = votes.delegate(&data, acc.charlie, acc.bob);
+        assert_eq!(votes.delegates(acc.charlie), acc.bob);
+        assert_eq!(events.len(), 1);
+        assert_eq!(votes.get_votes(acc.bob), 0);
+    }
+
+    #[ink::test]
+    fn past_votes_are_found_by_binary_search() {
+        let acc = default_accounts::<E>();
+        let (mut data, mut votes) = setup(1000, acc.alice);
+        // Block 0: alice 1000
+        advance_block::<E>();
+        transfer(&mut data, &mut votes, acc.alice, acc.bob, 100);
+        // Block 1: alice 900
+        advance_block::<E>();
+        advance_block::<E>();
+        transfer(&mut data, &mut votes, acc.alice, acc.bob, 100);
+        transfer(&mut data, &mut votes, acc.alice, acc.bob, 100);
+        // Block 3: alice 700 (a single checkpoint)
+        advance_block::<E>();
+
+        assert_eq!(votes.get_past_votes(acc.alice, 0), Ok(1000));
+        assert_eq!(votes.get_past_votes(acc.alice, 1), Ok(900));
+        assert_eq!(votes.get_past_votes(acc.alice, 2), Ok(900));
+        assert_eq!(votes.get_past_votes(acc.alice, 3), Ok(700));
+        assert_eq!(votes.get_past_votes(acc.bob, 0), Ok(0));
+        assert_eq!(votes.get_past_votes(acc.bob, 3), Ok(300));
+        assert_eq!(votes.checkpoint_count.get(acc.alice), Some(3));
+        assert!(matches!(
+            votes.get_past_votes(acc.alice, 4),
+            Err(PSP22Error::Custom(_))
+        ));
+    }
+}
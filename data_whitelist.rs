@@ -0,0 +1,290 @@
+use crate::data::PSP22Event;
+use crate::errors::PSP22Error;
+use crate::events::{AddedToWhitelist, RemovedFromWhitelist};
+use ink::{
+    prelude::{vec, vec::Vec},
+    primitives::AccountId,
+    storage::Mapping,
+};
+
+/// A class implementing the internal logic of the PSP22Whitelist extension.
+///
+/// While the whitelist is enabled, only whitelisted accounts can hold tokens: tokens can be
+/// transferred only between whitelisted accounts and minted only to whitelisted accounts.
+/// The owner of the contract, passed to the checks by the contract, is exempt.
+/// Accounts can be added to and removed from the whitelist also while it is disabled.
+/// The whitelist is disabled by default.
+///
+/// An account removed from the whitelist may still hold tokens. By default it cannot move
+/// them anymore. With `set_exit_allowed(true)` it can still transfer them out,
+/// but only to whitelisted accounts.
+///
+/// Like other extensions, it has to be wired up by the contract: `check_transfer` should
+/// be called before every `transfer` and `transfer_from` (with the owner of the tokens)
+/// and `check_mint` before every `mint` on `PSP22Data`. Access control of modifying
+/// the whitelist is left to the contract as well.
+#[ink::storage_item]
+#[derive(Debug, Default)]
+pub struct PSP22WhitelistData {
+    whitelist: Mapping<AccountId, bool>,
+    whitelist_enabled: bool,
+    exit_allowed: bool,
+}
+
+impl PSP22WhitelistData {
+    /// Returns `true` if holding tokens is restricted to whitelisted accounts.
+    pub fn is_whitelist_enabled(&self) -> bool {
+        self.whitelist_enabled
+    }
+
+    /// Enables (`enabled == true`) or disables the whitelist.
+    pub fn set_whitelist_enabled(&mut self, enabled: bool) {
+        self.whitelist_enabled = enabled;
+    }
+
+    /// Returns `true` if accounts not whitelisted can transfer tokens to whitelisted accounts.
+    pub fn is_exit_allowed(&self) -> bool {
+        self.exit_allowed
+    }
+
+    /// Allows (`allowed == true`) or forbids accounts not whitelisted (e.g. removed from
+    /// the whitelist while holding tokens) to transfer tokens to whitelisted accounts.
+    pub fn set_exit_allowed(&mut self, allowed: bool) {
+        self.exit_allowed = allowed;
+    }
+
+    /// Returns `true` if `account` is whitelisted.
+    pub fn is_whitelisted(&self, account: AccountId) -> bool {
+        self.whitelist.get(account).unwrap_or_default()
+    }
+
+    /// Adds `account` to the whitelist. Adding an account already whitelisted
+    /// is a no-op (no events).
+    pub fn add_to_whitelist(&mut self, account: AccountId) -> Vec<PSP22Event> {
+        if self.is_whitelisted(account) {
+            return vec![];
+        }
+        self.whitelist.insert(account, &true);
+        vec![PSP22Event::AddedToWhitelist(AddedToWhitelist { account })]
+    }
+
+    /// Removes `account` from the whitelist. Removing an account not whitelisted
+    /// is a no-op (no events).
+    pub fn remove_from_whitelist(&mut self, account: AccountId) -> Vec<PSP22Event> {
+        if !self.is_whitelisted(account) {
+            return vec![];
+        }
+        self.whitelist.remove(account);
+        vec![PSP22Event::RemovedFromWhitelist(RemovedFromWhitelist {
+            account,
+        })]
+    }
+
+    /// Adds all `accounts` to the whitelist, see `add_to_whitelist`.
+    pub fn add_to_whitelist_batch(&mut self, accounts: Vec<AccountId>) -> Vec<PSP22Event> {
+        accounts
+            .into_iter()
+            .flat_map(|account| self.add_to_whitelist(account))
+            .collect()
+    }
+
+    /// Removes all `accounts` from the whitelist, see `remove_from_whitelist`.
+    pub fn remove_from_whitelist_batch(&mut self, accounts: Vec<AccountId>) -> Vec<PSP22Event> {
+        accounts
+            .into_iter()
+            .flat_map(|account| self.remove_from_whitelist(account))
+            .collect()
+    }
+
+    /// Returns `TransferRestricted (not whitelisted)` error if the whitelist is enabled
+    /// and `to` or (unless exit is allowed) `from` is neither whitelisted nor `owner`.
+    pub fn check_transfer(
+        &self,
+        from: AccountId,
+        to: AccountId,
+        owner: Option<AccountId>,
+    ) -> Result<(), PSP22Error> {
+        let exit = self.exit_allowed && self.is_whitelisted(to);
+        if self.whitelist_enabled
+            && !(self.may_hold(to, owner) && (exit || self.may_hold(from, owner)))
+        {
+            return Err(PSP22Error::transfer_restricted("not whitelisted"));
+        }
+        Ok(())
+    }
+
+    /// Returns `TransferRestricted (not whitelisted)` error if the whitelist is enabled
+    /// and `to` is neither whitelisted nor `owner`.
+    pub fn check_mint(&self, to: AccountId, owner: Option<AccountId>) -> Result<(), PSP22Error> {
+        if self.whitelist_enabled && !self.may_hold(to, owner) {
+            return Err(PSP22Error::transfer_restricted("not whitelisted"));
+        }
+        Ok(())
+    }
+
+    fn may_hold(&self, account: AccountId, owner: Option<AccountId>) -> bool {
+        owner == Some(account) || self.is_whitelisted(account)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::PSP22Data;
+    use ink::env::{test::default_accounts, DefaultEnvironment as E};
+
+    // Transfers `value` from `from` to `to`, provided that the whitelist allows it.
+    fn transfer(
+        data: &mut PSP22Data,
+        whitelist: &PSP22WhitelistData,
+        from: AccountId,
+        to: AccountId,
+        value: u128,
+    ) -> Result<(), PSP22Error> {
+        whitelist.check_transfer(from, to, None)?;
+        data.transfer(from, to, value).map(|_| ())
+    }
+
+    #[ink::test]
+    fn disabled_whitelist_does_not_restrict_transfers() {
+        let acc = default_accounts::<E>();
+        let (mut data, _) = PSP22Data::new(1000, acc.alice);
+        let whitelist = PSP22WhitelistData::default();
+
+        assert!(!whitelist.is_whitelist_enabled());
+        assert!(transfer(&mut data, &whitelist, acc.alice, acc.bob, 100).is_ok());
+        assert!(whitelist.check_mint(acc.charlie, None).is_ok());
+        assert_eq!(data.balance_of(acc.bob), 100);
+    }
+
+    #[ink::test]
+    fn enabled_whitelist_checks_sender_and_recipient() {
+        let acc = default_accounts::<E>();
+        let (mut data, _) = PSP22Data::new(1000, acc.alice);
+        let mut whitelist = PSP22WhitelistData::default();
+        whitelist.add_to_whitelist(acc.alice);
+        whitelist.set_whitelist_enabled(true);
+        let restricted = Err(PSP22Error::transfer_restricted("not whitelisted"));
+
+        assert_eq!(
+            transfer(&mut data, &whitelist, acc.alice, acc.bob, 100),
+            restricted
+        );
+        whitelist.add_to_whitelist(acc.bob);
+        assert!(transfer(&mut data, &whitelist, acc.alice, acc.bob, 100).is_ok());
+        whitelist.remove_from_whitelist(acc.alice);
+        assert_eq!(
+            transfer(&mut data, &whitelist, acc.bob, acc.alice, 50),
+            restricted
+        );
+        whitelist.set_whitelist_enabled(false);
+        assert!(transfer(&mut data, &whitelist, acc.bob, acc.charlie, 50).is_ok());
+        assert_eq!(data.balance_of(acc.charlie), 50);
+    }
+
+    #[ink::test]
+    fn mint_recipients_must_be_whitelisted() {
+        let acc = default_accounts::<E>();
+        let mut whitelist = PSP22WhitelistData::default();
+        whitelist.add_to_whitelist(acc.bob);
+        whitelist.set_whitelist_enabled(true);
+
+        assert!(whitelist.check_mint(acc.bob, None).is_ok());
+        assert_eq!(
+            whitelist.check_mint(acc.charlie, None),
+            Err(PSP22Error::transfer_restricted("not whitelisted"))
+        );
+    }
+
+    #[ink::test]
+    fn owner_is_exempt() {
+        let acc = default_accounts::<E>();
+        let mut whitelist = PSP22WhitelistData::default();
+        whitelist.add_to_whitelist(acc.bob);
+        whitelist.set_whitelist_enabled(true);
+        let owner = Some(acc.alice);
+
+        assert!(whitelist.check_mint(acc.alice, owner).is_ok());
+        assert!(whitelist.check_transfer(acc.alice, acc.bob, owner).is_ok());
+        assert!(whitelist.check_transfer(acc.bob, acc.alice, owner).is_ok());
+        // The owner cannot let others hold tokens
+        assert!(whitelist
+            .check_transfer(acc.alice, acc.charlie, owner)
+            .is_err());
+        assert!(whitelist.check_transfer(acc.alice, acc.bob, None).is_err());
+    }
+
+    #[ink::test]
+    fn removed_account_can_exit_only_if_allowed() {
+        let acc = default_accounts::<E>();
+        let (mut data, _) = PSP22Data::new(1000, acc.alice);
+        let mut whitelist = PSP22WhitelistData::default();
+        whitelist.add_to_whitelist_batch(vec![acc.alice, acc.bob, acc.charlie]);
+        whitelist.set_whitelist_enabled(true);
+        assert!(transfer(&mut data, &whitelist, acc.alice, acc.bob, 100).is_ok());
+        whitelist.remove_from_whitelist(acc.bob);
+        let restricted = Err(PSP22Error::transfer_restricted("not whitelisted"));
+
+        assert!(!whitelist.is_exit_allowed());
+        assert_eq!(
+            transfer(&mut data, &whitelist, acc.bob, acc.charlie, 50),
+            restricted
+        );
+
+        whitelist.set_exit_allowed(true);
+        assert!(transfer(&mut data, &whitelist, acc.bob, acc.charlie, 50).is_ok());
+        // Only to whitelisted accounts
+        assert_eq!(
+            transfer(&mut data, &whitelist, acc.bob, acc.django, 50),
+            restricted
+        );
+        // Incoming transfers are still blocked
+        assert_eq!(
+            transfer(&mut data, &whitelist, acc.alice, acc.bob, 50),
+            restricted
+        );
+        assert_eq!(data.balance_of(acc.bob), 50);
+        assert_eq!(data.balance_of(acc.charlie), 50);
+    }
+
+    #[ink::test]
+    fn events_are_emitted_only_on_change() {
+        let acc = default_accounts::<E>();
+        let mut whitelist = PSP22WhitelistData::default();
+
+        assert!(matches!(
+            &whitelist.add_to_whitelist(acc.bob)[..],
+            [PSP22Event::AddedToWhitelist(e)] if e.account == acc.bob
+        ));
+        assert!(whitelist.is_whitelisted(acc.bob));
+        assert!(whitelist.add_to_whitelist(acc.bob).is_empty());
+        assert!(matches!(
+            &whitelist.remove_from_whitelist(acc.bob)[..],
+            [PSP22Event::RemovedFromWhitelist(e)] if e.account == acc.bob
+        ));
+        assert!(!whitelist.is_whitelisted(acc.bob));
+        assert!(whitelist.remove_from_whitelist(acc.bob).is_empty());
+    }
+
+    #[ink::test]
+    fn batches_skip_unchanged_accounts() {
+        let acc = default_accounts::<E>();
+        let mut whitelist = PSP22WhitelistData::default();
+        whitelist.add_to_whitelist(acc.bob);
+
+        let events = whitelist.add_to_whitelist_batch(vec![acc.alice, acc.bob, acc.charlie]);
+        assert!(matches!(
+            &events[..],
+            [PSP22Event::AddedToWhitelist(a), PSP22Event::AddedToWhitelist(c)]
+                if a.account == acc.alice && c.account == acc.charlie
+        ));
+        let events = whitelist.remove_from_whitelist_batch(vec![acc.alice, acc.django]);
+        assert!(matches!(
+            &events[..],
+            [PSP22Event::RemovedFromWhitelist(e)] if e.account == acc.alice
+        ));
+        assert!(!whitelist.is_whitelisted(acc.alice));
+        assert!(whitelist.is_whitelisted(acc.bob));
+        assert!(whitelist.is_whitelisted(acc.charlie));
+    }
+}
@@ -0,0 +1,349 @@
+//! `PSP22WrapperData`, the configuration of the PSP22Wrapper extension and the operations
+//! calling the underlying token.
+//!
+//! Unlike `PSP22Data`, which only touches its own storage, these operations call another
+//! contract and read the address of this one from the environment, so they need a real chain
+//! (or e2e tests) to run. They take `&mut PSP22Data` to mint and burn the wrapped tokens.
+
+use crate::callers::PSP22Contract;
+use crate::data::{PSP22Data, PSP22Event};
+use crate::errors::PSP22Error;
+use crate::events::CallGasLimitUpdated;
+use ink::{
+    env::{DefaultEnvironment, Environment},
+    prelude::{vec, vec::Vec},
+    primitives::AccountId,
+};
+
+/// A class holding the configuration of the PSP22Wrapper extension.
+///
+/// Underlying tokens are moved by `deposit`, `withdraw`, `recover_underlying` and `recover`,
+/// calling the underlying token at the address stored here with the gas limit stored here,
+/// which bounds the gas a malicious underlying token can burn.
+#[ink::storage_item]
+#[derive(Debug)]
+pub struct PSP22WrapperData {
+    underlying: AccountId,
+    call_gas_limit: u64,
+}
+
+impl PSP22WrapperData {
+    /// Creates a wrapper configuration of the `underlying` PSP22 token,
+    /// calling it without a gas limit.
+    pub fn new(underlying: AccountId) -> PSP22WrapperData {
+        PSP22WrapperData {
+            underlying,
+            call_gas_limit: 0,
+        }
+    }
+
+    /// Returns the address of the underlying PSP22 token.
+    pub fn underlying(&self) -> AccountId {
+        self.underlying
+    }
+
+    /// Returns the gas limit of the calls to the underlying token.
+    /// `0` means no limit: the calls can use all the gas left.
+    pub fn call_gas_limit(&self) -> u64 {
+        self.call_gas_limit
+    }
+
+    /// Sets the gas limit of the calls to the underlying token, `0` for no limit.
+    /// Access control is left to the contract.
+    ///
+    /// Returns a `CallGasLimitUpdated` event if the limit changes.
+    pub fn set_call_gas_limit(&mut self, limit: u64) -> Vec<PSP22Event> {
+        let old_limit = self.call_gas_limit;
+        if old_limit == limit {
+            return vec![];
+        }
+        self.call_gas_limit = limit;
+        vec![PSP22Event::CallGasLimitUpdated(CallGasLimitUpdated {
+            old_limit,
+            new_limit: limit,
+        })]
+    }
+
+    /// Returns the underlying token called in the environment `E` with `call_gas_limit`.
+    /// All calls to the underlying token are made through it.
+    pub fn underlying_contract<E: Environment<AccountId = AccountId>>(&self) -> PSP22Contract<E> {
+        PSP22Contract::new_in_env(self.underlying).with_gas_limit(self.call_gas_limit)
+    }
+
+    /// Transfers `value` tokens of the underlying PSP22 token from `caller`
+    /// to this contract and mints `value` tokens of `data` to `account`.
+    ///
+    /// `caller` must have approved this contract to spend `value` underlying tokens.
+    ///
+    /// The call to the underlying token is made in `DefaultEnvironment`,
+    /// see `deposit_in_env` for chains with a custom environment.
+    pub fn deposit(
+        &self,
+        data: &mut PSP22Data,
+        caller: AccountId,
+        account: AccountId,
+        value: u128,
+    ) -> Result<Vec<PSP22Event>, PSP22Error> {
+        self.deposit_in_env::<DefaultEnvironment>(data, caller, account, value)
+    }
+
+    /// Same as `deposit`, with the call to the underlying token made in the environment `E`.
+    ///
+    /// `E` must use the same `AccountId` as `PSP22Data`. Amounts of tokens are `u128`
+    /// regardless of `E::Balance`, which is the type of native balances only.
+    pub fn deposit_in_env<E: Environment<AccountId = AccountId>>(
+        &self,
+        data: &mut PSP22Data,
+        caller: AccountId,
+        account: AccountId,
+        value: u128,
+    ) -> Result<Vec<PSP22Event>, PSP22Error> {
+        // Checked before calling the underlying token, minting would fail anyway
+        if data.is_paused() {
+            return Err(PSP22Error::Paused);
+        }
+        if value == 0 {
+            return Ok(vec![]);
+        }
+        let contract = ink::env::account_id::<E>();
+        self.underlying_contract::<E>()
+            .transfer_from(caller, contract, value, Vec::new())?;
+        data.mint(account, value)
+    }
+
+    /// Burns `value` tokens of `data` from `caller` and transfers `value` tokens
+    /// of the underlying PSP22 token from this contract to `account`.
+    ///
+    /// The call to the underlying token is made in `DefaultEnvironment`,
+    /// see `withdraw_in_env` for chains with a custom environment.
+    pub fn withdraw(
+        &self,
+        data: &mut PSP22Data,
+        caller: AccountId,
+        account: AccountId,
+        value: u128,
+    ) -> Result<Vec<PSP22Event>, PSP22Error> {
+        self.withdraw_in_env::<DefaultEnvironment>(data, caller, account, value)
+    }
+
+    /// Same as `withdraw`, with the call to the underlying token made in the environment `E`,
+    /// see `deposit_in_env`.
+    pub fn withdraw_in_env<E: Environment<AccountId = AccountId>>(
+        &self,
+        data: &mut PSP22Data,
+        caller: AccountId,
+        account: AccountId,
+        value: u128,
+    ) -> Result<Vec<PSP22Event>, PSP22Error> {
+        let events = data.burn(caller, value)?;
+        if value > 0 {
+            self.underlying_contract::<E>()
+                .transfer(account, value, Vec::new())?;
+        }
+        Ok(events)
+    }
+
+    /// Transfers the surplus of the underlying PSP22 token held by this contract over
+    /// the total supply of `data` to `to`. Returns the transferred amount.
+    pub fn recover_underlying(&self, data: &PSP22Data, to: AccountId) -> Result<u128, PSP22Error> {
+        let surplus = self.underlying_surplus(data)?;
+        if surplus > 0 {
+            self.underlying_contract::<DefaultEnvironment>()
+                .transfer(to, surplus, Vec::new())?;
+        }
+        Ok(surplus)
+    }
+
+    /// Mints the surplus of the underlying PSP22 token held by this contract over
+    /// the total supply of `data` to `to`, like OpenZeppelin's `ERC20Wrapper._recover`.
+    /// Returns the minted amount.
+    ///
+    /// Unlike `recover_underlying`, the surplus stays in the contract and backs the minted tokens.
+    pub fn recover(
+        &self,
+        data: &mut PSP22Data,
+        to: AccountId,
+    ) -> Result<(u128, Vec<PSP22Event>), PSP22Error> {
+        let surplus = self.underlying_surplus(data)?;
+        let events = data.mint(to, surplus)?;
+        Ok((surplus, events))
+    }
+
+    // Returns the amount of the underlying PSP22 token held by this contract
+    // over the total supply of `data`.
+    fn underlying_surplus(&self, data: &PSP22Data) -> Result<u128, PSP22Error> {
+        let contract = ink::env::account_id::<DefaultEnvironment>();
+        let held = self
+            .underlying_contract::<DefaultEnvironment>()
+            .balance_of(contract)?;
+        Ok(held.saturating_sub(data.total_supply()))
+    }
+}
+
+// The wrapper operations used to be methods of `PSP22Data`, kept for one release.
+impl PSP22Data {
+    #[deprecated(note = "use `PSP22WrapperData::deposit` instead")]
+    pub fn deposit(
+        &mut self,
+        wrapper: &PSP22WrapperData,
+        caller: AccountId,
+        account: AccountId,
+        value: u128,
+    ) -> Result<Vec<PSP22Event>, PSP22Error> {
+        wrapper.deposit(self, caller, account, value)
+    }
+
+    #[deprecated(note = "use `PSP22WrapperData::withdraw` instead")]
+    pub fn withdraw(
+        &mut self,
+        wrapper: &PSP22WrapperData,
+        caller: AccountId,
+        account: AccountId,
+        value: u128,
+    ) -> Result<Vec<PSP22Event>, PSP22Error> {
+        wrapper.withdraw(self, caller, account, value)
+    }
+
+    #[deprecated(note = "use `PSP22WrapperData::recover_underlying` instead")]
+    pub fn recover_underlying(
+        &self,
+        wrapper: &PSP22WrapperData,
+        to: AccountId,
+    ) -> Result<u128, PSP22Error> {
+        wrapper.recover_underlying(self, to)
+    }
+
+    #[deprecated(note = "use `PSP22WrapperData::recover` instead")]
+    pub fn recover(
+        &mut self,
+        wrapper: &PSP22WrapperData,
+        to: AccountId,
+    ) -> Result<(u128, Vec<PSP22Event>), PSP22Error> {
+        wrapper.recover(self, to)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink::env::DefaultEnvironment as E;
+
+    #[test]
+    fn underlying_is_the_configured_address() {
+        let underlying = AccountId::from([7; 32]);
+        let wrapper = PSP22WrapperData::new(underlying);
+
+        assert_eq!(wrapper.underlying(), underlying);
+    }
+
+    #[test]
+    fn call_gas_limit_is_stored() {
+        let mut wrapper = PSP22WrapperData::new(AccountId::from([7; 32]));
+        assert_eq!(wrapper.call_gas_limit(), 0);
+
+        let events = wrapper.set_call_gas_limit(5_000_000_000);
+        assert!(matches!(
+            &events[..],
+            [PSP22Event::CallGasLimitUpdated(e)] if e.old_limit == 0 && e.new_limit == 5_000_000_000
+        ));
+        assert_eq!(wrapper.call_gas_limit(), 5_000_000_000);
+        // Unchanged
+        assert!(wrapper.set_call_gas_limit(5_000_000_000).is_empty());
+        let events = wrapper.set_call_gas_limit(0);
+        assert!(matches!(
+            &events[..],
+            [PSP22Event::CallGasLimitUpdated(e)] if e.old_limit == 5_000_000_000 && e.new_limit == 0
+        ));
+        assert_eq!(wrapper.call_gas_limit(), 0);
+    }
+
+    #[test]
+    fn call_gas_limit_is_applied_to_calls() {
+        let underlying = AccountId::from([7; 32]);
+        let mut wrapper = PSP22WrapperData::new(underlying);
+
+        let contract = wrapper.underlying_contract::<E>();
+        assert_eq!(contract.address(), underlying);
+        assert_eq!(contract.gas_limit(), 0);
+        assert!(!contract.is_lenient());
+
+        wrapper.set_call_gas_limit(5_000_000_000);
+        assert_eq!(
+            wrapper.underlying_contract::<E>().gas_limit(),
+            5_000_000_000
+        );
+    }
+
+    // An environment of a chain with 64-bit block numbers
+    #[derive(Clone)]
+    enum CustomEnvironment {}
+
+    impl Environment for CustomEnvironment {
+        const MAX_EVENT_TOPICS: usize = <E as Environment>::MAX_EVENT_TOPICS;
+        type AccountId = <E as Environment>::AccountId;
+        type Balance = <E as Environment>::Balance;
+        type Hash = <E as Environment>::Hash;
+        type Timestamp = <E as Environment>::Timestamp;
+        type BlockNumber = u64;
+        type ChainExtension = <E as Environment>::ChainExtension;
+    }
+
+    #[test]
+    fn deposit_and_withdraw_are_generic_over_environment() {
+        type Method = fn(
+            &PSP22WrapperData,
+            &mut PSP22Data,
+            AccountId,
+            AccountId,
+            u128,
+        ) -> Result<Vec<PSP22Event>, PSP22Error>;
+        let _: [Method; 6] = [
+            PSP22WrapperData::deposit,
+            PSP22WrapperData::withdraw,
+            PSP22WrapperData::deposit_in_env::<E>,
+            PSP22WrapperData::withdraw_in_env::<E>,
+            PSP22WrapperData::deposit_in_env::<CustomEnvironment>,
+            PSP22WrapperData::withdraw_in_env::<CustomEnvironment>,
+        ];
+    }
+
+    #[ink::test]
+    fn paused_token_is_not_deposited() {
+        let acc = ink::env::test::default_accounts::<E>();
+        let wrapper = PSP22WrapperData::new(acc.django);
+        let mut data = PSP22Data::default();
+        data.pause();
+
+        // Fails before calling the underlying token
+        assert_eq!(
+            wrapper.deposit(&mut data, acc.alice, acc.alice, 100).err(),
+            Some(PSP22Error::Paused)
+        );
+        // Zero deposits do not call the underlying token
+        data.unpause();
+        assert!(wrapper
+            .deposit(&mut data, acc.alice, acc.alice, 0)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[ink::test]
+    #[allow(deprecated)]
+    fn deprecated_data_methods_forward_to_wrapper() {
+        let acc = ink::env::test::default_accounts::<E>();
+        let wrapper = PSP22WrapperData::new(acc.django);
+        let (mut data, _) = PSP22Data::new(100, acc.alice);
+
+        // Both fail before calling the underlying token
+        assert_eq!(
+            data.withdraw(&wrapper, acc.alice, acc.alice, 101).err(),
+            Some(PSP22Error::InsufficientBalance)
+        );
+        data.pause();
+        assert_eq!(
+            data.deposit(&wrapper, acc.alice, acc.alice, 100).err(),
+            Some(PSP22Error::Paused)
+        );
+        assert_eq!(data.balance_of(acc.alice), 100);
+    }
+}
@@ -0,0 +1,115 @@
+use ink::{
+    env::{
+        hash::{Blake2x256, HashOutput},
+        DefaultEnvironment,
+    },
+    prelude::string::String,
+    primitives::AccountId,
+};
+
+type Hash = <Blake2x256 as HashOutput>::Type;
+
+/// The domain of the signed messages of a contract (permits, meta-transactions etc.),
+/// binding their signatures to the contract, so that they cannot be replayed on other
+/// deployments of the same code, on other chains or by other versions of the contract.
+///
+/// The domain separator is the hash of `(name, version, chain_id, contract address)`.
+/// `chain_id` is chosen by the contract, e.g. a prefix of the genesis hash of the chain,
+/// since the genesis hash is not available to contracts. The separator is computed once
+/// in `new` and recomputed only if the contract is called at another address
+/// (e.g. with a delegate call).
+#[ink::storage_item]
+#[derive(Debug)]
+pub struct DomainData {
+    name: String,
+    version: String,
+    chain_id: u64,
+    contract: AccountId,
+    separator: Hash,
+}
+
+impl DomainData {
+    /// Creates the domain of the current contract.
+    pub fn new(name: String, version: String, chain_id: u64) -> DomainData {
+        let contract = ink::env::account_id::<DefaultEnvironment>();
+        let separator = separator(&name, &version, chain_id, contract);
+        DomainData {
+            name,
+            version,
+            chain_id,
+            contract,
+            separator,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    pub fn chain_id(&self) -> u64 {
+        self.chain_id
+    }
+
+    /// Returns the domain separator, to be included in the payloads of all signed messages.
+    pub fn domain_separator(&self) -> Hash {
+        let contract = ink::env::account_id::<DefaultEnvironment>();
+        if contract == self.contract {
+            return self.separator;
+        }
+        separator(&self.name, &self.version, self.chain_id, contract)
+    }
+}
+
+fn separator(name: &str, version: &str, chain_id: u64, contract: AccountId) -> Hash {
+    let mut output = Hash::default();
+    ink::env::hash_encoded::<Blake2x256, _>(&(name, version, chain_id, contract), &mut output);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink::env::{
+        test::{default_accounts, set_callee},
+        DefaultEnvironment as E,
+    };
+
+    fn domain(name: &str, version: &str, chain_id: u64) -> DomainData {
+        DomainData::new(name.into(), version.into(), chain_id)
+    }
+
+    #[ink::test]
+    fn separator_depends_on_all_fields() {
+        let acc = default_accounts::<E>();
+        set_callee::<E>(acc.django);
+        let separator = domain("Token", "1", 7).domain_separator();
+
+        assert_ne!(domain("Other", "1", 7).domain_separator(), separator);
+        assert_ne!(domain("Token", "2", 7).domain_separator(), separator);
+        assert_ne!(domain("Token", "1", 8).domain_separator(), separator);
+        set_callee::<E>(acc.eve);
+        assert_ne!(domain("Token", "1", 7).domain_separator(), separator);
+    }
+
+    #[ink::test]
+    fn separator_follows_contract_address() {
+        let acc = default_accounts::<E>();
+        set_callee::<E>(acc.django);
+        let domain = domain("Token", "1", 7);
+        let separator = domain.domain_separator();
+
+        set_callee::<E>(acc.eve);
+        let at_eve = domain.domain_separator();
+        assert_ne!(at_eve, separator);
+        assert_eq!(
+            at_eve,
+            DomainData::new("Token".into(), "1".into(), 7).domain_separator()
+        );
+        set_callee::<E>(acc.django);
+        assert_eq!(domain.domain_separator(), separator);
+    }
+}
@@ -1,5 +1,14 @@
-use ink::prelude::string::String;
+use ink::prelude::{
+    format,
+    string::{String, ToString},
+};
 
+/// Errors returned by PSP22 messages.
+///
+/// SCALE indices of the variants (see `PSP22Error::code`) are a part of the public
+/// interface and never change: new variants are only appended and deprecated variants
+/// are kept in place. Integrators that do not want to handle deprecated variants
+/// can convert errors into `PSP22ErrorV2`, which has the same indices.
 #[derive(Debug, PartialEq, Eq)]
 #[ink::scale_derive(Encode, Decode, TypeInfo)]
 pub enum PSP22Error {
@@ -9,10 +18,712 @@ pub enum PSP22Error {
     InsufficientBalance,
     /// Returned if there is not enough allowance to complete the operation.
     InsufficientAllowance,
-    /// Returned if recipient's address is zero [deprecated].
+    /// Returned if recipient's address is zero. Used only if the `zero-address-guard`
+    /// feature is enabled.
     ZeroRecipientAddress,
-    /// Returned if sender's address is zero [deprecated].
+    /// Returned if sender's address is zero.
+    #[deprecated(note = "never returned, the sender is always the caller or an approved owner")]
     ZeroSenderAddress,
-    /// Returned if a safe transfer check failed [deprecated].
+    /// Returned if a safe transfer check failed.
+    #[deprecated(note = "never returned, PSP22 has no safe transfer checks")]
     SafeTransferCheckFailed(String),
+    /// Returned if a signed operation is submitted after its deadline.
+    DeadlineExpired,
+    /// Returned if a signature does not match the signed payload and the signer.
+    InvalidSignature,
+    /// Returned if the provided nonce is not equal to the expected nonce of the account.
+    NonceMismatch,
+    /// Returned if a flash loan failed: the borrower's callback did not succeed
+    /// or the loan was not repaid.
+    FlashLoanFailed(String),
+    /// Returned by `approve_checked` if the current allowance is not equal to the expected one.
+    AllowanceMismatch,
+    /// Returned if the sender's account is time-locked.
+    AccountLocked,
+    /// Returned if the sender's account is frozen by the administrator of the token.
+    AccountFrozen,
+    /// Returned if tokens are transferred, minted or burned while the token is paused.
+    Paused,
+    /// Returned if an allowance would exceed the maximal allowance set for the token.
+    AllowanceCapExceeded,
+    /// Returned if an account claims airdropped tokens for the second time.
+    AlreadyClaimed,
+    /// Returned if a Merkle proof does not match the claimed account and amount.
+    InvalidProof,
+    /// Returned if a transfer is not allowed by the whitelist or the blacklist of the token,
+    /// with the reason (e.g. `not whitelisted`).
+    TransferRestricted(String),
+    /// Returned if increasing an allowance would overflow `u128`.
+    AllowanceOverflow,
+    /// Returned if minting would make the total supply exceed the cap of the token.
+    CapExceeded,
+    /// Returned if a call to another token (e.g. the underlying token of a wrapper) failed
+    /// without returning a `PSP22Error`, e.g. it trapped or ran out of gas.
+    UnderlyingCallFailed,
+    /// Returned by operations which have no tokens to move, e.g. releasing vested tokens
+    /// when none are releasable, where a zero amount is an error rather than a no-op.
+    ZeroAmount,
+    /// Returned if burning would make the total supply fall below the minimum supply of the token.
+    SupplyBelowMinimum,
+    /// Returned if the sender or the recipient of a transfer is blacklisted.
+    Blacklisted,
+}
+
+#[allow(deprecated)]
+impl PSP22Error {
+    /// Shortcut for `Custom` error constructor.
+    pub fn custom(message: &str) -> Self {
+        PSP22Error::Custom(String::from(message))
+    }
+
+    /// Shortcut for `FlashLoanFailed` error constructor.
+    pub fn flash_loan_failed(reason: &str) -> Self {
+        PSP22Error::FlashLoanFailed(String::from(reason))
+    }
+
+    /// Shortcut for `TransferRestricted` error constructor.
+    pub fn transfer_restricted(reason: &str) -> Self {
+        PSP22Error::TransferRestricted(String::from(reason))
+    }
+
+    /// Returns `Custom` error carrying a restriction code of `PSP22Restrictable`
+    /// and its message, e.g. `Custom (restriction 1: sender denied)`.
+    pub fn restriction(code: u8, message: &str) -> Self {
+        PSP22Error::Custom(format!("restriction {code}: {message}"))
+    }
+
+    /// Returns a stable numeric code of the error variant, equal to its SCALE encoding index.
+    pub fn code(&self) -> u8 {
+        match self {
+            PSP22Error::Custom(_) => 0,
+            PSP22Error::InsufficientBalance => 1,
+            PSP22Error::InsufficientAllowance => 2,
+            PSP22Error::ZeroRecipientAddress => 3,
+            PSP22Error::ZeroSenderAddress => 4,
+            PSP22Error::SafeTransferCheckFailed(_) => 5,
+            PSP22Error::DeadlineExpired => 6,
+            PSP22Error::InvalidSignature => 7,
+            PSP22Error::NonceMismatch => 8,
+            PSP22Error::FlashLoanFailed(_) => 9,
+            PSP22Error::AllowanceMismatch => 10,
+            PSP22Error::AccountLocked => 11,
+            PSP22Error::AccountFrozen => 12,
+            PSP22Error::Paused => 13,
+            PSP22Error::AllowanceCapExceeded => 14,
+            PSP22Error::AlreadyClaimed => 15,
+            PSP22Error::InvalidProof => 16,
+            PSP22Error::TransferRestricted(_) => 17,
+            PSP22Error::AllowanceOverflow => 18,
+            PSP22Error::CapExceeded => 19,
+            PSP22Error::UnderlyingCallFailed => 20,
+            PSP22Error::ZeroAmount => 21,
+            PSP22Error::SupplyBelowMinimum => 22,
+            PSP22Error::Blacklisted => 23,
+        }
+    }
+}
+
+#[allow(deprecated)]
+impl core::fmt::Display for PSP22Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            PSP22Error::Custom(message) => write!(f, "{message}"),
+            PSP22Error::InsufficientBalance => write!(f, "insufficient balance"),
+            PSP22Error::InsufficientAllowance => write!(f, "insufficient allowance"),
+            PSP22Error::ZeroRecipientAddress => write!(f, "transfer to zero address"),
+            PSP22Error::ZeroSenderAddress => write!(f, "transfer from zero address"),
+            PSP22Error::SafeTransferCheckFailed(message) => {
+                write!(f, "safe transfer check failed: {message}")
+            }
+            PSP22Error::DeadlineExpired => write!(f, "deadline expired"),
+            PSP22Error::InvalidSignature => write!(f, "invalid signature"),
+            PSP22Error::NonceMismatch => write!(f, "nonce mismatch"),
+            PSP22Error::FlashLoanFailed(message) => write!(f, "flash loan failed: {message}"),
+            PSP22Error::AllowanceMismatch => write!(f, "allowance mismatch"),
+            PSP22Error::AccountLocked => write!(f, "account is locked until timestamp"),
+            PSP22Error::AccountFrozen => write!(f, "account is frozen by administrator"),
+            PSP22Error::Paused => write!(f, "token transfers are paused"),
+            PSP22Error::AllowanceCapExceeded => write!(f, "allowance exceeds cap"),
+            PSP22Error::AlreadyClaimed => write!(f, "this account has already claimed tokens"),
+            PSP22Error::InvalidProof => write!(f, "the provided merkle proof is invalid"),
+            PSP22Error::TransferRestricted(reason) => write!(f, "transfer restricted: {reason}"),
+            PSP22Error::AllowanceOverflow => write!(f, "allowance overflow"),
+            PSP22Error::CapExceeded => write!(f, "cap exceeded"),
+            PSP22Error::UnderlyingCallFailed => write!(f, "token call failed"),
+            PSP22Error::ZeroAmount => write!(f, "zero amount"),
+            PSP22Error::SupplyBelowMinimum => write!(f, "total supply below minimum"),
+            PSP22Error::Blacklisted => write!(f, "account is blacklisted"),
+        }
+    }
+}
+
+/// `PSP22Error` without the deprecated variants.
+///
+/// Every variant has the same SCALE index as the corresponding `PSP22Error` variant,
+/// so errors encoded by contracts returning `PSP22Error` decode as the same errors
+/// (with the exception of the deprecated variants, whose indices are left unused).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+pub enum PSP22ErrorV2 {
+    /// Custom error type for implementation-based errors.
+    #[codec(index = 0)]
+    Custom(String),
+    /// Returned when an account does not have enough tokens to complete the operation.
+    #[codec(index = 1)]
+    InsufficientBalance,
+    /// Returned if there is not enough allowance to complete the operation.
+    #[codec(index = 2)]
+    InsufficientAllowance,
+    /// Returned if recipient's address is zero. Used only if the `zero-address-guard`
+    /// feature is enabled.
+    #[codec(index = 3)]
+    ZeroRecipientAddress,
+    /// Returned if a signed operation is submitted after its deadline.
+    #[codec(index = 6)]
+    DeadlineExpired,
+    /// Returned if a signature does not match the signed payload and the signer.
+    #[codec(index = 7)]
+    InvalidSignature,
+    /// Returned if the provided nonce is not equal to the expected nonce of the account.
+    #[codec(index = 8)]
+    NonceMismatch,
+    /// Returned if a flash loan failed: the borrower's callback did not succeed
+    /// or the loan was not repaid.
+    #[codec(index = 9)]
+    FlashLoanFailed(String),
+    /// Returned by `approve_checked` if the current allowance is not equal to the expected one.
+    #[codec(index = 10)]
+    AllowanceMismatch,
+    /// Returned if the sender's account is time-locked.
+    #[codec(index = 11)]
+    AccountLocked,
+    /// Returned if the sender's account is frozen by the administrator of the token.
+    #[codec(index = 12)]
+    AccountFrozen,
+    /// Returned if tokens are transferred, minted or burned while the token is paused.
+    #[codec(index = 13)]
+    Paused,
+    /// Returned if an allowance would exceed the maximal allowance set for the token.
+    #[codec(index = 14)]
+    AllowanceCapExceeded,
+    /// Returned if an account claims airdropped tokens for the second time.
+    #[codec(index = 15)]
+    AlreadyClaimed,
+    /// Returned if a Merkle proof does not match the claimed account and amount.
+    #[codec(index = 16)]
+    InvalidProof,
+    /// Returned if a transfer is not allowed by the whitelist or the blacklist of the token,
+    /// with the reason (e.g. `not whitelisted`).
+    #[codec(index = 17)]
+    TransferRestricted(String),
+    /// Returned if increasing an allowance would overflow `u128`.
+    #[codec(index = 18)]
+    AllowanceOverflow,
+    /// Returned if minting would make the total supply exceed the cap of the token.
+    #[codec(index = 19)]
+    CapExceeded,
+    /// Returned if a call to another token (e.g. the underlying token of a wrapper) failed
+    /// without returning a `PSP22Error`, e.g. it trapped or ran out of gas.
+    #[codec(index = 20)]
+    UnderlyingCallFailed,
+    /// Returned by operations which have no tokens to move, e.g. releasing vested tokens
+    /// when none are releasable, where a zero amount is an error rather than a no-op.
+    #[codec(index = 21)]
+    ZeroAmount,
+    /// Returned if burning would make the total supply fall below the minimum supply of the token.
+    #[codec(index = 22)]
+    SupplyBelowMinimum,
+    /// Returned if the sender or the recipient of a transfer is blacklisted.
+    #[codec(index = 23)]
+    Blacklisted,
+}
+
+impl PSP22ErrorV2 {
+    /// Returns a stable numeric code of the error variant, equal to its SCALE encoding index.
+    pub fn code(&self) -> u8 {
+        PSP22Error::from(self.clone()).code()
+    }
+}
+
+impl core::fmt::Display for PSP22ErrorV2 {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", PSP22Error::from(self.clone()))
+    }
+}
+
+impl From<PSP22ErrorV2> for PSP22Error {
+    fn from(error: PSP22ErrorV2) -> Self {
+        match error {
+            PSP22ErrorV2::Custom(message) => PSP22Error::Custom(message),
+            PSP22ErrorV2::InsufficientBalance => PSP22Error::InsufficientBalance,
+            PSP22ErrorV2::InsufficientAllowance => PSP22Error::InsufficientAllowance,
+            PSP22ErrorV2::ZeroRecipientAddress => PSP22Error::ZeroRecipientAddress,
+            PSP22ErrorV2::DeadlineExpired => PSP22Error::DeadlineExpired,
+            PSP22ErrorV2::InvalidSignature => PSP22Error::InvalidSignature,
+            PSP22ErrorV2::NonceMismatch => PSP22Error::NonceMismatch,
+            PSP22ErrorV2::FlashLoanFailed(message) => PSP22Error::FlashLoanFailed(message),
+            PSP22ErrorV2::AllowanceMismatch => PSP22Error::AllowanceMismatch,
+            PSP22ErrorV2::AccountLocked => PSP22Error::AccountLocked,
+            PSP22ErrorV2::AccountFrozen => PSP22Error::AccountFrozen,
+            PSP22ErrorV2::Paused => PSP22Error::Paused,
+            PSP22ErrorV2::AllowanceCapExceeded => PSP22Error::AllowanceCapExceeded,
+            PSP22ErrorV2::AlreadyClaimed => PSP22Error::AlreadyClaimed,
+            PSP22ErrorV2::InvalidProof => PSP22Error::InvalidProof,
+            PSP22ErrorV2::TransferRestricted(reason) => PSP22Error::TransferRestricted(reason),
+            PSP22ErrorV2::AllowanceOverflow => PSP22Error::AllowanceOverflow,
+            PSP22ErrorV2::CapExceeded => PSP22Error::CapExceeded,
+            PSP22ErrorV2::UnderlyingCallFailed => PSP22Error::UnderlyingCallFailed,
+            PSP22ErrorV2::ZeroAmount => PSP22Error::ZeroAmount,
+            PSP22ErrorV2::SupplyBelowMinimum => PSP22Error::SupplyBelowMinimum,
+            PSP22ErrorV2::Blacklisted => PSP22Error::Blacklisted,
+        }
+    }
+}
+
+// Deprecated variants are converted to `Custom` errors with their messages.
+#[allow(deprecated)]
+impl From<PSP22Error> for PSP22ErrorV2 {
+    fn from(error: PSP22Error) -> Self {
+        match error {
+            PSP22Error::Custom(message) => PSP22ErrorV2::Custom(message),
+            PSP22Error::InsufficientBalance => PSP22ErrorV2::InsufficientBalance,
+            PSP22Error::InsufficientAllowance => PSP22ErrorV2::InsufficientAllowance,
+            PSP22Error::ZeroRecipientAddress => PSP22ErrorV2::ZeroRecipientAddress,
+            PSP22Error::DeadlineExpired => PSP22ErrorV2::DeadlineExpired,
+            PSP22Error::InvalidSignature => PSP22ErrorV2::InvalidSignature,
+            PSP22Error::NonceMismatch => PSP22ErrorV2::NonceMismatch,
+            PSP22Error::FlashLoanFailed(message) => PSP22ErrorV2::FlashLoanFailed(message),
+            PSP22Error::AllowanceMismatch => PSP22ErrorV2::AllowanceMismatch,
+            PSP22Error::AccountLocked => PSP22ErrorV2::AccountLocked,
+            PSP22Error::AccountFrozen => PSP22ErrorV2::AccountFrozen,
+            PSP22Error::Paused => PSP22ErrorV2::Paused,
+            PSP22Error::AllowanceCapExceeded => PSP22ErrorV2::AllowanceCapExceeded,
+            PSP22Error::AlreadyClaimed => PSP22ErrorV2::AlreadyClaimed,
+            PSP22Error::InvalidProof => PSP22ErrorV2::InvalidProof,
+            PSP22Error::TransferRestricted(reason) => PSP22ErrorV2::TransferRestricted(reason),
+            PSP22Error::AllowanceOverflow => PSP22ErrorV2::AllowanceOverflow,
+            PSP22Error::CapExceeded => PSP22ErrorV2::CapExceeded,
+            PSP22Error::UnderlyingCallFailed => PSP22ErrorV2::UnderlyingCallFailed,
+            PSP22Error::ZeroAmount => PSP22ErrorV2::ZeroAmount,
+            PSP22Error::SupplyBelowMinimum => PSP22ErrorV2::SupplyBelowMinimum,
+            PSP22Error::Blacklisted => PSP22ErrorV2::Blacklisted,
+            error @ (PSP22Error::ZeroSenderAddress | PSP22Error::SafeTransferCheckFailed(_)) => {
+                PSP22ErrorV2::Custom(error.to_string())
+            }
+        }
+    }
+}
+
+/// Errors returned by `Ownable` messages and `OwnableData` methods.
+///
+/// Contracts returning `PSP22Error` from privileged messages can use `?` on these errors,
+/// they convert into `PSP22Error::Custom` with the `Display` message.
+#[derive(Debug, PartialEq, Eq)]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+pub enum OwnableError {
+    /// Returned if the caller is not the owner of the contract.
+    CallerIsNotOwner,
+    /// Returned if the new owner's address is zero.
+    NewOwnerIsZero,
+    /// Returned if the caller accepting ownership is not the pending owner.
+    NotPendingOwner,
+}
+
+impl OwnableError {
+    /// Returns a stable numeric code of the error variant, equal to its SCALE encoding index.
+    pub fn code(&self) -> u8 {
+        match self {
+            OwnableError::CallerIsNotOwner => 0,
+            OwnableError::NewOwnerIsZero => 1,
+            OwnableError::NotPendingOwner => 2,
+        }
+    }
+}
+
+impl core::fmt::Display for OwnableError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            OwnableError::CallerIsNotOwner => write!(f, "caller is not the owner"),
+            OwnableError::NewOwnerIsZero => write!(f, "new owner is the zero address"),
+            OwnableError::NotPendingOwner => write!(f, "caller is not the pending owner"),
+        }
+    }
+}
+
+impl From<OwnableError> for PSP22Error {
+    fn from(error: OwnableError) -> Self {
+        PSP22Error::Custom(error.to_string())
+    }
+}
+
+/// Errors returned by `AccessControl` messages and `AccessControlData` methods.
+///
+/// Like `OwnableError`, they convert into `PSP22Error::Custom` with the `Display` message.
+#[derive(Debug, PartialEq, Eq)]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+pub enum AccessControlError {
+    /// Returned if the caller does not have the role required by the message.
+    MissingRole,
+    /// Returned if an account tries to renounce a role on behalf of another account.
+    InvalidCaller,
+}
+
+impl AccessControlError {
+    /// Returns a stable numeric code of the error variant, equal to its SCALE encoding index.
+    pub fn code(&self) -> u8 {
+        match self {
+            AccessControlError::MissingRole => 0,
+            AccessControlError::InvalidCaller => 1,
+        }
+    }
+}
+
+impl core::fmt::Display for AccessControlError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            AccessControlError::MissingRole => write!(f, "caller is missing the role"),
+            AccessControlError::InvalidCaller => write!(f, "can only renounce roles for self"),
+        }
+    }
+}
+
+impl From<AccessControlError> for PSP22Error {
+    fn from(error: AccessControlError) -> Self {
+        PSP22Error::Custom(error.to_string())
+    }
+}
+
+#[cfg(test)]
+#[allow(deprecated)]
+mod tests {
+    use super::*;
+    use ink::scale::{Decode, Encode};
+
+    #[test]
+    fn display_messages() {
+        let cases = [
+            (PSP22Error::Custom(String::from("some error")), "some error"),
+            (
+                PSP22Error::restriction(1, "sender denied"),
+                "restriction 1: sender denied",
+            ),
+            (PSP22Error::InsufficientBalance, "insufficient balance"),
+            (PSP22Error::InsufficientAllowance, "insufficient allowance"),
+            (PSP22Error::ZeroRecipientAddress, "transfer to zero address"),
+            (PSP22Error::ZeroSenderAddress, "transfer from zero address"),
+            (
+                PSP22Error::SafeTransferCheckFailed(String::from("rejected")),
+                "safe transfer check failed: rejected",
+            ),
+            (PSP22Error::DeadlineExpired, "deadline expired"),
+            (PSP22Error::InvalidSignature, "invalid signature"),
+            (PSP22Error::NonceMismatch, "nonce mismatch"),
+            (
+                PSP22Error::flash_loan_failed("repayment transfer failed"),
+                "flash loan failed: repayment transfer failed",
+            ),
+            (PSP22Error::AllowanceMismatch, "allowance mismatch"),
+            (
+                PSP22Error::AccountLocked,
+                "account is locked until timestamp",
+            ),
+            (
+                PSP22Error::AccountFrozen,
+                "account is frozen by administrator",
+            ),
+            (PSP22Error::Paused, "token transfers are paused"),
+            (PSP22Error::AllowanceCapExceeded, "allowance exceeds cap"),
+            (
+                PSP22Error::AlreadyClaimed,
+                "this account has already claimed tokens",
+            ),
+            (
+                PSP22Error::InvalidProof,
+                "the provided merkle proof is invalid",
+            ),
+            (
+                PSP22Error::transfer_restricted("not whitelisted"),
+                "transfer restricted: not whitelisted",
+            ),
+            (PSP22Error::AllowanceOverflow, "allowance overflow"),
+            (PSP22Error::CapExceeded, "cap exceeded"),
+            (PSP22Error::UnderlyingCallFailed, "token call failed"),
+            (PSP22Error::ZeroAmount, "zero amount"),
+            (PSP22Error::SupplyBelowMinimum, "total supply below minimum"),
+            (PSP22Error::Blacklisted, "account is blacklisted"),
+        ];
+        for (error, message) in cases {
+            assert_eq!(error.to_string(), message);
+        }
+        assert_eq!(
+            OwnableError::CallerIsNotOwner.to_string(),
+            "caller is not the owner"
+        );
+        assert_eq!(
+            OwnableError::NewOwnerIsZero.to_string(),
+            "new owner is the zero address"
+        );
+        assert_eq!(
+            OwnableError::NotPendingOwner.to_string(),
+            "caller is not the pending owner"
+        );
+        assert_eq!(
+            AccessControlError::MissingRole.to_string(),
+            "caller is missing the role"
+        );
+        assert_eq!(
+            AccessControlError::InvalidCaller.to_string(),
+            "can only renounce roles for self"
+        );
+    }
+
+    // Error codes are a part of the public interface, they must never change.
+    #[test]
+    fn codes_are_stable() {
+        let cases = [
+            (PSP22Error::custom("error"), 0),
+            (PSP22Error::InsufficientBalance, 1),
+            (PSP22Error::InsufficientAllowance, 2),
+            (PSP22Error::ZeroRecipientAddress, 3),
+            (PSP22Error::ZeroSenderAddress, 4),
+            (
+                PSP22Error::SafeTransferCheckFailed(String::from("error")),
+                5,
+            ),
+            (PSP22Error::DeadlineExpired, 6),
+            (PSP22Error::InvalidSignature, 7),
+            (PSP22Error::NonceMismatch, 8),
+            (PSP22Error::flash_loan_failed("error"), 9),
+            (PSP22Error::AllowanceMismatch, 10),
+            (PSP22Error::AccountLocked, 11),
+            (PSP22Error::AccountFrozen, 12),
+            (PSP22Error::Paused, 13),
+            (PSP22Error::AllowanceCapExceeded, 14),
+            (PSP22Error::AlreadyClaimed, 15),
+            (PSP22Error::InvalidProof, 16),
+            (PSP22Error::transfer_restricted("error"), 17),
+            (PSP22Error::AllowanceOverflow, 18),
+            (PSP22Error::CapExceeded, 19),
+            (PSP22Error::UnderlyingCallFailed, 20),
+            (PSP22Error::ZeroAmount, 21),
+            (PSP22Error::SupplyBelowMinimum, 22),
+            (PSP22Error::Blacklisted, 23),
+        ];
+        for (error, code) in cases {
+            assert_eq!(error.code(), code);
+            assert_eq!(error.encode()[0], code);
+        }
+        for (error, code) in [
+            (OwnableError::CallerIsNotOwner, 0),
+            (OwnableError::NewOwnerIsZero, 1),
+            (OwnableError::NotPendingOwner, 2),
+        ] {
+            assert_eq!(error.code(), code);
+            assert_eq!(error.encode()[0], code);
+        }
+        for (error, code) in [
+            (AccessControlError::MissingRole, 0),
+            (AccessControlError::InvalidCaller, 1),
+        ] {
+            assert_eq!(error.code(), code);
+            assert_eq!(error.encode()[0], code);
+        }
+    }
+
+    // Pins the SCALE encoding of every variant, decoding raw bytes as produced by contracts.
+    #[test]
+    fn scale_indices_are_pinned() {
+        let message = String::from("abc").encode();
+        let with_message = |index: u8| [&[index][..], &message[..]].concat();
+        let cases = [
+            (with_message(0), PSP22Error::custom("abc")),
+            (vec![1], PSP22Error::InsufficientBalance),
+            (vec![2], PSP22Error::InsufficientAllowance),
+            (vec![3], PSP22Error::ZeroRecipientAddress),
+            (vec![4], PSP22Error::ZeroSenderAddress),
+            (
+                with_message(5),
+                PSP22Error::SafeTransferCheckFailed(String::from("abc")),
+            ),
+            (vec![6], PSP22Error::DeadlineExpired),
+            (vec![7], PSP22Error::InvalidSignature),
+            (vec![8], PSP22Error::NonceMismatch),
+            (with_message(9), PSP22Error::flash_loan_failed("abc")),
+            (vec![10], PSP22Error::AllowanceMismatch),
+            (vec![11], PSP22Error::AccountLocked),
+            (vec![12], PSP22Error::AccountFrozen),
+            (vec![13], PSP22Error::Paused),
+            (vec![14], PSP22Error::AllowanceCapExceeded),
+            (vec![15], PSP22Error::AlreadyClaimed),
+            (vec![16], PSP22Error::InvalidProof),
+            (with_message(17), PSP22Error::transfer_restricted("abc")),
+            (vec![18], PSP22Error::AllowanceOverflow),
+            (vec![19], PSP22Error::CapExceeded),
+            (vec![20], PSP22Error::UnderlyingCallFailed),
+            (vec![21], PSP22Error::ZeroAmount),
+            (vec![22], PSP22Error::SupplyBelowMinimum),
+            (vec![23], PSP22Error::Blacklisted),
+        ];
+        for (bytes, error) in cases {
+            assert_eq!(PSP22Error::decode(&mut &bytes[..]).ok(), Some(error));
+        }
+        assert!(PSP22Error::decode(&mut &[24][..]).is_err());
+
+        let cases = [
+            (with_message(0), PSP22ErrorV2::Custom(String::from("abc"))),
+            (vec![1], PSP22ErrorV2::InsufficientBalance),
+            (vec![2], PSP22ErrorV2::InsufficientAllowance),
+            (vec![3], PSP22ErrorV2::ZeroRecipientAddress),
+            (vec![6], PSP22ErrorV2::DeadlineExpired),
+            (vec![7], PSP22ErrorV2::InvalidSignature),
+            (vec![8], PSP22ErrorV2::NonceMismatch),
+            (
+                with_message(9),
+                PSP22ErrorV2::FlashLoanFailed(String::from("abc")),
+            ),
+            (vec![10], PSP22ErrorV2::AllowanceMismatch),
+            (vec![11], PSP22ErrorV2::AccountLocked),
+            (vec![12], PSP22ErrorV2::AccountFrozen),
+            (vec![13], PSP22ErrorV2::Paused),
+            (vec![14], PSP22ErrorV2::AllowanceCapExceeded),
+            (vec![15], PSP22ErrorV2::AlreadyClaimed),
+            (vec![16], PSP22ErrorV2::InvalidProof),
+            (
+                with_message(17),
+                PSP22ErrorV2::TransferRestricted(String::from("abc")),
+            ),
+            (vec![18], PSP22ErrorV2::AllowanceOverflow),
+            (vec![19], PSP22ErrorV2::CapExceeded),
+            (vec![20], PSP22ErrorV2::UnderlyingCallFailed),
+            (vec![21], PSP22ErrorV2::ZeroAmount),
+            (vec![22], PSP22ErrorV2::SupplyBelowMinimum),
+            (vec![23], PSP22ErrorV2::Blacklisted),
+        ];
+        for (bytes, error) in cases {
+            assert_eq!(error.code(), bytes[0]);
+            assert_eq!(error.encode(), bytes);
+            assert_eq!(PSP22ErrorV2::decode(&mut &bytes[..]).ok(), Some(error));
+        }
+        for index in [4, 5, 24] {
+            assert!(PSP22ErrorV2::decode(&mut &with_message(index)[..]).is_err());
+        }
+    }
+
+    #[test]
+    fn v2_conversions() {
+        let errors = [
+            PSP22ErrorV2::Custom(String::from("abc")),
+            PSP22ErrorV2::InsufficientBalance,
+            PSP22ErrorV2::InsufficientAllowance,
+            PSP22ErrorV2::ZeroRecipientAddress,
+            PSP22ErrorV2::DeadlineExpired,
+            PSP22ErrorV2::InvalidSignature,
+            PSP22ErrorV2::NonceMismatch,
+            PSP22ErrorV2::FlashLoanFailed(String::from("abc")),
+            PSP22ErrorV2::AllowanceMismatch,
+            PSP22ErrorV2::AccountLocked,
+            PSP22ErrorV2::AccountFrozen,
+            PSP22ErrorV2::Paused,
+            PSP22ErrorV2::AllowanceCapExceeded,
+            PSP22ErrorV2::AlreadyClaimed,
+            PSP22ErrorV2::InvalidProof,
+            PSP22ErrorV2::TransferRestricted(String::from("abc")),
+            PSP22ErrorV2::AllowanceOverflow,
+            PSP22ErrorV2::CapExceeded,
+            PSP22ErrorV2::UnderlyingCallFailed,
+            PSP22ErrorV2::ZeroAmount,
+            PSP22ErrorV2::SupplyBelowMinimum,
+            PSP22ErrorV2::Blacklisted,
+        ];
+        for error in errors {
+            let v1 = PSP22Error::from(error.clone());
+            assert_eq!(v1.to_string(), error.to_string());
+            assert_eq!(PSP22ErrorV2::from(v1), error);
+        }
+        assert_eq!(
+            PSP22ErrorV2::from(PSP22Error::ZeroSenderAddress),
+            PSP22ErrorV2::Custom(String::from("transfer from zero address"))
+        );
+        assert_eq!(
+            PSP22ErrorV2::from(PSP22Error::SafeTransferCheckFailed(String::from("abc"))),
+            PSP22ErrorV2::Custom(String::from("safe transfer check failed: abc"))
+        );
+    }
+
+    // A message result as seen by a contract calling a token, which can tell locked
+    // and frozen accounts apart without parsing `Custom` messages.
+    #[test]
+    fn cross_contract_callers_match_lock_variants() {
+        fn describe(encoded: &[u8]) -> &'static str {
+            match Result::<(), PSP22ErrorV2>::decode(&mut &encoded[..]) {
+                Ok(Ok(())) => "transferred",
+                Ok(Err(PSP22ErrorV2::AccountLocked)) => "retry later",
+                Ok(Err(PSP22ErrorV2::AccountFrozen)) => "contact administrator",
+                Ok(Err(_)) => "other error",
+                Err(_) => "undecodable",
+            }
+        }
+
+        let results: [Result<(), PSP22Error>; 4] = [
+            Ok(()),
+            Err(PSP22Error::AccountLocked),
+            Err(PSP22Error::AccountFrozen),
+            Err(PSP22Error::custom("account is locked until timestamp")),
+        ];
+        let described: Vec<_> = results.iter().map(|r| describe(&r.encode())).collect();
+        assert_eq!(
+            described,
+            [
+                "transferred",
+                "retry later",
+                "contact administrator",
+                "other error"
+            ]
+        );
+    }
+
+    #[test]
+    fn transfer_restrictions_match_regardless_of_reason() {
+        let errors = [
+            PSP22Error::transfer_restricted("not whitelisted"),
+            PSP22Error::transfer_restricted("region not allowed"),
+            PSP22Error::Blacklisted,
+        ];
+        let restricted: Vec<_> = errors
+            .iter()
+            .map(|error| matches!(error, PSP22Error::TransferRestricted(_)))
+            .collect();
+        assert_eq!(restricted, [true, true, false]);
+
+        let reasons: Vec<_> = errors
+            .iter()
+            .filter_map(|error| match error {
+                PSP22Error::TransferRestricted(reason) => Some(reason.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(reasons, ["not whitelisted", "region not allowed"]);
+    }
+
+    #[test]
+    fn ownable_error_converts_to_custom() {
+        fn guarded() -> Result<(), PSP22Error> {
+            let check: Result<(), OwnableError> = Err(OwnableError::CallerIsNotOwner);
+            check?;
+            Ok(())
+        }
+        assert_eq!(
+            guarded(),
+            Err(PSP22Error::custom("caller is not the owner"))
+        );
+
+        for error in [
+            OwnableError::CallerIsNotOwner,
+            OwnableError::NewOwnerIsZero,
+            OwnableError::NotPendingOwner,
+        ] {
+            let message = error.to_string();
+            assert_eq!(PSP22Error::from(error), PSP22Error::Custom(message));
+        }
+    }
 }
@@ -1,7 +1,27 @@
-use ink::primitives::AccountId;
+use ink::{prelude::vec::Vec, primitives::AccountId};
+
+/// Signature topic of `Approval`, `blake2b_256("Approval(AccountId,AccountId,u128)")`.
+///
+/// The signature topics of `Approval` and `Transfer` are part of the stable interface
+/// of this crate, indexers can rely on them: the name and fields of these events never change.
+/// With the `anonymous-events` feature both events are anonymous and have no signature topic.
+pub const APPROVAL_SIGNATURE_TOPIC: [u8; 32] = [
+    0x25, 0xcd, 0xb6, 0xc9, 0x38, 0x82, 0xe9, 0x25, 0xab, 0xbf, 0xc9, 0xa8, 0xb7, 0xc8, 0x58, 0x84,
+    0xb7, 0x3c, 0x03, 0x8c, 0x03, 0xa2, 0x49, 0x2f, 0x23, 0x8a, 0x5e, 0x5b, 0xa3, 0xfb, 0xff, 0x8c,
+];
+
+/// Signature topic of `Transfer`, `blake2b_256("Transfer(Option<AccountId>,Option<AccountId>,u128)")`.
+pub const TRANSFER_SIGNATURE_TOPIC: [u8; 32] = [
+    0x99, 0x0d, 0xf0, 0x76, 0xcb, 0x1e, 0x95, 0x27, 0xaa, 0x10, 0x2c, 0xd1, 0x00, 0xc1, 0x48, 0x1e,
+    0xfe, 0x39, 0x3e, 0xea, 0xbb, 0x58, 0x25, 0xf9, 0xaf, 0x1f, 0x5e, 0x58, 0x22, 0x18, 0x64, 0xde,
+];
 
 /// Event emitted when allowance by `owner` to `spender` changes.
-#[ink::event]
+///
+/// The first topic of the event is `APPROVAL_SIGNATURE_TOPIC`, unless the event
+/// is anonymous (the `anonymous-events` feature).
+#[cfg_attr(not(feature = "anonymous-events"), ink::event)]
+#[cfg_attr(feature = "anonymous-events", ink::event(anonymous))]
 pub struct Approval {
     /// Account providing allowance.
     #[ink(topic)]
@@ -14,7 +34,11 @@ pub struct Approval {
 }
 
 /// Event emitted when transfer of tokens occurs.
-#[ink::event]
+///
+/// The first topic of the event is `TRANSFER_SIGNATURE_TOPIC`, unless the event
+/// is anonymous (the `anonymous-events` feature).
+#[cfg_attr(not(feature = "anonymous-events"), ink::event)]
+#[cfg_attr(feature = "anonymous-events", ink::event(anonymous))]
 pub struct Transfer {
     /// Transfer sender. `None` in case of minting new tokens.
     #[ink(topic)]
@@ -25,3 +49,307 @@ pub struct Transfer {
     /// Amount of tokens transferred (or minted/burned).
     pub value: u128,
 }
+
+/// Event emitted when a signed permit is used to set an allowance.
+#[ink::event]
+pub struct PermitUsed {
+    /// Account which signed the permit.
+    #[ink(topic)]
+    pub owner: AccountId,
+    /// Allowance beneficiary.
+    #[ink(topic)]
+    pub spender: AccountId,
+    /// New allowance amount.
+    pub value: u128,
+    /// Nonce consumed by the permit.
+    pub nonce: u64,
+}
+
+/// Event emitted alongside `Transfer` when the transfer carries non-empty `data`.
+#[ink::event]
+pub struct TransferWithData {
+    /// Transfer sender. `None` in case of minting new tokens.
+    #[ink(topic)]
+    pub from: Option<AccountId>,
+    /// Transfer recipient. `None` in case of burning tokens.
+    #[ink(topic)]
+    pub to: Option<AccountId>,
+    /// Amount of tokens transferred (or minted/burned).
+    pub value: u128,
+    /// Additional data attached to the transfer, e.g. a memo or a payment reference.
+    pub data: Vec<u8>,
+}
+
+/// Event emitted when a snapshot of balances and total supply is taken.
+#[ink::event]
+pub struct SnapshotCreated {
+    /// Id of the new snapshot.
+    pub id: u64,
+    /// Number of the block in which the snapshot was taken.
+    pub block_number: u32,
+}
+
+/// Event emitted when an account changes its delegatee.
+#[ink::event]
+pub struct DelegateChanged {
+    /// Account delegating its voting power.
+    #[ink(topic)]
+    pub delegator: AccountId,
+    /// Previous delegatee of `delegator`.
+    #[ink(topic)]
+    pub from_delegate: AccountId,
+    /// New delegatee of `delegator`.
+    #[ink(topic)]
+    pub to_delegate: AccountId,
+}
+
+/// Event emitted when the voting power of a delegatee changes.
+#[ink::event]
+pub struct DelegateVotesChanged {
+    /// Account whose voting power changed.
+    #[ink(topic)]
+    pub delegate: AccountId,
+    /// Voting power before the change.
+    pub previous_votes: u128,
+    /// Voting power after the change.
+    pub new_votes: u128,
+}
+
+/// Event emitted when a flash loan is made.
+#[ink::event]
+pub struct FlashLoan {
+    /// Account receiving the loan and the `on_flash_loan` callback.
+    #[ink(topic)]
+    pub receiver: AccountId,
+    /// Token lent.
+    #[ink(topic)]
+    pub token: AccountId,
+    /// Amount of tokens lent.
+    pub amount: u128,
+    /// Fee paid on top of `amount` when repaying the loan.
+    pub fee: u128,
+}
+
+/// Event emitted when a vesting schedule is created.
+#[ink::event]
+pub struct VestingScheduleCreated {
+    /// Account receiving the vested tokens.
+    #[ink(topic)]
+    pub beneficiary: AccountId,
+    /// Timestamp at which vesting starts.
+    pub start: u64,
+    /// Time after `start` before which no tokens are vested.
+    pub cliff: u64,
+    /// Time after `start` at which all tokens are vested.
+    pub duration: u64,
+    /// Total amount of tokens vested by the schedule.
+    pub amount: u128,
+}
+
+/// Event emitted when tokens bridged in from another chain are minted.
+#[ink::event]
+pub struct BridgedIn {
+    /// Account receiving the tokens.
+    #[ink(topic)]
+    pub to: AccountId,
+    /// Amount of tokens minted.
+    pub value: u128,
+    /// Opaque reference of the transaction on the other chain.
+    pub tx_ref: Vec<u8>,
+}
+
+/// Event emitted when tokens bridged out to another chain are burned.
+#[ink::event]
+pub struct BridgedOut {
+    /// Account whose tokens are burned.
+    #[ink(topic)]
+    pub from: AccountId,
+    /// Amount of tokens burned.
+    pub value: u128,
+    /// Opaque recipient on the other chain.
+    pub dest: Vec<u8>,
+}
+
+/// Event emitted when an account is added to the whitelist.
+#[ink::event]
+pub struct AddedToWhitelist {
+    /// Whitelisted account.
+    #[ink(topic)]
+    pub account: AccountId,
+}
+
+/// Event emitted when an account is removed from the whitelist.
+#[ink::event]
+pub struct RemovedFromWhitelist {
+    /// Account removed from the whitelist.
+    #[ink(topic)]
+    pub account: AccountId,
+}
+
+/// Event emitted when an account is added to the blacklist.
+#[ink::event]
+pub struct AddedToBlacklist {
+    /// Blacklisted account.
+    #[ink(topic)]
+    pub account: AccountId,
+}
+
+/// Event emitted when an account is removed from the blacklist.
+#[ink::event]
+pub struct RemovedFromBlacklist {
+    /// Account removed from the blacklist.
+    #[ink(topic)]
+    pub account: AccountId,
+}
+
+/// Event emitted when the owner of the contract changes.
+#[ink::event]
+pub struct OwnershipTransferred {
+    /// Previous owner, `None` if there was no owner.
+    #[ink(topic)]
+    pub previous_owner: Option<AccountId>,
+    /// New owner, `None` if ownership was renounced.
+    #[ink(topic)]
+    pub new_owner: Option<AccountId>,
+}
+
+/// Event emitted when `role` is granted to `account`.
+#[ink::event]
+pub struct RoleGranted {
+    /// The granted role.
+    #[ink(topic)]
+    pub role: u32,
+    /// Account receiving the role.
+    #[ink(topic)]
+    pub account: AccountId,
+    /// Account granting the role, an admin of `role`.
+    #[ink(topic)]
+    pub sender: AccountId,
+}
+
+/// Event emitted when `role` is revoked from `account`.
+#[ink::event]
+pub struct RoleRevoked {
+    /// The revoked role.
+    #[ink(topic)]
+    pub role: u32,
+    /// Account losing the role.
+    #[ink(topic)]
+    pub account: AccountId,
+    /// Account revoking the role: an admin of `role`, or `account` itself when renouncing.
+    #[ink(topic)]
+    pub sender: AccountId,
+}
+
+/// Event emitted when the gas limit of the calls to the underlying token
+/// of a wrapper changes, see `PSP22WrapperData::set_call_gas_limit`.
+#[ink::event]
+pub struct CallGasLimitUpdated {
+    /// Previous gas limit, `0` meaning no limit.
+    pub old_limit: u64,
+    /// New gas limit, `0` meaning no limit.
+    pub new_limit: u64,
+}
+
+/// Event emitted when a timelocked mint is queued.
+#[ink::event]
+pub struct MintQueued {
+    /// Id of the queued mint.
+    pub id: u64,
+    /// Account receiving the tokens.
+    #[ink(topic)]
+    pub to: AccountId,
+    /// Amount of tokens to mint.
+    pub value: u128,
+    /// Block timestamp from which the mint can be executed.
+    pub execute_after: u64,
+}
+
+/// Event emitted when a queued timelocked mint is cancelled.
+#[ink::event]
+pub struct MintCancelled {
+    /// Id of the cancelled mint.
+    pub id: u64,
+}
+
+/// Event emitted when an account claims its airdropped tokens.
+#[ink::event]
+pub struct AirdropClaimed {
+    /// Account receiving the tokens.
+    #[ink(topic)]
+    pub account: AccountId,
+    /// Amount of tokens claimed.
+    pub amount: u128,
+}
+
+/// Event emitted when vested tokens are released to their beneficiary.
+#[ink::event]
+pub struct TokensReleased {
+    /// Account receiving the released tokens.
+    #[ink(topic)]
+    pub beneficiary: AccountId,
+    /// Amount of tokens released.
+    pub amount: u128,
+}
+
+/// Event emitted when the storage of `PSP22Data` is migrated to a new layout,
+/// see `migrate_v0_to_v1`.
+#[ink::event]
+pub struct StorageVersion {
+    /// Version of the storage layout after the migration.
+    pub version: u8,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink::env::{test::recorded_events, DefaultEnvironment as E, Event};
+
+    fn emit_transfer_and_approval() -> Vec<ink::env::test::EmittedEvent> {
+        let account = AccountId::from([1; 32]);
+        ink::env::emit_event::<E, _>(Transfer {
+            from: None,
+            to: Some(account),
+            value: 100,
+        });
+        ink::env::emit_event::<E, _>(Approval {
+            owner: account,
+            spender: account,
+            amount: 100,
+        });
+        recorded_events().collect()
+    }
+
+    #[test]
+    #[cfg(not(feature = "anonymous-events"))]
+    fn signature_topics_are_stable() {
+        assert_eq!(Transfer::SIGNATURE_TOPIC, Some(TRANSFER_SIGNATURE_TOPIC));
+        assert_eq!(Approval::SIGNATURE_TOPIC, Some(APPROVAL_SIGNATURE_TOPIC));
+    }
+
+    #[ink::test]
+    #[cfg(not(feature = "anonymous-events"))]
+    fn emitted_events_start_with_signature_topic() {
+        let events = emit_transfer_and_approval();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].topics[0], TRANSFER_SIGNATURE_TOPIC);
+        assert_eq!(events[1].topics[0], APPROVAL_SIGNATURE_TOPIC);
+        // The remaining topics are the fields marked with `#[ink(topic)]`
+        assert_eq!(events[0].topics.len(), 3);
+        assert_eq!(events[1].topics.len(), 3);
+    }
+
+    #[ink::test]
+    #[cfg(feature = "anonymous-events")]
+    fn anonymous_events_have_no_signature_topic() {
+        assert_eq!(Transfer::SIGNATURE_TOPIC, None);
+        assert_eq!(Approval::SIGNATURE_TOPIC, None);
+
+        let events = emit_transfer_and_approval();
+        assert_eq!(events.len(), 2);
+        // Only the fields marked with `#[ink(topic)]`
+        assert_eq!(events[0].topics.len(), 2);
+        assert_eq!(events[1].topics.len(), 2);
+        assert_ne!(events[0].topics[0], TRANSFER_SIGNATURE_TOPIC);
+    }
+}
@@ -0,0 +1,185 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+pub use self::capped_snapshot_token::{CappedSnapshotToken, CappedSnapshotTokenRef};
+
+// A PSP22 token combining the PSP22Capped and PSP22Snapshot extensions, intended for testing
+// that extensions compose (see `psp22::combined_tests!`).
+//
+// Like in `examples/snapshot_token`, `PSP22SnapshotData::update` is called right before every
+// operation changing balances, including capped mints with `PSP22Data::mint_capped`.
+// Minting is not restricted, a real token would allow only privileged accounts to mint.
+#[ink::contract]
+mod capped_snapshot_token {
+    use ink::prelude::{string::String, vec::Vec};
+    use psp22::{
+        EmitsPSP22Events, PSP22Capped, PSP22Data, PSP22Error, PSP22Mintable, PSP22Snapshot,
+        PSP22SnapshotData, PSP22,
+    };
+
+    #[ink(storage)]
+    pub struct CappedSnapshotToken {
+        data: PSP22Data,
+        snapshots: PSP22SnapshotData,
+        cap: u128,
+    }
+
+    impl CappedSnapshotToken {
+        #[ink(constructor)]
+        pub fn new(supply: u128, cap: u128) -> Self {
+            let (data, events) = PSP22Data::new(supply, Self::env().caller());
+            let contract = Self {
+                data,
+                snapshots: Default::default(),
+                cap,
+            };
+            contract.emit_events(events);
+            contract
+        }
+    }
+
+    impl EmitsPSP22Events for CappedSnapshotToken {}
+
+    impl PSP22 for CappedSnapshotToken {
+        #[ink(message)]
+        fn total_supply(&self) -> u128 {
+            self.data.total_supply()
+        }
+
+        #[ink(message)]
+        fn balance_of(&self, owner: AccountId) -> u128 {
+            self.data.balance_of(owner)
+        }
+
+        #[ink(message)]
+        fn allowance(&self, owner: AccountId, spender: AccountId) -> u128 {
+            self.data.allowance(owner, spender)
+        }
+
+        #[ink(message)]
+        fn transfer(
+            &mut self,
+            to: AccountId,
+            value: u128,
+            data: Vec<u8>,
+        ) -> Result<(), PSP22Error> {
+            let caller = self.env().caller();
+            self.snapshots.update(&self.data, Some(caller), Some(to));
+            let events = self.data.transfer_with_data(caller, to, value, data)?;
+            self.emit_events(events);
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn transfer_from(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            value: u128,
+            data: Vec<u8>,
+        ) -> Result<(), PSP22Error> {
+            self.snapshots.update(&self.data, Some(from), Some(to));
+            let events =
+                self.data
+                    .transfer_from_with_data(self.env().caller(), from, to, value, data)?;
+            self.emit_events(events);
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn approve(&mut self, spender: AccountId, value: u128) -> Result<(), PSP22Error> {
+            let events = self.data.approve(self.env().caller(), spender, value)?;
+            self.emit_events(events);
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn increase_allowance(
+            &mut self,
+            spender: AccountId,
+            delta_value: u128,
+        ) -> Result<(), PSP22Error> {
+            let events = self
+                .data
+                .increase_allowance(self.env().caller(), spender, delta_value)?;
+            self.emit_events(events);
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn decrease_allowance(
+            &mut self,
+            spender: AccountId,
+            delta_value: u128,
+        ) -> Result<(), PSP22Error> {
+            let events = self
+                .data
+                .decrease_allowance(self.env().caller(), spender, delta_value)?;
+            self.emit_events(events);
+            Ok(())
+        }
+    }
+
+    impl PSP22Mintable for CappedSnapshotToken {
+        #[ink(message)]
+        fn mint(&mut self, value: u128) -> Result<(), PSP22Error> {
+            let caller = self.env().caller();
+            self.snapshots.update(&self.data, None, Some(caller));
+            let events = self.data.mint_capped(caller, value, self.cap)?;
+            self.emit_events(events);
+            Ok(())
+        }
+    }
+
+    impl PSP22Capped for CappedSnapshotToken {
+        #[ink(message)]
+        fn cap(&self) -> u128 {
+            self.cap
+        }
+    }
+
+    impl PSP22Snapshot for CappedSnapshotToken {
+        #[ink(message)]
+        fn snapshot(&mut self) -> Result<u64, PSP22Error> {
+            let (id, events) = self.snapshots.snapshot();
+            self.emit_events(events);
+            Ok(id)
+        }
+
+        #[ink(message)]
+        fn balance_of_at(&self, account: AccountId, snapshot_id: u64) -> Result<u128, PSP22Error> {
+            self.snapshots
+                .balance_of_at(&self.data, account, snapshot_id)
+        }
+
+        #[ink(message)]
+        fn total_supply_at(&self, snapshot_id: u64) -> Result<u128, PSP22Error> {
+            self.snapshots.total_supply_at(&self.data, snapshot_id)
+        }
+
+        #[ink(message)]
+        fn get_past_total_supply(&self, timepoint: u64) -> u128 {
+            self.snapshots.get_past_total_supply(&self.data, timepoint)
+        }
+
+        #[ink(message)]
+        fn clock(&self) -> u64 {
+            self.snapshots.clock()
+        }
+
+        #[ink(message)]
+        fn clock_mode(&self) -> String {
+            String::from(self.snapshots.clock_mode())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        psp22::tests!(
+            CappedSnapshotToken,
+            (|supply| CappedSnapshotToken::new(supply, u128::MAX))
+        );
+        psp22::combined_tests!(CappedSnapshotToken, CappedSnapshotToken::new);
+    }
+}
@@ -0,0 +1,343 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+pub use self::compliant_token::{CompliantToken, CompliantTokenRef};
+
+// An example of a compliance token with the PSP22Restrictable extension: transfers can be
+// restricted for several reasons, each with a machine-readable code, so that wallets and
+// indexers can check whether a transfer is allowed (and why not) without simulating it.
+//
+// Compared to the minimal token, the contract:
+// (1) keeps PSP22BlacklistData, a cap of balances and the owner next to PSP22Data in its storage,
+// (2) implements PSP22Restrictable, defining its restriction codes,
+// (3) calls `impls::check_restriction` before every transfer, so that restricted transfers fail
+//     with `Custom (restriction <code>: <message>)`.
+#[ink::contract]
+mod compliant_token {
+    use ink::prelude::{string::String, vec::Vec};
+    use psp22::{
+        impls, EmitsPSP22Events, HasPSP22Data, Ownable, OwnableData, OwnableError, PSP22Blacklist,
+        PSP22BlacklistData, PSP22Data, PSP22Error, PSP22Restrictable, NO_RESTRICTION, PSP22,
+    };
+
+    // (2)
+    pub const SENDER_DENIED: u8 = 1;
+    pub const RECIPIENT_DENIED: u8 = 2;
+    pub const BALANCE_CAP_EXCEEDED: u8 = 3;
+    pub const TOKEN_PAUSED: u8 = 4;
+    pub const SPENDER_DENIED: u8 = 5;
+
+    #[ink(storage)]
+    pub struct CompliantToken {
+        data: PSP22Data,
+        blacklist: PSP22BlacklistData, // (1)
+        max_balance: u128,             // (1)
+        ownable: OwnableData,          // (1)
+    }
+
+    impl CompliantToken {
+        /// Creates a token with `supply` tokens held by the caller, who becomes the owner.
+        /// Transfers must not make a balance exceed `max_balance`.
+        #[ink(constructor)]
+        pub fn new(supply: u128, max_balance: u128) -> Self {
+            let (data, events) = PSP22Data::new(supply, Self::env().caller());
+            let contract = Self {
+                data,
+                blacklist: PSP22BlacklistData::default(),
+                max_balance,
+                ownable: OwnableData::new(Self::env().caller()),
+            };
+            contract.emit_events(events);
+            contract
+        }
+
+        /// Pauses all transfers. Only the owner can pause.
+        #[ink(message)]
+        pub fn pause(&mut self) -> Result<(), PSP22Error> {
+            self.ownable.ensure_owner(self.env().caller())?;
+            self.data.pause();
+            Ok(())
+        }
+
+        /// Unpauses transfers. Only the owner can unpause.
+        #[ink(message)]
+        pub fn unpause(&mut self) -> Result<(), PSP22Error> {
+            self.ownable.ensure_owner(self.env().caller())?;
+            self.data.unpause();
+            Ok(())
+        }
+    }
+
+    impl HasPSP22Data for CompliantToken {
+        fn data(&self) -> &PSP22Data {
+            &self.data
+        }
+        fn data_mut(&mut self) -> &mut PSP22Data {
+            &mut self.data
+        }
+    }
+
+    impl EmitsPSP22Events for CompliantToken {}
+
+    #[rustfmt::skip]
+    impl PSP22 for CompliantToken {
+        #[ink(message)]
+        fn total_supply(&self) -> u128 { impls::total_supply(self) }
+        #[ink(message)]
+        fn balance_of(&self, owner: AccountId) -> u128 { impls::balance_of(self, owner) }
+        #[ink(message)]
+        fn allowance(&self, owner: AccountId, spender: AccountId) -> u128 { impls::allowance(self, owner, spender) }
+        #[ink(message)]
+        fn transfer(&mut self, to: AccountId, value: u128, data: Vec<u8>) -> Result<(), PSP22Error> {
+            let caller = self.env().caller();
+            impls::check_restriction(self, caller, caller, to, value)?; // (3)
+            impls::transfer(self, to, value, data)
+        }
+        #[ink(message)]
+        fn transfer_from(&mut self, from: AccountId, to: AccountId, value: u128, data: Vec<u8>) -> Result<(), PSP22Error> {
+            impls::check_restriction(self, self.env().caller(), from, to, value)?; // (3)
+            impls::transfer_from(self, from, to, value, data)
+        }
+        #[ink(message)]
+        fn approve(&mut self, spender: AccountId, value: u128) -> Result<(), PSP22Error> { impls::approve(self, spender, value) }
+        #[ink(message)]
+        fn increase_allowance(&mut self, spender: AccountId, delta_value: u128) -> Result<(), PSP22Error> { impls::increase_allowance(self, spender, delta_value) }
+        #[ink(message)]
+        fn decrease_allowance(&mut self, spender: AccountId, delta_value: u128) -> Result<(), PSP22Error> { impls::decrease_allowance(self, spender, delta_value) }
+    }
+
+    // (2)
+    impl PSP22Restrictable for CompliantToken {
+        #[ink(message)]
+        fn detect_transfer_restriction(
+            &self,
+            caller: AccountId,
+            from: AccountId,
+            to: AccountId,
+            value: u128,
+        ) -> u8 {
+            if self.data.is_paused() {
+                return TOKEN_PAUSED;
+            }
+            if self.blacklist.is_blacklisted(from) {
+                return SENDER_DENIED;
+            }
+            if self.blacklist.is_blacklisted(caller) {
+                return SPENDER_DENIED;
+            }
+            if self.blacklist.is_blacklisted(to) {
+                return RECIPIENT_DENIED;
+            }
+            if from != to && self.data.balance_of(to).saturating_add(value) > self.max_balance {
+                return BALANCE_CAP_EXCEEDED;
+            }
+            NO_RESTRICTION
+        }
+
+        #[ink(message)]
+        fn message_for_restriction(&self, code: u8) -> String {
+            let message = match code {
+                NO_RESTRICTION => "no restriction",
+                SENDER_DENIED => "sender denied",
+                RECIPIENT_DENIED => "recipient denied",
+                BALANCE_CAP_EXCEEDED => "recipient balance cap exceeded",
+                TOKEN_PAUSED => "token paused",
+                SPENDER_DENIED => "spender denied",
+                _ => "unknown restriction",
+            };
+            String::from(message)
+        }
+    }
+
+    impl PSP22Blacklist for CompliantToken {
+        #[ink(message)]
+        fn add_to_blacklist(&mut self, account: AccountId) -> Result<(), PSP22Error> {
+            self.ownable.ensure_owner(self.env().caller())?;
+            let events = self.blacklist.add_to_blacklist(account);
+            self.emit_events(events);
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn remove_from_blacklist(&mut self, account: AccountId) -> Result<(), PSP22Error> {
+            self.ownable.ensure_owner(self.env().caller())?;
+            let events = self.blacklist.remove_from_blacklist(account);
+            self.emit_events(events);
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn is_blacklisted(&self, account: AccountId) -> bool {
+            self.blacklist.is_blacklisted(account)
+        }
+    }
+
+    impl Ownable for CompliantToken {
+        #[ink(message)]
+        fn owner(&self) -> Option<AccountId> {
+            self.ownable.owner()
+        }
+
+        #[ink(message)]
+        fn transfer_ownership(&mut self, new_owner: AccountId) -> Result<(), OwnableError> {
+            let events = self
+                .ownable
+                .transfer_ownership(self.env().caller(), new_owner)?;
+            self.emit_events(events);
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn renounce_ownership(&mut self) -> Result<(), OwnableError> {
+            let events = self.ownable.renounce_ownership(self.env().caller())?;
+            self.emit_events(events);
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink::env::{test::*, DefaultEnvironment as E};
+
+        psp22::tests!(
+            CompliantToken,
+            (|supply| CompliantToken::new(supply, u128::MAX))
+        );
+
+        #[ink::test]
+        fn unrestricted_transfers_succeed() {
+            let acc = default_accounts::<E>();
+            set_caller::<E>(acc.alice);
+            let mut token = CompliantToken::new(1000, 500);
+
+            assert_eq!(
+                token.detect_transfer_restriction(acc.alice, acc.alice, acc.bob, 500),
+                NO_RESTRICTION
+            );
+            assert_eq!(
+                token.message_for_restriction(NO_RESTRICTION),
+                "no restriction"
+            );
+            assert!(token.transfer(acc.bob, 500, vec![]).is_ok());
+            assert_eq!(token.balance_of(acc.bob), 500);
+        }
+
+        #[ink::test]
+        fn denied_sender_and_recipient_are_restricted() {
+            let acc = default_accounts::<E>();
+            set_caller::<E>(acc.alice);
+            let mut token = CompliantToken::new(1000, u128::MAX);
+            assert!(token.transfer(acc.bob, 100, vec![]).is_ok());
+            assert!(token.add_to_blacklist(acc.bob).is_ok());
+
+            assert_eq!(
+                token.detect_transfer_restriction(acc.bob, acc.bob, acc.alice, 10),
+                SENDER_DENIED
+            );
+            assert_eq!(
+                token.detect_transfer_restriction(acc.alice, acc.alice, acc.bob, 10),
+                RECIPIENT_DENIED
+            );
+            assert_eq!(
+                token.transfer(acc.bob, 10, vec![]),
+                Err(PSP22Error::restriction(2, "recipient denied"))
+            );
+            set_caller::<E>(acc.bob);
+            assert_eq!(
+                token.transfer(acc.alice, 10, vec![]),
+                Err(PSP22Error::restriction(1, "sender denied"))
+            );
+
+            set_caller::<E>(acc.alice);
+            assert!(token.remove_from_blacklist(acc.bob).is_ok());
+            assert!(token.transfer(acc.bob, 10, vec![]).is_ok());
+        }
+
+        #[ink::test]
+        fn denied_spender_is_restricted() {
+            let acc = default_accounts::<E>();
+            set_caller::<E>(acc.alice);
+            let mut token = CompliantToken::new(1000, u128::MAX);
+            assert!(token.approve(acc.bob, 100).is_ok());
+            assert!(token.add_to_blacklist(acc.bob).is_ok());
+
+            assert_eq!(
+                token.detect_transfer_restriction(acc.bob, acc.alice, acc.charlie, 10),
+                SPENDER_DENIED
+            );
+            set_caller::<E>(acc.bob);
+            assert_eq!(
+                token.transfer_from(acc.alice, acc.charlie, 10, vec![]),
+                Err(PSP22Error::restriction(5, "spender denied"))
+            );
+            assert_eq!(token.balance_of(acc.charlie), 0);
+
+            set_caller::<E>(acc.alice);
+            assert!(token.remove_from_blacklist(acc.bob).is_ok());
+            set_caller::<E>(acc.bob);
+            assert!(token
+                .transfer_from(acc.alice, acc.charlie, 10, vec![])
+                .is_ok());
+        }
+
+        #[ink::test]
+        fn exceeding_balance_cap_is_restricted() {
+            let acc = default_accounts::<E>();
+            set_caller::<E>(acc.alice);
+            let mut token = CompliantToken::new(1000, 500);
+            assert!(token.transfer(acc.bob, 400, vec![]).is_ok());
+
+            assert_eq!(
+                token.detect_transfer_restriction(acc.alice, acc.alice, acc.bob, 101),
+                BALANCE_CAP_EXCEEDED
+            );
+            assert_eq!(
+                token.transfer(acc.bob, 101, vec![]),
+                Err(PSP22Error::restriction(3, "recipient balance cap exceeded"))
+            );
+            assert!(token.transfer(acc.bob, 100, vec![]).is_ok());
+            assert_eq!(token.balance_of(acc.bob), 500);
+        }
+
+        #[ink::test]
+        fn transfers_are_restricted_while_paused() {
+            let acc = default_accounts::<E>();
+            set_caller::<E>(acc.alice);
+            let mut token = CompliantToken::new(1000, u128::MAX);
+            assert!(token.approve(acc.bob, 100).is_ok());
+            assert!(token.pause().is_ok());
+
+            assert_eq!(
+                token.detect_transfer_restriction(acc.bob, acc.alice, acc.charlie, 10),
+                TOKEN_PAUSED
+            );
+            set_caller::<E>(acc.bob);
+            assert_eq!(
+                token.transfer_from(acc.alice, acc.charlie, 10, vec![]),
+                Err(PSP22Error::restriction(4, "token paused"))
+            );
+
+            set_caller::<E>(acc.alice);
+            assert!(token.unpause().is_ok());
+            set_caller::<E>(acc.bob);
+            assert!(token
+                .transfer_from(acc.alice, acc.charlie, 10, vec![])
+                .is_ok());
+        }
+
+        #[ink::test]
+        fn only_owner_manages_restrictions() {
+            let acc = default_accounts::<E>();
+            set_caller::<E>(acc.alice);
+            let mut token = CompliantToken::new(1000, u128::MAX);
+
+            set_caller::<E>(acc.bob);
+            let not_owner = Err(OwnableError::CallerIsNotOwner.into());
+            assert_eq!(token.add_to_blacklist(acc.charlie), not_owner);
+            assert_eq!(token.remove_from_blacklist(acc.charlie), not_owner);
+            assert_eq!(token.pause(), not_owner);
+            assert_eq!(token.unpause(), not_owner);
+            assert_eq!(token.message_for_restriction(255), "unknown restriction");
+        }
+    }
+}
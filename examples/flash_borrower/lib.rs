@@ -0,0 +1,217 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+pub use self::flash_borrower::{FlashBorrower, FlashBorrowerRef};
+
+// An example borrower of flash loans made by PSP22 tokens implementing PSP22FlashLender.
+//
+// The borrower does nothing with the loan and repays it right away: it approves
+// the lender to take back the loaned amount plus the fee and returns `CALLBACK_SUCCESS`.
+// The fee is paid from tokens held by the borrower beforehand. A real borrower would
+// make use of the loaned tokens (e.g. for arbitrage) before repaying.
+//
+// For testing lenders, the borrower can be switched to misbehave: it then returns
+// all-zero bytes instead of `CALLBACK_SUCCESS`, which must make the loan fail.
+#[ink::contract]
+mod flash_borrower {
+    use ink::{contract_ref, prelude::vec::Vec};
+    use psp22::{PSP22Error, PSP22FlashBorrower, CALLBACK_SUCCESS, PSP22};
+
+    #[ink(storage)]
+    #[derive(Default)]
+    pub struct FlashBorrower {
+        misbehaving: bool,
+    }
+
+    impl FlashBorrower {
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            Self { misbehaving: false }
+        }
+
+        /// Makes `on_flash_loan` return all-zero bytes instead of `CALLBACK_SUCCESS`.
+        /// `false` switches the misbehavior off.
+        #[ink(message)]
+        pub fn set_misbehaving(&mut self, misbehaving: bool) {
+            self.misbehaving = misbehaving;
+        }
+    }
+
+    impl PSP22FlashBorrower for FlashBorrower {
+        #[ink(message)]
+        fn on_flash_loan(
+            &mut self,
+            _initiator: AccountId,
+            token: AccountId,
+            amount: u128,
+            fee: u128,
+            _data: Vec<u8>,
+        ) -> Result<[u8; 4], PSP22Error> {
+            // Loans are made by tokens themselves, so only the token can be the lender
+            let lender = self.env().caller();
+            if lender != token {
+                return Err(PSP22Error::custom("Untrusted lender"));
+            }
+            let repayment = amount
+                .checked_add(fee)
+                .ok_or(PSP22Error::custom("Repayment exceeds 2^128-1"))?;
+            let mut token: contract_ref!(PSP22) = token.into();
+            token.approve(lender, repayment)?;
+            if self.misbehaving {
+                return Ok([0; 4]);
+            }
+            Ok(CALLBACK_SUCCESS)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink::env::{
+            test::{default_accounts, set_caller},
+            DefaultEnvironment as E,
+        };
+
+        #[ink::test]
+        fn untrusted_lender_is_rejected() {
+            let acc = default_accounts::<E>();
+            let mut borrower = FlashBorrower::new();
+            set_caller::<E>(acc.bob);
+
+            assert_eq!(
+                borrower.on_flash_loan(acc.alice, acc.django, 100, 1, Vec::new()),
+                Err(PSP22Error::custom("Untrusted lender"))
+            );
+        }
+    }
+
+    #[cfg(all(test, feature = "e2e-tests"))]
+    mod e2e_tests {
+        use super::*;
+        use flash_token::{FlashToken, FlashTokenRef};
+        use ink::prelude::vec;
+        use ink_e2e::{account_id, AccountKeyring, ContractsBackend};
+        use psp22::PSP22FlashLender;
+
+        type E2EResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+        #[ink_e2e::test]
+        async fn flash_loan_is_repaid_with_fee<Client: E2EBackend>(
+            mut client: Client,
+        ) -> E2EResult<()> {
+            let alice = account_id(AccountKeyring::Alice);
+
+            // 1% fee
+            let token = client
+                .instantiate(
+                    "flash_token",
+                    &ink_e2e::alice(),
+                    &mut FlashTokenRef::new(1000, 100),
+                )
+                .submit()
+                .await
+                .expect("token instantiation failed");
+            let mut token_call = token.call_builder::<FlashToken>();
+            let borrower = client
+                .instantiate(
+                    "flash_borrower",
+                    &ink_e2e::alice(),
+                    &mut FlashBorrowerRef::new(),
+                )
+                .submit()
+                .await
+                .expect("borrower instantiation failed");
+
+            // The borrower needs tokens to pay the fee
+            client
+                .call(
+                    &ink_e2e::alice(),
+                    &token_call.transfer(borrower.account_id, 10, vec![]),
+                )
+                .submit()
+                .await
+                .expect("transfer failed");
+
+            let result = client
+                .call(
+                    &ink_e2e::alice(),
+                    &token_call.flash_loan(borrower.account_id, token.account_id, 500, vec![]),
+                )
+                .submit()
+                .await
+                .expect("flash loan failed");
+            assert_eq!(result.return_value(), Ok(()));
+
+            for (account, expected) in [(alice, 990), (borrower.account_id, 5)] {
+                let balance = client
+                    .call(&ink_e2e::alice(), &token_call.balance_of(account))
+                    .dry_run()
+                    .await?
+                    .return_value();
+                assert_eq!(balance, expected);
+            }
+            let total_supply = client
+                .call(&ink_e2e::alice(), &token_call.total_supply())
+                .dry_run()
+                .await?
+                .return_value();
+            assert_eq!(total_supply, 995);
+            Ok(())
+        }
+
+        #[ink_e2e::test]
+        async fn flash_loan_with_wrong_callback_result_fails<Client: E2EBackend>(
+            mut client: Client,
+        ) -> E2EResult<()> {
+            let token = client
+                .instantiate(
+                    "flash_token",
+                    &ink_e2e::alice(),
+                    &mut FlashTokenRef::new(1000, 100),
+                )
+                .submit()
+                .await
+                .expect("token instantiation failed");
+            let mut token_call = token.call_builder::<FlashToken>();
+            let borrower = client
+                .instantiate(
+                    "flash_borrower",
+                    &ink_e2e::alice(),
+                    &mut FlashBorrowerRef::new(),
+                )
+                .submit()
+                .await
+                .expect("borrower instantiation failed");
+            let mut borrower_call = borrower.call_builder::<FlashBorrower>();
+            client
+                .call(&ink_e2e::alice(), &borrower_call.set_misbehaving(true))
+                .submit()
+                .await
+                .expect("setting misbehavior failed");
+
+            let result = client
+                .call(
+                    &ink_e2e::alice(),
+                    &token_call.flash_loan(borrower.account_id, token.account_id, 500, vec![]),
+                )
+                .dry_run()
+                .await?;
+            assert_eq!(
+                result.return_value(),
+                Err(PSP22Error::flash_loan_failed(
+                    "callback returned wrong selector"
+                ))
+            );
+
+            let balance = client
+                .call(
+                    &ink_e2e::alice(),
+                    &token_call.balance_of(borrower.account_id),
+                )
+                .dry_run()
+                .await?
+                .return_value();
+            assert_eq!(balance, 0);
+            Ok(())
+        }
+    }
+}
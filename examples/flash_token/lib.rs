@@ -0,0 +1,110 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+pub use self::flash_token::{FlashToken, FlashTokenRef};
+
+// An example of a PSP22 token lending itself in flash loans (PSP22FlashLender extension).
+//
+// Compared to the basic token, the contract:
+// (1) keeps PSP22FlashFeeData next to PSP22Data in its storage,
+// (2) implements PSP22FlashLender based on PSP22FlashFeeData methods.
+//
+// Loaned tokens are minted to the borrower and burned, together with the fee,
+// right after its `PSP22FlashBorrower::on_flash_loan` returns.
+// See `examples/flash_borrower` for a matching borrower contract.
+#[psp22::implementation]
+#[ink::contract]
+mod flash_token {
+    use ink::prelude::vec::Vec;
+    use psp22::{EnvEventSink, PSP22Data, PSP22Error, PSP22FlashFeeData, PSP22FlashLender};
+
+    #[ink(storage)]
+    pub struct FlashToken {
+        #[psp22(data)]
+        data: PSP22Data,
+        flash: PSP22FlashFeeData, // (1)
+    }
+
+    impl FlashToken {
+        /// Creates a token with `supply` tokens held by the caller, charging
+        /// `fee_bps` basis points of each flash loan.
+        #[ink(constructor)]
+        pub fn new(supply: u128, fee_bps: u16) -> Result<Self, PSP22Error> {
+            let flash = PSP22FlashFeeData::new(fee_bps)?;
+            let (data, events) = PSP22Data::new(supply, Self::env().caller());
+            let contract = Self { data, flash };
+            contract.emit_events(events);
+            Ok(contract)
+        }
+    }
+
+    // (2)
+    impl PSP22FlashLender for FlashToken {
+        #[ink(message)]
+        fn max_flash_loan(&self, token: AccountId) -> u128 {
+            self.flash.max_flash_loan(&self.data, token)
+        }
+
+        #[ink(message)]
+        fn flash_fee(&self, token: AccountId, amount: u128) -> Result<u128, PSP22Error> {
+            self.flash.flash_fee(token, amount)
+        }
+
+        #[ink(message)]
+        fn flash_loan(
+            &mut self,
+            receiver: AccountId,
+            token: AccountId,
+            amount: u128,
+            data: Vec<u8>,
+        ) -> Result<(), PSP22Error> {
+            let initiator = self.env().caller();
+            self.flash.flash_loan(
+                &mut self.data,
+                initiator,
+                receiver,
+                token,
+                amount,
+                data,
+                &mut EnvEventSink,
+            )
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink::env::{
+            test::{callee, default_accounts},
+            DefaultEnvironment as E,
+        };
+
+        psp22::tests!(FlashToken, (|supply| FlashToken::new(supply, 0).unwrap()));
+
+        #[ink::test]
+        fn fee_above_100_percent_fails() {
+            assert!(FlashToken::new(1000, 10_001).is_err());
+        }
+
+        #[ink::test]
+        fn flash_fee_and_max_flash_loan_work() {
+            let acc = default_accounts::<E>();
+            let token = FlashToken::new(1000, 100).unwrap();
+            let this = callee::<E>();
+
+            assert_eq!(token.flash_fee(this, 500), Ok(5));
+            assert_eq!(token.max_flash_loan(this), u128::MAX - 1000);
+            assert_eq!(token.max_flash_loan(acc.django), 0);
+        }
+
+        #[ink::test]
+        fn flash_loan_of_other_token_fails() {
+            let acc = default_accounts::<E>();
+            let mut token = FlashToken::new(1000, 100).unwrap();
+
+            assert_eq!(
+                token.flash_loan(acc.bob, acc.django, 500, Vec::new()),
+                Err(PSP22Error::custom("unsupported token"))
+            );
+        }
+    }
+}
@@ -0,0 +1,215 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+pub use self::metatx_token::{MetaTxToken, MetaTxTokenRef};
+
+// An example of a PSP22 token with the PSP22MetaTx extension: holders without native tokens
+// sign transfers off-chain and a relayer submits them, paying for the gas and receiving
+// a fee in tokens.
+//
+// Compared to the minimal token, the contract:
+// (1) keeps PSP22MetaTxData and DomainData next to PSP22Data in its storage,
+// (2) implements PSP22MetaTx based on PSP22MetaTxData methods, with the caller as the relayer,
+// (3) exposes the payload to be signed, so that clients do not have to compute it themselves,
+// (4) implements PSP22Domain, exposing the domain separator included in the payload.
+#[ink::contract]
+mod metatx_token {
+    use ink::prelude::vec::Vec;
+    use psp22::{
+        impls, DomainData, EmitsPSP22Events, HasPSP22Data, PSP22Data, PSP22Domain, PSP22Error,
+        PSP22MetaTx, PSP22MetaTxData, PSP22,
+    };
+
+    // Identifies the chain in the domain, must differ between chains the contract is deployed on
+    const CHAIN_ID: u64 = 0;
+
+    #[ink(storage)]
+    pub struct MetaTxToken {
+        data: PSP22Data,
+        metatx: PSP22MetaTxData, // (1)
+        domain: DomainData,      // (1)
+    }
+
+    impl MetaTxToken {
+        #[ink(constructor)]
+        pub fn new(supply: u128) -> Self {
+            let (data, events) = PSP22Data::new(supply, Self::env().caller());
+            let contract = Self {
+                data,
+                metatx: PSP22MetaTxData::default(),
+                domain: DomainData::new("MetaTxToken".into(), "1".into(), CHAIN_ID),
+            };
+            contract.emit_events(events);
+            contract
+        }
+
+        // (3)
+        #[ink(message)]
+        pub fn transfer_payload(
+            &self,
+            owner: AccountId,
+            to: AccountId,
+            value: u128,
+            fee: u128,
+            nonce: u64,
+            deadline: u64,
+        ) -> [u8; 32] {
+            self.metatx
+                .transfer_payload(&self.domain, owner, to, value, fee, nonce, deadline)
+        }
+    }
+
+    impl HasPSP22Data for MetaTxToken {
+        fn data(&self) -> &PSP22Data {
+            &self.data
+        }
+        fn data_mut(&mut self) -> &mut PSP22Data {
+            &mut self.data
+        }
+    }
+
+    impl EmitsPSP22Events for MetaTxToken {}
+
+    #[rustfmt::skip]
+    impl PSP22 for MetaTxToken {
+        #[ink(message)]
+        fn total_supply(&self) -> u128 { impls::total_supply(self) }
+        #[ink(message)]
+        fn balance_of(&self, owner: AccountId) -> u128 { impls::balance_of(self, owner) }
+        #[ink(message)]
+        fn allowance(&self, owner: AccountId, spender: AccountId) -> u128 { impls::allowance(self, owner, spender) }
+        #[ink(message)]
+        fn transfer(&mut self, to: AccountId, value: u128, data: Vec<u8>) -> Result<(), PSP22Error> { impls::transfer(self, to, value, data) }
+        #[ink(message)]
+        fn transfer_from(&mut self, from: AccountId, to: AccountId, value: u128, data: Vec<u8>) -> Result<(), PSP22Error> { impls::transfer_from(self, from, to, value, data) }
+        #[ink(message)]
+        fn approve(&mut self, spender: AccountId, value: u128) -> Result<(), PSP22Error> { impls::approve(self, spender, value) }
+        #[ink(message)]
+        fn increase_allowance(&mut self, spender: AccountId, delta_value: u128) -> Result<(), PSP22Error> { impls::increase_allowance(self, spender, delta_value) }
+        #[ink(message)]
+        fn decrease_allowance(&mut self, spender: AccountId, delta_value: u128) -> Result<(), PSP22Error> { impls::decrease_allowance(self, spender, delta_value) }
+    }
+
+    // (2)
+    impl PSP22MetaTx for MetaTxToken {
+        #[ink(message)]
+        fn transfer_with_signature(
+            &mut self,
+            owner: AccountId,
+            to: AccountId,
+            value: u128,
+            fee: u128,
+            deadline: u64,
+            nonce: u64,
+            signature: Vec<u8>,
+        ) -> Result<(), PSP22Error> {
+            let events = self.metatx.transfer_with_signature(
+                &mut self.data,
+                &self.domain,
+                self.env().caller(),
+                owner,
+                to,
+                value,
+                fee,
+                deadline,
+                nonce,
+                signature,
+            )?;
+            self.emit_events(events);
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn meta_tx_nonce(&self, owner: AccountId) -> u64 {
+            self.metatx.nonce_of(owner)
+        }
+    }
+
+    // (4)
+    impl PSP22Domain for MetaTxToken {
+        #[ink(message)]
+        fn domain_separator(&self) -> [u8; 32] {
+            self.domain.domain_separator()
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::MetaTxToken;
+        psp22::tests!(MetaTxToken, MetaTxToken::new);
+    }
+
+    #[cfg(all(test, feature = "e2e-tests"))]
+    mod e2e_tests {
+        use super::*;
+        use ink_e2e::{account_id, AccountKeyring, ChainBackend, ContractsBackend};
+
+        type E2EResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+        #[ink_e2e::test]
+        async fn relayer_pays_gas_and_receives_fee<Client: E2EBackend>(
+            mut client: Client,
+        ) -> E2EResult<()> {
+            let bob = account_id(AccountKeyring::Bob);
+            let charlie = account_id(AccountKeyring::Charlie);
+            let dave = account_id(AccountKeyring::Dave);
+
+            let token = client
+                .instantiate(
+                    "metatx_token",
+                    &ink_e2e::alice(),
+                    &mut MetaTxTokenRef::new(1000),
+                )
+                .submit()
+                .await
+                .expect("instantiation failed");
+            let mut call = token.call_builder::<MetaTxToken>();
+            client
+                .call(&ink_e2e::alice(), &call.transfer(charlie, 100, vec![]))
+                .submit()
+                .await
+                .expect("transfer failed");
+
+            // Charlie signs a transfer of 50 tokens to Dave with a fee of 5 tokens
+            let nonce = client
+                .call(&ink_e2e::alice(), &call.meta_tx_nonce(charlie))
+                .dry_run()
+                .await?
+                .return_value();
+            let payload = client
+                .call(
+                    &ink_e2e::alice(),
+                    &call.transfer_payload(charlie, dave, 50, 5, nonce, u64::MAX),
+                )
+                .dry_run()
+                .await?
+                .return_value();
+            let signature = ink_e2e::charlie().sign(&payload).0.to_vec();
+
+            // Bob relays it
+            let charlie_native = client.free_balance(charlie).await?;
+            let bob_native = client.free_balance(bob).await?;
+            let relay =
+                call.transfer_with_signature(charlie, dave, 50, 5, u64::MAX, nonce, signature);
+            client
+                .call(&ink_e2e::bob(), &relay)
+                .submit()
+                .await
+                .expect("relayed transfer failed");
+
+            assert!(client.free_balance(bob).await? < bob_native);
+            assert_eq!(client.free_balance(charlie).await?, charlie_native);
+            for (account, balance) in [(charlie, 45), (dave, 50), (bob, 5)] {
+                let result = client
+                    .call(&ink_e2e::alice(), &call.balance_of(account))
+                    .dry_run()
+                    .await?;
+                assert_eq!(result.return_value(), balance);
+            }
+
+            // The signature cannot be relayed again
+            let result = client.call(&ink_e2e::bob(), &relay).dry_run().await?;
+            assert_eq!(result.return_value(), Err(PSP22Error::NonceMismatch));
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,307 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+pub use self::migrator::{Migrator, MigratorRef};
+
+// An example of a new PSP22 token replacing an old PSP22 token (e.g. one built with OpenBrush)
+// through a migration: holders approve the old token to the migrator and call `migrate(amount)`,
+// the migrator pulls `amount` old tokens and mints `amount` new tokens 1:1 to the caller.
+//
+// The old tokens are either locked in the migrator forever or, if the migrator may burn them
+// (the old token implements PSP22Burnable), burned right away. Migration is possible until
+// a deadline, after which the old tokens left with their holders are no longer convertible.
+// In both cases the old tokens locked or burned always equal the new tokens minted.
+//
+// Pulling the old tokens reuses `PSP22WrapperData::deposit` with the address of the old token,
+// like in `examples/wrapped_token`, but the new tokens cannot be unwrapped.
+//
+// Upgrading the old contract in place (`set_code_hash` to a contract built with this crate)
+// does not carry the balances over by itself: PSP22Data keeps its state under its own fixed
+// storage keys (see `TOTAL_SUPPLY_KEY` etc.), so it does not see balances stored in another
+// layout (OpenBrush derives its keys differently). Reading them requires the
+// `psp22::compat::openbrush` shim, a migration contract is the simpler and safer way.
+#[ink::contract]
+mod migrator {
+    use ink::prelude::vec::Vec;
+    use psp22::{
+        impls, EmitsPSP22Events, HasPSP22Data, PSP22BurnableRef, PSP22Data, PSP22Error,
+        PSP22WrapperData, ReentrancyGuardData, PSP22,
+    };
+
+    #[ink(storage)]
+    pub struct Migrator {
+        data: PSP22Data,
+        old_token: PSP22WrapperData,
+        burn_old: bool,
+        deadline: u64,
+        total_migrated: u128,
+        guard: ReentrancyGuardData,
+    }
+
+    impl Migrator {
+        /// Creates a token minted by migrating `old_token` until `deadline` (a block timestamp).
+        /// Migrated old tokens are burned if `burn_old` is `true` and locked otherwise.
+        #[ink(constructor)]
+        pub fn new(old_token: AccountId, deadline: u64, burn_old: bool) -> Self {
+            Self {
+                data: Default::default(),
+                old_token: PSP22WrapperData::new(old_token),
+                burn_old,
+                deadline,
+                total_migrated: 0,
+                guard: Default::default(),
+            }
+        }
+
+        /// Pulls `amount` old tokens from the caller, who must have approved them
+        /// to this contract, and mints `amount` new tokens to the caller.
+        ///
+        /// Returns `DeadlineExpired` error if the migration is closed.
+        #[ink(message)]
+        pub fn migrate(&mut self, amount: u128) -> Result<(), PSP22Error> {
+            if !self.is_migration_open() {
+                return Err(PSP22Error::DeadlineExpired);
+            }
+            psp22::non_reentrant!(self.guard, {
+                let caller = self.env().caller();
+                let events = self
+                    .old_token
+                    .deposit(&mut self.data, caller, caller, amount)?;
+                if self.burn_old && amount > 0 {
+                    let result = PSP22BurnableRef::new(self.old_token.underlying())
+                        .burn(amount)
+                        .try_invoke();
+                    match result {
+                        Ok(Ok(result)) => result?,
+                        _ => return Err(PSP22Error::UnderlyingCallFailed),
+                    }
+                }
+                self.total_migrated = self.total_migrated.saturating_add(amount);
+                self.emit_events(events);
+                Ok(())
+            })
+        }
+
+        /// Returns the address of the old token.
+        #[ink(message)]
+        pub fn old_token(&self) -> AccountId {
+            self.old_token.underlying()
+        }
+
+        /// Returns the total amount of old tokens migrated so far.
+        #[ink(message)]
+        pub fn total_migrated(&self) -> u128 {
+            self.total_migrated
+        }
+
+        /// Returns the timestamp after which migration is closed.
+        #[ink(message)]
+        pub fn migration_deadline(&self) -> u64 {
+            self.deadline
+        }
+
+        /// Returns `true` if old tokens can still be migrated.
+        #[ink(message)]
+        pub fn is_migration_open(&self) -> bool {
+            self.env().block_timestamp() <= self.deadline
+        }
+    }
+
+    impl HasPSP22Data for Migrator {
+        fn data(&self) -> &PSP22Data {
+            &self.data
+        }
+        fn data_mut(&mut self) -> &mut PSP22Data {
+            &mut self.data
+        }
+    }
+
+    impl EmitsPSP22Events for Migrator {}
+
+    #[rustfmt::skip]
+    impl PSP22 for Migrator {
+        #[ink(message)]
+        fn total_supply(&self) -> u128 { impls::total_supply(self) }
+        #[ink(message)]
+        fn balance_of(&self, owner: AccountId) -> u128 { impls::balance_of(self, owner) }
+        #[ink(message)]
+        fn allowance(&self, owner: AccountId, spender: AccountId) -> u128 { impls::allowance(self, owner, spender) }
+        #[ink(message)]
+        fn transfer(&mut self, to: AccountId, value: u128, data: Vec<u8>) -> Result<(), PSP22Error> { impls::transfer(self, to, value, data) }
+        #[ink(message)]
+        fn transfer_from(&mut self, from: AccountId, to: AccountId, value: u128, data: Vec<u8>) -> Result<(), PSP22Error> { impls::transfer_from(self, from, to, value, data) }
+        #[ink(message)]
+        fn approve(&mut self, spender: AccountId, value: u128) -> Result<(), PSP22Error> { impls::approve(self, spender, value) }
+        #[ink(message)]
+        fn increase_allowance(&mut self, spender: AccountId, delta_value: u128) -> Result<(), PSP22Error> { impls::increase_allowance(self, spender, delta_value) }
+        #[ink(message)]
+        fn decrease_allowance(&mut self, spender: AccountId, delta_value: u128) -> Result<(), PSP22Error> { impls::decrease_allowance(self, spender, delta_value) }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink::env::{test::*, DefaultEnvironment as E};
+
+        #[ink::test]
+        fn migration_closes_after_deadline() {
+            let acc = default_accounts::<E>();
+            let mut migrator = Migrator::new(acc.django, 10, false);
+            assert_eq!(migrator.old_token(), acc.django);
+            assert!(migrator.is_migration_open());
+
+            set_block_timestamp::<E>(11);
+            assert!(!migrator.is_migration_open());
+            assert_eq!(migrator.migrate(100), Err(PSP22Error::DeadlineExpired));
+            assert_eq!(migrator.total_migrated(), 0);
+            assert_eq!(migrator.total_supply(), 0);
+        }
+
+        #[ink::test]
+        fn storage_of_other_layouts_is_not_read() {
+            // A contract upgraded with `set_code_hash` keeps the storage of its old code,
+            // e.g. the total supply stored in the root of the contract storage
+            ink::env::set_contract_storage(&0u32, &1000u128);
+
+            let data = PSP22Data::default();
+            assert_eq!(data.total_supply(), 0);
+        }
+    }
+
+    #[cfg(all(test, feature = "e2e-tests"))]
+    mod e2e_tests {
+        use super::*;
+        use ink_e2e::{account_id, AccountKeyring, ContractsBackend};
+        use mock_token::{MockToken, MockTokenRef};
+        use snapshot_token::{SnapshotToken, SnapshotTokenRef};
+
+        type E2EResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+        #[ink_e2e::test]
+        async fn migrated_tokens_are_locked<Client: E2EBackend>(
+            mut client: Client,
+        ) -> E2EResult<()> {
+            let alice = account_id(AccountKeyring::Alice);
+
+            let old = client
+                .instantiate(
+                    "mock_token",
+                    &ink_e2e::alice(),
+                    &mut MockTokenRef::new(1000),
+                )
+                .submit()
+                .await
+                .expect("old token instantiation failed");
+            let mut old_call = old.call_builder::<MockToken>();
+            let migrator = client
+                .instantiate(
+                    "migrator",
+                    &ink_e2e::alice(),
+                    &mut MigratorRef::new(old.account_id, u64::MAX, false),
+                )
+                .submit()
+                .await
+                .expect("migrator instantiation failed");
+            let mut migrator_call = migrator.call_builder::<Migrator>();
+
+            client
+                .call(
+                    &ink_e2e::alice(),
+                    &old_call.approve(migrator.account_id, 400),
+                )
+                .submit()
+                .await
+                .expect("approve failed");
+            client
+                .call(&ink_e2e::alice(), &migrator_call.migrate(300))
+                .submit()
+                .await
+                .expect("migration failed");
+
+            // Migrating more than approved fails
+            let result = client
+                .call(&ink_e2e::alice(), &migrator_call.migrate(101))
+                .dry_run()
+                .await?;
+            assert_eq!(
+                result.return_value(),
+                Err(PSP22Error::InsufficientAllowance)
+            );
+
+            // Old tokens locked in the migrator back the new tokens 1:1
+            for (call, expected) in [
+                (old_call.balance_of(alice), 700),
+                (old_call.balance_of(migrator.account_id), 300),
+                (migrator_call.balance_of(alice), 300),
+            ] {
+                let result = client.call(&ink_e2e::alice(), &call).dry_run().await?;
+                assert_eq!(result.return_value(), expected);
+            }
+            for call in [migrator_call.total_supply(), migrator_call.total_migrated()] {
+                let result = client.call(&ink_e2e::alice(), &call).dry_run().await?;
+                assert_eq!(result.return_value(), 300);
+            }
+            Ok(())
+        }
+
+        #[ink_e2e::test]
+        async fn migrated_tokens_are_burned<Client: E2EBackend>(
+            mut client: Client,
+        ) -> E2EResult<()> {
+            let alice = account_id(AccountKeyring::Alice);
+
+            let old = client
+                .instantiate(
+                    "snapshot_token",
+                    &ink_e2e::alice(),
+                    &mut SnapshotTokenRef::new(1000),
+                )
+                .submit()
+                .await
+                .expect("old token instantiation failed");
+            let mut old_call = old.call_builder::<SnapshotToken>();
+            let migrator = client
+                .instantiate(
+                    "migrator",
+                    &ink_e2e::alice(),
+                    &mut MigratorRef::new(old.account_id, u64::MAX, true),
+                )
+                .submit()
+                .await
+                .expect("migrator instantiation failed");
+            let mut migrator_call = migrator.call_builder::<Migrator>();
+
+            client
+                .call(
+                    &ink_e2e::alice(),
+                    &old_call.approve(migrator.account_id, 400),
+                )
+                .submit()
+                .await
+                .expect("approve failed");
+            client
+                .call(&ink_e2e::alice(), &migrator_call.migrate(400))
+                .submit()
+                .await
+                .expect("migration failed");
+
+            // Old supply and new supply add up to the initial supply
+            for (call, expected) in [
+                (old_call.total_supply(), 600),
+                (migrator_call.total_supply(), 400),
+                (migrator_call.total_migrated(), 400),
+            ] {
+                let result = client.call(&ink_e2e::alice(), &call).dry_run().await?;
+                assert_eq!(result.return_value(), expected);
+            }
+            for (call, expected) in [
+                (old_call.balance_of(alice), 600),
+                (old_call.balance_of(migrator.account_id), 0),
+                (migrator_call.balance_of(alice), 400),
+            ] {
+                let result = client.call(&ink_e2e::alice(), &call).dry_run().await?;
+                assert_eq!(result.return_value(), expected);
+            }
+            Ok(())
+        }
+    }
+}
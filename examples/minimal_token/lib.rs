@@ -0,0 +1,60 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+// A complete PSP22 token implemented with the generic functions from `psp22::impls`.
+#[ink::contract]
+mod minimal_token {
+    use ink::prelude::vec::Vec;
+    use psp22::{impls, EmitsPSP22Events, HasPSP22Data, PSP22Data, PSP22Error, PSP22};
+
+    #[ink(storage)]
+    pub struct MinimalToken {
+        data: PSP22Data,
+    }
+
+    impl MinimalToken {
+        #[ink(constructor)]
+        pub fn new(supply: u128) -> Self {
+            let (data, events) = PSP22Data::new(supply, Self::env().caller());
+            let contract = Self { data };
+            contract.emit_events(events);
+            contract
+        }
+    }
+
+    impl HasPSP22Data for MinimalToken {
+        fn data(&self) -> &PSP22Data {
+            &self.data
+        }
+        fn data_mut(&mut self) -> &mut PSP22Data {
+            &mut self.data
+        }
+    }
+
+    impl EmitsPSP22Events for MinimalToken {}
+
+    #[rustfmt::skip]
+    impl PSP22 for MinimalToken {
+        #[ink(message)]
+        fn total_supply(&self) -> u128 { impls::total_supply(self) }
+        #[ink(message)]
+        fn balance_of(&self, owner: AccountId) -> u128 { impls::balance_of(self, owner) }
+        #[ink(message)]
+        fn allowance(&self, owner: AccountId, spender: AccountId) -> u128 { impls::allowance(self, owner, spender) }
+        #[ink(message)]
+        fn transfer(&mut self, to: AccountId, value: u128, data: Vec<u8>) -> Result<(), PSP22Error> { impls::transfer(self, to, value, data) }
+        #[ink(message)]
+        fn transfer_from(&mut self, from: AccountId, to: AccountId, value: u128, data: Vec<u8>) -> Result<(), PSP22Error> { impls::transfer_from(self, from, to, value, data) }
+        #[ink(message)]
+        fn approve(&mut self, spender: AccountId, value: u128) -> Result<(), PSP22Error> { impls::approve(self, spender, value) }
+        #[ink(message)]
+        fn increase_allowance(&mut self, spender: AccountId, delta_value: u128) -> Result<(), PSP22Error> { impls::increase_allowance(self, spender, delta_value) }
+        #[ink(message)]
+        fn decrease_allowance(&mut self, spender: AccountId, delta_value: u128) -> Result<(), PSP22Error> { impls::decrease_allowance(self, spender, delta_value) }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::MinimalToken;
+        psp22::tests!(MinimalToken, MinimalToken::new);
+    }
+}
@@ -0,0 +1,119 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+pub use self::mock_token::{MockToken, MockTokenRef};
+
+// A PSP22 token intended only for testing contracts which interact with PSP22 tokens.
+//
+// It behaves exactly like the example token from the `psp22` crate, but additionally
+// has a fail switch: transfers to the account set with `set_failing_recipient`
+// are rejected with `PSP22Error::Custom`. This allows simulating a recipient,
+// whose transfer fails, in end-to-end tests.
+//
+// It can also act as a malicious token: with a target set by `set_reentry_target`,
+// each `transfer` first tries to call `PSP22Wrapper::withdraw_to` back on the target,
+// withdrawing the wrapped tokens held by the mock itself. This allows testing
+// reentrancy protection of wrapper contracts calling this token.
+//
+// PSP22 is implemented by the `psp22::implementation` attribute, except for `transfer`
+// and `transfer_from`, which are overridden to check the switches.
+#[psp22::implementation]
+#[ink::contract]
+mod mock_token {
+    use ink::{contract_ref, prelude::vec::Vec};
+    use psp22::{PSP22Data, PSP22Error, PSP22Wrapper, PSP22};
+
+    #[ink(storage)]
+    pub struct MockToken {
+        #[psp22(data)]
+        data: PSP22Data,
+        failing_recipient: Option<AccountId>,
+        reentry_target: Option<AccountId>,
+    }
+
+    impl MockToken {
+        #[ink(constructor)]
+        pub fn new(supply: u128) -> Self {
+            let (data, events) = PSP22Data::new(supply, Self::env().caller());
+            let contract = Self {
+                data,
+                failing_recipient: None,
+                reentry_target: None,
+            };
+            contract.emit_events(events);
+            contract
+        }
+
+        /// Makes all subsequent transfers to `recipient` fail.
+        /// `None` switches the failures off.
+        #[ink(message)]
+        pub fn set_failing_recipient(&mut self, recipient: Option<AccountId>) {
+            self.failing_recipient = recipient;
+        }
+
+        /// Makes all subsequent transfers (except the ones to the mock itself) try to call
+        /// `PSP22Wrapper::withdraw_to` on `target` first. `None` switches the reentry off.
+        #[ink(message)]
+        pub fn set_reentry_target(&mut self, target: Option<AccountId>) {
+            self.reentry_target = target;
+        }
+
+        // The result of the reentrant call is ignored, so that the transfer goes on as if
+        // nothing happened. Its effects can be observed in the wrapped token balances.
+        fn try_reentry(&self, to: AccountId, value: u128) {
+            let this = self.env().account_id();
+            if let Some(target) = self.reentry_target {
+                if to != this {
+                    let mut wrapper: contract_ref!(PSP22Wrapper) = target.into();
+                    let _ = wrapper.call_mut().withdraw_to(this, value).try_invoke();
+                }
+            }
+        }
+
+        fn check_recipient(&self, to: AccountId) -> Result<(), PSP22Error> {
+            if self.failing_recipient == Some(to) {
+                return Err(PSP22Error::custom("Mock transfer failure"));
+            }
+            Ok(())
+        }
+    }
+
+    impl PSP22 for MockToken {
+        #[ink(message)]
+        fn transfer(
+            &mut self,
+            to: AccountId,
+            value: u128,
+            data: Vec<u8>,
+        ) -> Result<(), PSP22Error> {
+            self.check_recipient(to)?;
+            self.try_reentry(to, value);
+            let events = self
+                .data
+                .transfer_with_data(self.env().caller(), to, value, data)?;
+            self.emit_events(events);
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn transfer_from(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            value: u128,
+            data: Vec<u8>,
+        ) -> Result<(), PSP22Error> {
+            self.check_recipient(to)?;
+            let events =
+                self.data
+                    .transfer_from_with_data(self.env().caller(), from, to, value, data)?;
+            self.emit_events(events);
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::MockToken;
+        psp22::tests!(MockToken, MockToken::new);
+    }
+}
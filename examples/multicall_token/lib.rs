@@ -0,0 +1,214 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+pub use self::multicall_token::{MulticallToken, MulticallTokenRef};
+
+// An example of a PSP22 token with the PSP22Multicall extension, batching several messages
+// of the caller (e.g. approve and transfer_from) in a single transaction, and with
+// the PSP22BatchApprove extension, setting several allowances in a single transaction.
+#[ink::contract]
+mod multicall_token {
+    use ink::prelude::vec::Vec;
+    use psp22::{
+        impls, EmitsPSP22Events, HasPSP22Data, PSP22BatchApprove, PSP22Data, PSP22Error,
+        PSP22Multicall, PSP22,
+    };
+
+    #[ink(storage)]
+    pub struct MulticallToken {
+        data: PSP22Data,
+    }
+
+    impl MulticallToken {
+        #[ink(constructor)]
+        pub fn new(supply: u128) -> Self {
+            let (data, events) = PSP22Data::new(supply, Self::env().caller());
+            let contract = Self { data };
+            contract.emit_events(events);
+            contract
+        }
+    }
+
+    impl HasPSP22Data for MulticallToken {
+        fn data(&self) -> &PSP22Data {
+            &self.data
+        }
+        fn data_mut(&mut self) -> &mut PSP22Data {
+            &mut self.data
+        }
+    }
+
+    impl EmitsPSP22Events for MulticallToken {}
+
+    #[rustfmt::skip]
+    impl PSP22 for MulticallToken {
+        #[ink(message)]
+        fn total_supply(&self) -> u128 { impls::total_supply(self) }
+        #[ink(message)]
+        fn balance_of(&self, owner: AccountId) -> u128 { impls::balance_of(self, owner) }
+        #[ink(message)]
+        fn allowance(&self, owner: AccountId, spender: AccountId) -> u128 { impls::allowance(self, owner, spender) }
+        #[ink(message)]
+        fn transfer(&mut self, to: AccountId, value: u128, data: Vec<u8>) -> Result<(), PSP22Error> { impls::transfer(self, to, value, data) }
+        #[ink(message)]
+        fn transfer_from(&mut self, from: AccountId, to: AccountId, value: u128, data: Vec<u8>) -> Result<(), PSP22Error> { impls::transfer_from(self, from, to, value, data) }
+        #[ink(message)]
+        fn approve(&mut self, spender: AccountId, value: u128) -> Result<(), PSP22Error> { impls::approve(self, spender, value) }
+        #[ink(message)]
+        fn increase_allowance(&mut self, spender: AccountId, delta_value: u128) -> Result<(), PSP22Error> { impls::increase_allowance(self, spender, delta_value) }
+        #[ink(message)]
+        fn decrease_allowance(&mut self, spender: AccountId, delta_value: u128) -> Result<(), PSP22Error> { impls::decrease_allowance(self, spender, delta_value) }
+    }
+
+    impl PSP22Multicall for MulticallToken {
+        #[ink(message)]
+        fn multicall(&mut self, calls: Vec<Vec<u8>>) -> Result<Vec<Vec<u8>>, PSP22Error> {
+            impls::multicall(self, calls)
+        }
+    }
+
+    impl PSP22BatchApprove for MulticallToken {
+        #[ink(message)]
+        fn batch_approve(&mut self, approvals: Vec<(AccountId, u128)>) -> Result<(), PSP22Error> {
+            impls::batch_approve(self, approvals)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink::env::{test::*, DefaultEnvironment as E};
+
+        psp22::tests!(MulticallToken, MulticallToken::new);
+
+        #[ink::test]
+        fn batch_approve_sets_allowances_of_caller() {
+            let acc = default_accounts::<E>();
+            set_caller::<E>(acc.alice);
+            let mut token = MulticallToken::new(1000);
+
+            assert!(token
+                .batch_approve(vec![(acc.bob, 100), (acc.alice, 50), (acc.charlie, 200)])
+                .is_ok());
+            assert_eq!(token.allowance(acc.alice, acc.bob), 100);
+            assert_eq!(token.allowance(acc.alice, acc.charlie), 200);
+            assert_eq!(token.allowance(acc.alice, acc.alice), 0);
+            assert_eq!(recorded_events().count(), 3);
+        }
+    }
+
+    #[cfg(all(test, feature = "e2e-tests"))]
+    mod e2e_tests {
+        use super::*;
+        use ink::scale::Encode;
+        use ink_e2e::{account_id, AccountKeyring, ContractsBackend};
+        use psp22::{encode_call, selectors};
+
+        type E2EResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+        #[ink_e2e::test]
+        async fn approve_and_transfer_from_are_batched<Client: E2EBackend>(
+            mut client: Client,
+        ) -> E2EResult<()> {
+            let alice = account_id(AccountKeyring::Alice);
+            let bob = account_id(AccountKeyring::Bob);
+            let charlie = account_id(AccountKeyring::Charlie);
+
+            let token = client
+                .instantiate(
+                    "multicall_token",
+                    &ink_e2e::alice(),
+                    &mut MulticallTokenRef::new(1000),
+                )
+                .submit()
+                .await
+                .expect("instantiation failed");
+            let mut call = token.call_builder::<MulticallToken>();
+            client
+                .call(&ink_e2e::alice(), &call.approve(bob, 100))
+                .submit()
+                .await
+                .expect("approve failed");
+
+            // Bob takes 60 tokens of Alice and lets Charlie spend them
+            let calls = vec![
+                encode_call(
+                    selectors::TRANSFER_FROM,
+                    (alice, bob, 60u128, Vec::<u8>::new()),
+                ),
+                encode_call(selectors::APPROVE, (charlie, 60u128)),
+                encode_call(selectors::ALLOWANCE, (alice, bob)),
+            ];
+            let result = client
+                .call(&ink_e2e::bob(), &call.multicall(calls))
+                .submit()
+                .await
+                .expect("multicall failed");
+            assert_eq!(
+                result.return_value(),
+                Ok(vec![vec![0], vec![0], 40u128.encode()])
+            );
+
+            let balance = client
+                .call(&ink_e2e::alice(), &call.balance_of(bob))
+                .dry_run()
+                .await?
+                .return_value();
+            assert_eq!(balance, 60);
+            let allowance = client
+                .call(&ink_e2e::alice(), &call.allowance(bob, charlie))
+                .dry_run()
+                .await?
+                .return_value();
+            assert_eq!(allowance, 60);
+            Ok(())
+        }
+
+        #[ink_e2e::test]
+        async fn failing_middle_call_reverts_all<Client: E2EBackend>(
+            mut client: Client,
+        ) -> E2EResult<()> {
+            let alice = account_id(AccountKeyring::Alice);
+            let bob = account_id(AccountKeyring::Bob);
+            let charlie = account_id(AccountKeyring::Charlie);
+
+            let token = client
+                .instantiate(
+                    "multicall_token",
+                    &ink_e2e::alice(),
+                    &mut MulticallTokenRef::new(1000),
+                )
+                .submit()
+                .await
+                .expect("instantiation failed");
+            let mut call = token.call_builder::<MulticallToken>();
+
+            let calls = vec![
+                encode_call(selectors::APPROVE, (bob, 10u128)),
+                encode_call(selectors::TRANSFER, (charlie, 1001u128, Vec::<u8>::new())),
+                encode_call(selectors::APPROVE, (charlie, 10u128)),
+            ];
+            let multicall = call.multicall(calls);
+            let result = client.call(&ink_e2e::alice(), &multicall).dry_run().await?;
+            assert_eq!(result.return_value(), Err(PSP22Error::InsufficientBalance));
+            let result = client.call(&ink_e2e::alice(), &multicall).submit().await;
+            assert!(result.is_err(), "multicall should revert");
+
+            // Nothing was applied, including the first call
+            for spender in [bob, charlie] {
+                let allowance = client
+                    .call(&ink_e2e::alice(), &call.allowance(alice, spender))
+                    .dry_run()
+                    .await?
+                    .return_value();
+                assert_eq!(allowance, 0);
+            }
+            let balance = client
+                .call(&ink_e2e::alice(), &call.balance_of(alice))
+                .dry_run()
+                .await?
+                .return_value();
+            assert_eq!(balance, 1000);
+            Ok(())
+        }
+    }
+}
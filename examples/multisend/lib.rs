@@ -0,0 +1,228 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+pub use self::multisend::{Multisend, MultisendRef};
+
+// A utility contract distributing tokens of an arbitrary PSP22 token to many recipients.
+//
+// The sender approves the Multisend contract to spend the total amount and calls `multisend`.
+// The contract pulls the total with a single `transfer_from` and then distributes it with
+// individual `transfer` calls. A failing transfer does not revert the whole batch:
+// its amount is returned to the sender and the failure is reported in the result.
+//
+// Tokens are called through `psp22::PSP22Contract`. A multisend created with `new_lenient`
+// also accepts non-conforming tokens, whose `transfer` and `transfer_from` return nothing
+// instead of `Result<(), PSP22Error>`.
+#[ink::contract]
+mod multisend {
+    use ink::prelude::{vec, vec::Vec};
+    use psp22::{PSP22Contract, PSP22Error};
+
+    /// Maximal number of recipients in a single `multisend` call. Bounds the weight
+    /// of the call, so that it always fits in a block.
+    pub const MAX_RECIPIENTS: usize = 100;
+
+    #[ink(storage)]
+    #[derive(Default)]
+    pub struct Multisend {
+        lenient: bool,
+    }
+
+    impl Multisend {
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            Self { lenient: false }
+        }
+
+        /// Creates a multisend treating missing return values of token calls as success.
+        #[ink(constructor)]
+        pub fn new_lenient() -> Self {
+            Self { lenient: true }
+        }
+
+        /// Returns `true` if missing return values of token calls are treated as success.
+        #[ink(message)]
+        pub fn is_lenient(&self) -> bool {
+            self.lenient
+        }
+
+        /// Sends `value` tokens of the PSP22 `token` to each `(recipient, value)` pair
+        /// in `recipients`, on behalf of the caller.
+        ///
+        /// The caller must have approved this contract to spend the sum of all values.
+        /// Returns a vector of flags, one per recipient, telling whether the transfer
+        /// to that recipient succeeded. Tokens which could not be delivered are
+        /// transferred back to the caller.
+        #[ink(message)]
+        pub fn multisend(
+            &mut self,
+            token: AccountId,
+            recipients: Vec<(AccountId, u128)>,
+        ) -> Result<Vec<bool>, PSP22Error> {
+            if recipients.len() > MAX_RECIPIENTS {
+                return Err(PSP22Error::custom(
+                    "Too many recipients in a single multisend.",
+                ));
+            }
+            let total = recipients
+                .iter()
+                .try_fold(0u128, |total, (_, value)| total.checked_add(*value))
+                .ok_or(PSP22Error::custom(
+                    "Total multisend amount exceeds 2^128-1.",
+                ))?;
+
+            let caller = self.env().caller();
+            let mut token = PSP22Contract::new(token);
+            if self.lenient {
+                token = token.lenient();
+            }
+            token.transfer_from(caller, self.env().account_id(), total, vec![])?;
+
+            let mut report = Vec::with_capacity(recipients.len());
+            let mut undelivered: u128 = 0;
+            for (to, value) in recipients {
+                let delivered = token.transfer(to, value, vec![]).is_ok();
+                if !delivered {
+                    // The sum of all values was checked for overflow above
+                    undelivered = undelivered.saturating_add(value);
+                }
+                report.push(delivered);
+            }
+            if undelivered > 0 {
+                token.transfer(caller, undelivered, vec![])?;
+            }
+            Ok(report)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink::env::{test::default_accounts, DefaultEnvironment as E};
+
+        #[ink::test]
+        fn too_many_recipients_fails() {
+            let acc = default_accounts::<E>();
+            let mut multisend = Multisend::new();
+            let recipients = vec![(acc.bob, 1); MAX_RECIPIENTS + 1];
+
+            assert!(matches!(
+                multisend.multisend(acc.django, recipients),
+                Err(PSP22Error::Custom(_))
+            ));
+        }
+
+        #[ink::test]
+        fn total_overflow_fails() {
+            let acc = default_accounts::<E>();
+            let mut multisend = Multisend::new();
+            let recipients = vec![(acc.bob, u128::MAX), (acc.charlie, 1)];
+
+            assert!(matches!(
+                multisend.multisend(acc.django, recipients),
+                Err(PSP22Error::Custom(_))
+            ));
+        }
+
+        #[ink::test]
+        fn strict_by_default() {
+            assert!(!Multisend::new().is_lenient());
+            assert!(Multisend::new_lenient().is_lenient());
+        }
+    }
+
+    #[cfg(all(test, feature = "e2e-tests"))]
+    mod e2e_tests {
+        use super::*;
+        use ink_e2e::{account_id, AccountKeyring, ContractsBackend};
+        use mock_token::{MockToken, MockTokenRef};
+
+        type E2EResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+        // Runs a multisend to three recipients, the second of which fails.
+        async fn multisend_with_failed_recipient<Client: E2EBackend>(
+            client: &mut Client,
+            constructor: &mut MultisendRef,
+        ) -> E2EResult<()> {
+            let alice = account_id(AccountKeyring::Alice);
+            let bob = account_id(AccountKeyring::Bob);
+            let charlie = account_id(AccountKeyring::Charlie);
+            let dave = account_id(AccountKeyring::Dave);
+
+            let token = client
+                .instantiate(
+                    "mock_token",
+                    &ink_e2e::alice(),
+                    &mut MockTokenRef::new(1000),
+                )
+                .submit()
+                .await
+                .expect("token instantiation failed");
+            let mut token_call = token.call_builder::<MockToken>();
+            let multisend = client
+                .instantiate("multisend", &ink_e2e::alice(), constructor)
+                .submit()
+                .await
+                .expect("multisend instantiation failed");
+            let mut multisend_call = multisend.call_builder::<Multisend>();
+
+            client
+                .call(
+                    &ink_e2e::alice(),
+                    &token_call.approve(multisend.account_id, 600),
+                )
+                .submit()
+                .await
+                .expect("approve failed");
+            client
+                .call(
+                    &ink_e2e::alice(),
+                    &token_call.set_failing_recipient(Some(charlie)),
+                )
+                .submit()
+                .await
+                .expect("setting failing recipient failed");
+
+            let recipients = vec![(bob, 100), (charlie, 200), (dave, 300)];
+            let result = client
+                .call(
+                    &ink_e2e::alice(),
+                    &multisend_call.multisend(token.account_id, recipients),
+                )
+                .submit()
+                .await
+                .expect("multisend failed");
+            assert_eq!(result.return_value(), Ok(vec![true, false, true]));
+
+            for (account, expected) in [
+                (alice, 600),
+                (bob, 100),
+                (charlie, 0),
+                (dave, 300),
+                (multisend.account_id, 0),
+            ] {
+                let balance = client
+                    .call(&ink_e2e::alice(), &token_call.balance_of(account))
+                    .dry_run()
+                    .await?
+                    .return_value();
+                assert_eq!(balance, expected);
+            }
+            Ok(())
+        }
+
+        #[ink_e2e::test]
+        async fn multisend_reports_failed_recipient<Client: E2EBackend>(
+            mut client: Client,
+        ) -> E2EResult<()> {
+            multisend_with_failed_recipient(&mut client, &mut MultisendRef::new()).await
+        }
+
+        // Errors returned by a conforming token are not treated as success in lenient mode
+        #[ink_e2e::test]
+        async fn lenient_multisend_reports_failed_recipient<Client: E2EBackend>(
+            mut client: Client,
+        ) -> E2EResult<()> {
+            multisend_with_failed_recipient(&mut client, &mut MultisendRef::new_lenient()).await
+        }
+    }
+}
@@ -0,0 +1,409 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+pub use self::snapshot_token::{SnapshotToken, SnapshotTokenRef};
+
+// An example of a PSP22 token with the PSP22Snapshot extension, e.g. for dividend distributions.
+//
+// Compared to the basic token, the contract:
+// (1) keeps PSP22SnapshotData next to PSP22Data in its storage,
+// (2) calls `PSP22SnapshotData::update` right before every operation changing balances,
+// (3) exposes `snapshot()` (restricted to the owner) and emits the resulting events.
+//
+// The owner is kept in OwnableData, which also implements the `Ownable` trait:
+// privileged messages start with `ensure_owner`.
+//
+// Next to PSP22Mintable and PSP22Burnable, the token has `mint_with_data` and `burn_with_data`
+// messages, e.g. for a bridge correlating mints and burns with transfers on another chain.
+// The data is carried in a `TransferWithData` event emitted after the `Transfer` event.
+#[ink::contract]
+mod snapshot_token {
+    use ink::prelude::{string::String, vec::Vec};
+    use psp22::{
+        EmitsPSP22Events, Ownable, OwnableData, OwnableError, PSP22Burnable, PSP22Data, PSP22Error,
+        PSP22Mintable, PSP22Snapshot, PSP22SnapshotData, PSP22,
+    };
+
+    #[ink(storage)]
+    pub struct SnapshotToken {
+        data: PSP22Data,
+        snapshots: PSP22SnapshotData, // (1)
+        ownable: OwnableData,
+    }
+
+    impl SnapshotToken {
+        #[ink(constructor)]
+        pub fn new(supply: u128) -> Self {
+            let (data, events) = PSP22Data::new(supply, Self::env().caller());
+            let contract = Self {
+                data,
+                snapshots: Default::default(),
+                ownable: OwnableData::new(Self::env().caller()),
+            };
+            contract.emit_events(events);
+            contract
+        }
+
+        /// Mints `value` tokens to `to`, attaching `data` to the emitted events.
+        /// Only the owner can mint.
+        #[ink(message)]
+        pub fn mint_with_data(
+            &mut self,
+            to: AccountId,
+            value: u128,
+            data: Vec<u8>,
+        ) -> Result<(), PSP22Error> {
+            self.ownable.ensure_owner(self.env().caller())?;
+            self.snapshots.update(&self.data, None, Some(to)); // (2)
+            let events = self.data.mint_with_data(to, value, data)?;
+            self.emit_events(events);
+            Ok(())
+        }
+
+        /// Burns `value` tokens from the caller's account, attaching `data`
+        /// to the emitted events.
+        #[ink(message)]
+        pub fn burn_with_data(&mut self, value: u128, data: Vec<u8>) -> Result<(), PSP22Error> {
+            let caller = self.env().caller();
+            self.snapshots.update(&self.data, Some(caller), None); // (2)
+            let events = self.data.burn_with_data(caller, value, data)?;
+            self.emit_events(events);
+            Ok(())
+        }
+
+        /// Moves the whole balance of each of `accounts` holding less than `threshold` tokens
+        /// to `collector` and returns the total amount moved. Only the owner can sweep.
+        #[ink(message)]
+        pub fn sweep_dust(
+            &mut self,
+            accounts: Vec<AccountId>,
+            threshold: u128,
+            collector: AccountId,
+        ) -> Result<u128, PSP22Error> {
+            self.ownable.ensure_owner(self.env().caller())?;
+            for account in &accounts {
+                self.snapshots
+                    .update(&self.data, Some(*account), Some(collector)); // (2)
+            }
+            let (total, events) = self.data.sweep_dust(accounts, threshold, collector)?;
+            self.emit_events(events);
+            Ok(total)
+        }
+    }
+
+    impl EmitsPSP22Events for SnapshotToken {}
+
+    impl PSP22 for SnapshotToken {
+        #[ink(message)]
+        fn total_supply(&self) -> u128 {
+            self.data.total_supply()
+        }
+
+        #[ink(message)]
+        fn balance_of(&self, owner: AccountId) -> u128 {
+            self.data.balance_of(owner)
+        }
+
+        #[ink(message)]
+        fn allowance(&self, owner: AccountId, spender: AccountId) -> u128 {
+            self.data.allowance(owner, spender)
+        }
+
+        #[ink(message)]
+        fn transfer(
+            &mut self,
+            to: AccountId,
+            value: u128,
+            data: Vec<u8>,
+        ) -> Result<(), PSP22Error> {
+            let caller = self.env().caller();
+            self.snapshots.update(&self.data, Some(caller), Some(to)); // (2)
+            let events = self.data.transfer_with_data(caller, to, value, data)?;
+            self.emit_events(events);
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn transfer_from(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            value: u128,
+            data: Vec<u8>,
+        ) -> Result<(), PSP22Error> {
+            self.snapshots.update(&self.data, Some(from), Some(to)); // (2)
+            let events =
+                self.data
+                    .transfer_from_with_data(self.env().caller(), from, to, value, data)?;
+            self.emit_events(events);
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn approve(&mut self, spender: AccountId, value: u128) -> Result<(), PSP22Error> {
+            let events = self.data.approve(self.env().caller(), spender, value)?;
+            self.emit_events(events);
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn increase_allowance(
+            &mut self,
+            spender: AccountId,
+            delta_value: u128,
+        ) -> Result<(), PSP22Error> {
+            let events = self
+                .data
+                .increase_allowance(self.env().caller(), spender, delta_value)?;
+            self.emit_events(events);
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn decrease_allowance(
+            &mut self,
+            spender: AccountId,
+            delta_value: u128,
+        ) -> Result<(), PSP22Error> {
+            let events = self
+                .data
+                .decrease_allowance(self.env().caller(), spender, delta_value)?;
+            self.emit_events(events);
+            Ok(())
+        }
+    }
+
+    impl PSP22Mintable for SnapshotToken {
+        #[ink(message)]
+        fn mint(&mut self, value: u128) -> Result<(), PSP22Error> {
+            self.ownable.ensure_owner(self.env().caller())?;
+            let owner = self.env().caller();
+            self.snapshots.update(&self.data, None, Some(owner)); // (2)
+            let events = self.data.mint(owner, value)?;
+            self.emit_events(events);
+            Ok(())
+        }
+    }
+
+    impl PSP22Burnable for SnapshotToken {
+        #[ink(message)]
+        fn burn(&mut self, value: u128) -> Result<(), PSP22Error> {
+            let caller = self.env().caller();
+            self.snapshots.update(&self.data, Some(caller), None); // (2)
+            let events = self.data.burn(caller, value)?;
+            self.emit_events(events);
+            Ok(())
+        }
+    }
+
+    impl Ownable for SnapshotToken {
+        #[ink(message)]
+        fn owner(&self) -> Option<AccountId> {
+            self.ownable.owner()
+        }
+
+        #[ink(message)]
+        fn transfer_ownership(&mut self, new_owner: AccountId) -> Result<(), OwnableError> {
+            let events = self
+                .ownable
+                .transfer_ownership(self.env().caller(), new_owner)?;
+            self.emit_events(events);
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn renounce_ownership(&mut self) -> Result<(), OwnableError> {
+            let events = self.ownable.renounce_ownership(self.env().caller())?;
+            self.emit_events(events);
+            Ok(())
+        }
+    }
+
+    // (3)
+    impl PSP22Snapshot for SnapshotToken {
+        #[ink(message)]
+        fn snapshot(&mut self) -> Result<u64, PSP22Error> {
+            self.ownable.ensure_owner(self.env().caller())?;
+            let (id, events) = self.snapshots.snapshot();
+            self.emit_events(events);
+            Ok(id)
+        }
+
+        #[ink(message)]
+        fn balance_of_at(&self, account: AccountId, snapshot_id: u64) -> Result<u128, PSP22Error> {
+            self.snapshots
+                .balance_of_at(&self.data, account, snapshot_id)
+        }
+
+        #[ink(message)]
+        fn total_supply_at(&self, snapshot_id: u64) -> Result<u128, PSP22Error> {
+            self.snapshots.total_supply_at(&self.data, snapshot_id)
+        }
+
+        #[ink(message)]
+        fn get_past_total_supply(&self, timepoint: u64) -> u128 {
+            self.snapshots.get_past_total_supply(&self.data, timepoint)
+        }
+
+        #[ink(message)]
+        fn clock(&self) -> u64 {
+            self.snapshots.clock()
+        }
+
+        #[ink(message)]
+        fn clock_mode(&self) -> String {
+            String::from(self.snapshots.clock_mode())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink::env::{test::*, DefaultEnvironment as E};
+        use psp22::{SnapshotCreated, TransferWithData};
+
+        psp22::tests!(SnapshotToken, SnapshotToken::new);
+
+        #[ink::test]
+        fn snapshot_emits_event() {
+            let acc = default_accounts::<E>();
+            set_caller::<E>(acc.alice);
+            let mut token = SnapshotToken::new(1000);
+            advance_block::<E>();
+            advance_block::<E>();
+            let start = recorded_events().count();
+
+            assert_eq!(token.snapshot(), Ok(1));
+            assert_eq!(token.snapshot(), Ok(2));
+
+            let events: Vec<_> = recorded_events().skip(start).collect();
+            assert_eq!(events.len(), 2);
+            for (event, id) in events.iter().zip([1, 2]) {
+                let e = <SnapshotCreated as ink::scale::Decode>::decode(&mut &event.data[..])
+                    .expect("Event is not SnapshotCreated");
+                assert_eq!(e.id, id);
+                assert_eq!(e.block_number, 2);
+            }
+        }
+
+        #[ink::test]
+        fn mint_and_burn_with_data_emit_data() {
+            let acc = default_accounts::<E>();
+            set_caller::<E>(acc.alice);
+            let mut token = SnapshotToken::new(1000);
+            let start = recorded_events().count();
+
+            assert!(token.mint_with_data(acc.bob, 100, vec![7; 32]).is_ok());
+            set_caller::<E>(acc.bob);
+            assert!(token.burn_with_data(30, vec![8]).is_ok());
+            assert_eq!(
+                token.mint_with_data(acc.bob, 100, vec![]),
+                Err(OwnableError::CallerIsNotOwner.into())
+            );
+
+            // Transfer, TransferWithData for each operation
+            let events: Vec<_> = recorded_events().skip(start).collect();
+            assert_eq!(events.len(), 4);
+            let minted = <TransferWithData as ink::scale::Decode>::decode(&mut &events[1].data[..])
+                .expect("Event is not TransferWithData");
+            assert_eq!(
+                (minted.from, minted.to, minted.value),
+                (None, Some(acc.bob), 100)
+            );
+            assert_eq!(minted.data, vec![7; 32]);
+            let burned = <TransferWithData as ink::scale::Decode>::decode(&mut &events[3].data[..])
+                .expect("Event is not TransferWithData");
+            assert_eq!(
+                (burned.from, burned.to, burned.value),
+                (Some(acc.bob), None, 30)
+            );
+            assert_eq!(burned.data, vec![8]);
+            assert_eq!(token.balance_of(acc.bob), 70);
+        }
+
+        #[ink::test]
+        fn owner_sweeps_dust() {
+            let acc = default_accounts::<E>();
+            set_caller::<E>(acc.alice);
+            let mut token = SnapshotToken::new(1000);
+            assert!(token.transfer(acc.bob, 5, vec![]).is_ok());
+            assert!(token.transfer(acc.charlie, 50, vec![]).is_ok());
+            assert_eq!(token.snapshot(), Ok(1));
+
+            set_caller::<E>(acc.bob);
+            assert_eq!(
+                token.sweep_dust(vec![acc.bob], 10, acc.bob),
+                Err(OwnableError::CallerIsNotOwner.into())
+            );
+            set_caller::<E>(acc.alice);
+            let start = recorded_events().count();
+            assert_eq!(
+                token.sweep_dust(vec![acc.bob, acc.charlie], 10, acc.django),
+                Ok(5)
+            );
+
+            assert_eq!(recorded_events().count() - start, 1);
+            assert_eq!(token.balance_of(acc.bob), 0);
+            assert_eq!(token.balance_of(acc.charlie), 50);
+            assert_eq!(token.balance_of(acc.django), 5);
+            // Snapshots keep the balances from before the sweep
+            assert_eq!(token.balance_of_at(acc.bob, 1), Ok(5));
+            assert_eq!(token.balance_of_at(acc.django, 1), Ok(0));
+        }
+
+        #[ink::test]
+        fn snapshot_by_non_owner_fails() {
+            let acc = default_accounts::<E>();
+            set_caller::<E>(acc.alice);
+            let mut token = SnapshotToken::new(1000);
+
+            set_caller::<E>(acc.bob);
+            assert!(token.snapshot().is_err());
+        }
+
+        #[ink::test]
+        fn privileges_follow_ownership() {
+            let acc = default_accounts::<E>();
+            set_caller::<E>(acc.alice);
+            let mut token = SnapshotToken::new(1000);
+
+            assert_eq!(token.owner(), Some(acc.alice));
+            assert!(token.transfer_ownership(acc.bob).is_ok());
+            assert_eq!(token.owner(), Some(acc.bob));
+            assert_eq!(token.mint(100), Err(OwnableError::CallerIsNotOwner.into()));
+
+            set_caller::<E>(acc.bob);
+            assert!(token.mint(100).is_ok());
+            assert_eq!(token.balance_of(acc.bob), 100);
+            assert!(token.renounce_ownership().is_ok());
+            assert_eq!(token.owner(), None);
+            assert!(token.snapshot().is_err());
+            assert_eq!(
+                token.transfer_ownership(acc.bob),
+                Err(OwnableError::CallerIsNotOwner)
+            );
+        }
+
+        #[ink::test]
+        fn snapshots_keep_historical_values() {
+            let acc = default_accounts::<E>();
+            set_caller::<E>(acc.alice);
+            let mut token = SnapshotToken::new(1000);
+
+            let first = token.snapshot().unwrap();
+            assert!(token.transfer(acc.bob, 100, vec![]).is_ok());
+            assert!(token.mint(500).is_ok());
+            let second = token.snapshot().unwrap();
+            set_caller::<E>(acc.bob);
+            assert!(token.burn(100).is_ok());
+
+            assert_eq!(token.balance_of_at(acc.alice, first), Ok(1000));
+            assert_eq!(token.balance_of_at(acc.bob, first), Ok(0));
+            assert_eq!(token.total_supply_at(first), Ok(1000));
+            assert_eq!(token.balance_of_at(acc.alice, second), Ok(1400));
+            assert_eq!(token.balance_of_at(acc.bob, second), Ok(100));
+            assert_eq!(token.total_supply_at(second), Ok(1500));
+            assert_eq!(token.balance_of(acc.bob), 0);
+            assert_eq!(token.total_supply(), 1400);
+        }
+    }
+}
@@ -0,0 +1,185 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+pub use self::upgradeable_token::{UpgradeableToken, UpgradeableTokenRef};
+
+// An example of a PSP22 token whose code can be replaced by its owner with `set_code`,
+// e.g. to add extensions after the token is already in use. `examples/upgradeable_token_v2`
+// is the next version of this contract, adding pausability.
+//
+// After `set_code_hash` the new code works on the storage written by the old code, so
+// the storage layout of each version must be compatible with the previous ones:
+// (1) Fields stored inline (not in `Lazy` or `Mapping`) are SCALE-encoded one after
+//     another in the root storage cell. Here this is only the pause flag of PSP22Data.
+//     New versions must not add, remove or reorder inline fields, otherwise the root cell
+//     can no longer be decoded.
+// (2) The total supply, balances and allowances are stored by PSP22Data under fixed keys
+//     (`psp22::TOTAL_SUPPLY_KEY` etc.), not depending on the name or position of `data`.
+// (3) All other fields are wrapped in `Lazy` (or `Mapping`) with a `ManualKey`, so each
+//     of them lives in its own storage cell under a fixed key. New versions add fields
+//     only this way, with keys never used before.
+// All versions should use the same version of the psp22 crate, as the layout of PSP22Data
+// itself can change between versions of the crate.
+#[psp22::implementation]
+#[ink::contract]
+mod upgradeable_token {
+    use ink::storage::{traits::ManualKey, Lazy};
+    use psp22::{OwnableError, PSP22Data, PSP22Error};
+
+    #[ink(storage)]
+    pub struct UpgradeableToken {
+        #[psp22(data)]
+        data: PSP22Data, // (1), (2)
+        owner: Lazy<AccountId, ManualKey<0x6f776e72>>, // (3)
+    }
+
+    impl UpgradeableToken {
+        #[ink(constructor)]
+        pub fn new(supply: u128) -> Self {
+            let caller = Self::env().caller();
+            let (data, events) = PSP22Data::new(supply, caller);
+            let mut contract = Self {
+                data,
+                owner: Default::default(),
+            };
+            contract.owner.set(&caller);
+            contract.emit_events(events);
+            contract
+        }
+
+        #[ink(message)]
+        pub fn owner(&self) -> AccountId {
+            self.owner.get().unwrap_or_default()
+        }
+
+        /// Replaces the code of this contract with the code uploaded under `code_hash`,
+        /// keeping the storage. Only the owner can upgrade the contract.
+        #[ink(message)]
+        pub fn set_code(&mut self, code_hash: Hash) -> Result<(), PSP22Error> {
+            if self.env().caller() != self.owner() {
+                return Err(OwnableError::CallerIsNotOwner.into());
+            }
+            self.env()
+                .set_code_hash(&code_hash)
+                .map_err(|_| PSP22Error::custom("Setting code hash failed."))
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink::env::{test::*, DefaultEnvironment as E};
+
+        psp22::tests!(UpgradeableToken, UpgradeableToken::new);
+
+        #[ink::test]
+        fn upgrade_by_non_owner_fails() {
+            let acc = default_accounts::<E>();
+            set_caller::<E>(acc.alice);
+            let mut token = UpgradeableToken::new(1000);
+
+            assert_eq!(token.owner(), acc.alice);
+            set_caller::<E>(acc.bob);
+            assert_eq!(
+                token.set_code(Hash::from([1; 32])),
+                Err(OwnableError::CallerIsNotOwner.into())
+            );
+        }
+    }
+
+    #[cfg(all(test, feature = "e2e-tests"))]
+    mod e2e_tests {
+        use super::*;
+        use ink_e2e::{account_id, AccountKeyring, ContractsBackend};
+        use psp22::PSP22;
+        use upgradeable_token_v2::UpgradeableTokenV2;
+
+        type E2EResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+        #[ink_e2e::test]
+        async fn balances_survive_upgrade_to_v2<Client: E2EBackend>(
+            mut client: Client,
+        ) -> E2EResult<()> {
+            let alice = account_id(AccountKeyring::Alice);
+            let bob = account_id(AccountKeyring::Bob);
+
+            let token = client
+                .instantiate(
+                    "upgradeable_token",
+                    &ink_e2e::alice(),
+                    &mut UpgradeableTokenRef::new(1000),
+                )
+                .submit()
+                .await
+                .expect("token instantiation failed");
+            let mut v1_call = token.call_builder::<UpgradeableToken>();
+            client
+                .call(&ink_e2e::alice(), &v1_call.transfer(bob, 100, vec![]))
+                .submit()
+                .await
+                .expect("transfer failed");
+
+            let code_hash = client
+                .upload("upgradeable_token_v2", &ink_e2e::alice())
+                .submit()
+                .await
+                .expect("upload failed")
+                .code_hash;
+            let result = client
+                .call(&ink_e2e::bob(), &v1_call.set_code(code_hash))
+                .dry_run()
+                .await?;
+            assert!(result.return_value().is_err());
+            client
+                .call(&ink_e2e::alice(), &v1_call.set_code(code_hash))
+                .submit()
+                .await
+                .expect("upgrade failed");
+
+            // Same account, new code
+            let mut v2_call = ink_e2e::create_call_builder::<UpgradeableTokenV2>(token.account_id);
+            for (account, expected) in [(alice, 900), (bob, 100)] {
+                let balance = client
+                    .call(&ink_e2e::alice(), &v2_call.balance_of(account))
+                    .dry_run()
+                    .await?
+                    .return_value();
+                assert_eq!(balance, expected);
+            }
+            let owner = client
+                .call(&ink_e2e::alice(), &v2_call.owner())
+                .dry_run()
+                .await?
+                .return_value();
+            assert_eq!(owner, alice);
+
+            client
+                .call(&ink_e2e::alice(), &v2_call.pause())
+                .submit()
+                .await
+                .expect("pause failed");
+            let result = client
+                .call(&ink_e2e::bob(), &v2_call.transfer(alice, 10, vec![]))
+                .dry_run()
+                .await?;
+            assert_eq!(result.return_value(), Err(PSP22Error::Paused));
+
+            client
+                .call(&ink_e2e::alice(), &v2_call.unpause())
+                .submit()
+                .await
+                .expect("unpause failed");
+            client
+                .call(&ink_e2e::bob(), &v2_call.transfer(alice, 10, vec![]))
+                .submit()
+                .await
+                .expect("transfer after unpause failed");
+            let total_supply = client
+                .call(&ink_e2e::alice(), &v2_call.total_supply())
+                .dry_run()
+                .await?
+                .return_value();
+            assert_eq!(total_supply, 1000);
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,162 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+pub use self::upgradeable_token_v2::{UpgradeableTokenV2, UpgradeableTokenV2Ref};
+
+// The second version of `examples/upgradeable_token`, installed with its `set_code` message.
+//
+// Compared to the first version, the contract:
+// (1) adds the `pauser` field - an account allowed to pause the token next to the owner.
+//     Like every field added in an upgrade, it is kept in `Lazy` under a new `ManualKey`,
+//     leaving the storage written by the first version intact,
+// (2) exposes pausing, which PSP22Data already supports (`PSP22Data::pause`).
+//
+// The layout of the fields present in the first version is unchanged, see the comment
+// in `examples/upgradeable_token` for the rules. The contract can be instantiated
+// on its own, too.
+#[psp22::implementation]
+#[ink::contract]
+mod upgradeable_token_v2 {
+    use ink::storage::{traits::ManualKey, Lazy};
+    use psp22::{OwnableError, PSP22Data, PSP22Error};
+
+    #[ink(storage)]
+    pub struct UpgradeableTokenV2 {
+        #[psp22(data)]
+        data: PSP22Data,
+        owner: Lazy<AccountId, ManualKey<0x6f776e72>>,
+        pauser: Lazy<AccountId, ManualKey<0x70737273>>, // (1)
+    }
+
+    impl UpgradeableTokenV2 {
+        #[ink(constructor)]
+        pub fn new(supply: u128) -> Self {
+            let caller = Self::env().caller();
+            let (data, events) = PSP22Data::new(supply, caller);
+            let mut contract = Self {
+                data,
+                owner: Default::default(),
+                pauser: Default::default(),
+            };
+            contract.owner.set(&caller);
+            contract.emit_events(events);
+            contract
+        }
+
+        #[ink(message)]
+        pub fn owner(&self) -> AccountId {
+            self.owner.get().unwrap_or_default()
+        }
+
+        /// Replaces the code of this contract with the code uploaded under `code_hash`,
+        /// keeping the storage. Only the owner can upgrade the contract.
+        #[ink(message)]
+        pub fn set_code(&mut self, code_hash: Hash) -> Result<(), PSP22Error> {
+            self.ensure_owner()?;
+            self.env()
+                .set_code_hash(&code_hash)
+                .map_err(|_| PSP22Error::custom("Setting code hash failed."))
+        }
+
+        /// Returns the account allowed to pause the token next to the owner, if set.
+        #[ink(message)]
+        pub fn pauser(&self) -> Option<AccountId> {
+            self.pauser.get()
+        }
+
+        /// Allows `pauser` to pause and unpause the token. Only the owner can set the pauser.
+        #[ink(message)]
+        pub fn set_pauser(&mut self, pauser: AccountId) -> Result<(), PSP22Error> {
+            self.ensure_owner()?;
+            self.pauser.set(&pauser);
+            Ok(())
+        }
+
+        // (2)
+        #[ink(message)]
+        pub fn is_paused(&self) -> bool {
+            self.data.is_paused()
+        }
+
+        /// Blocks all transfers until `unpause` is called. Only the owner
+        /// and the pauser can pause the token.
+        #[ink(message)]
+        pub fn pause(&mut self) -> Result<(), PSP22Error> {
+            self.ensure_pauser()?;
+            self.data.pause();
+            Ok(())
+        }
+
+        /// Unblocks transfers. Only the owner and the pauser can unpause the token.
+        #[ink(message)]
+        pub fn unpause(&mut self) -> Result<(), PSP22Error> {
+            self.ensure_pauser()?;
+            self.data.unpause();
+            Ok(())
+        }
+
+        fn ensure_owner(&self) -> Result<(), PSP22Error> {
+            if self.env().caller() != self.owner() {
+                return Err(OwnableError::CallerIsNotOwner.into());
+            }
+            Ok(())
+        }
+
+        fn ensure_pauser(&self) -> Result<(), PSP22Error> {
+            if self.pauser() == Some(self.env().caller()) {
+                return Ok(());
+            }
+            self.ensure_owner()
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink::env::{test::*, DefaultEnvironment as E};
+        use psp22::PSP22;
+
+        psp22::tests!(UpgradeableTokenV2, UpgradeableTokenV2::new);
+
+        #[ink::test]
+        fn owner_and_pauser_can_pause() {
+            let acc = default_accounts::<E>();
+            set_caller::<E>(acc.alice);
+            let mut token = UpgradeableTokenV2::new(1000);
+
+            set_caller::<E>(acc.bob);
+            assert_eq!(token.pause(), Err(OwnableError::CallerIsNotOwner.into()));
+            assert_eq!(
+                token.set_pauser(acc.bob),
+                Err(OwnableError::CallerIsNotOwner.into())
+            );
+
+            set_caller::<E>(acc.alice);
+            assert!(token.pause().is_ok());
+            assert!(token.is_paused());
+            assert!(token.set_pauser(acc.bob).is_ok());
+            assert_eq!(token.pauser(), Some(acc.bob));
+
+            set_caller::<E>(acc.bob);
+            assert!(token.unpause().is_ok());
+            assert!(!token.is_paused());
+            assert!(token.pause().is_ok());
+            assert!(token.is_paused());
+        }
+
+        #[ink::test]
+        fn paused_token_blocks_transfers() {
+            let acc = default_accounts::<E>();
+            set_caller::<E>(acc.alice);
+            let mut token = UpgradeableTokenV2::new(1000);
+            assert!(token.pause().is_ok());
+
+            assert_eq!(
+                token.transfer(acc.bob, 100, Vec::new()),
+                Err(PSP22Error::Paused)
+            );
+            assert!(token.unpause().is_ok());
+            assert!(token.transfer(acc.bob, 100, Vec::new()).is_ok());
+            assert_eq!(token.balance_of(acc.bob), 100);
+        }
+    }
+}
@@ -0,0 +1,197 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+pub use self::votes_token::{VotesToken, VotesTokenRef};
+
+// An example of a PSP22 token with the PSP22Votes extension, usable as a governance token.
+//
+// Compared to the basic token, the contract:
+// (1) keeps PSP22VotesData next to PSP22Data in its storage,
+// (2) calls `PSP22VotesData::update` right after every operation changing balances,
+// (3) implements PSP22Votes based on PSP22VotesData methods.
+#[ink::contract]
+mod votes_token {
+    use ink::prelude::vec::Vec;
+    use psp22::{EmitsPSP22Events, PSP22Data, PSP22Error, PSP22Votes, PSP22VotesData, PSP22};
+
+    #[ink(storage)]
+    pub struct VotesToken {
+        data: PSP22Data,
+        votes: PSP22VotesData, // (1)
+    }
+
+    impl VotesToken {
+        #[ink(constructor)]
+        pub fn new(supply: u128) -> Self {
+            let caller = Self::env().caller();
+            let (data, mut events) = PSP22Data::new(supply, caller);
+            let mut votes = PSP22VotesData::default();
+            events.extend(votes.update(None, Some(caller), supply)); // (2)
+            let contract = Self { data, votes };
+            contract.emit_events(events);
+            contract
+        }
+    }
+
+    impl EmitsPSP22Events for VotesToken {}
+
+    impl PSP22 for VotesToken {
+        #[ink(message)]
+        fn total_supply(&self) -> u128 {
+            self.data.total_supply()
+        }
+
+        #[ink(message)]
+        fn balance_of(&self, owner: AccountId) -> u128 {
+            self.data.balance_of(owner)
+        }
+
+        #[ink(message)]
+        fn allowance(&self, owner: AccountId, spender: AccountId) -> u128 {
+            self.data.allowance(owner, spender)
+        }
+
+        #[ink(message)]
+        fn transfer(
+            &mut self,
+            to: AccountId,
+            value: u128,
+            data: Vec<u8>,
+        ) -> Result<(), PSP22Error> {
+            let caller = self.env().caller();
+            let mut events = self.data.transfer_with_data(caller, to, value, data)?;
+            events.extend(self.votes.update(Some(caller), Some(to), value)); // (2)
+            self.emit_events(events);
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn transfer_from(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            value: u128,
+            data: Vec<u8>,
+        ) -> Result<(), PSP22Error> {
+            let mut events =
+                self.data
+                    .transfer_from_with_data(self.env().caller(), from, to, value, data)?;
+            events.extend(self.votes.update(Some(from), Some(to), value)); // (2)
+            self.emit_events(events);
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn approve(&mut self, spender: AccountId, value: u128) -> Result<(), PSP22Error> {
+            let events = self.data.approve(self.env().caller(), spender, value)?;
+            self.emit_events(events);
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn increase_allowance(
+            &mut self,
+            spender: AccountId,
+            delta_value: u128,
+        ) -> Result<(), PSP22Error> {
+            let events = self
+                .data
+                .increase_allowance(self.env().caller(), spender, delta_value)?;
+            self.emit_events(events);
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn decrease_allowance(
+            &mut self,
+            spender: AccountId,
+            delta_value: u128,
+        ) -> Result<(), PSP22Error> {
+            let events = self
+                .data
+                .decrease_allowance(self.env().caller(), spender, delta_value)?;
+            self.emit_events(events);
+            Ok(())
+        }
+    }
+
+    // (3)
+    impl PSP22Votes for VotesToken {
+        #[ink(message)]
+        fn delegate(&mut self, delegatee: AccountId) -> Result<(), PSP22Error> {
+            let events = self
+                .votes
+                .delegate(&self.data, self.env().caller(), delegatee);
+            self.emit_events(events);
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn delegates(&self, account: AccountId) -> AccountId {
+            self.votes.delegates(account)
+        }
+
+        #[ink(message)]
+        fn get_votes(&self, account: AccountId) -> u128 {
+            self.votes.get_votes(account)
+        }
+
+        #[ink(message)]
+        fn get_votes_batch(&self, accounts: Vec<AccountId>) -> Vec<u128> {
+            self.votes.get_votes_batch(&accounts)
+        }
+
+        #[ink(message)]
+        fn get_past_votes(&self, account: AccountId, timepoint: u64) -> Result<u128, PSP22Error> {
+            self.votes.get_past_votes(account, timepoint)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink::env::{test::*, DefaultEnvironment as E};
+
+        psp22::tests!(VotesToken, VotesToken::new);
+
+        #[ink::test]
+        fn votes_follow_delegations_and_transfers() {
+            let acc = default_accounts::<E>();
+            set_caller::<E>(acc.alice);
+            let mut token = VotesToken::new(1000);
+            assert_eq!(token.delegates(acc.alice), acc.alice);
+            assert_eq!(token.get_votes(acc.alice), 1000);
+
+            assert!(token.delegate(acc.charlie).is_ok());
+            advance_block::<E>();
+            assert!(token.transfer(acc.bob, 400, vec![]).is_ok());
+            advance_block::<E>();
+
+            assert_eq!(token.get_votes(acc.alice), 0);
+            assert_eq!(token.get_votes(acc.bob), 400);
+            assert_eq!(token.get_votes(acc.charlie), 600);
+            assert_eq!(token.get_past_votes(acc.charlie, 0), Ok(1000));
+            assert_eq!(token.get_past_votes(acc.bob, 0), Ok(0));
+            assert!(matches!(
+                token.get_past_votes(acc.charlie, 2),
+                Err(PSP22Error::Custom(_))
+            ));
+        }
+
+        #[ink::test]
+        fn account_without_balance_can_delegate() {
+            let acc = default_accounts::<E>();
+            set_caller::<E>(acc.alice);
+            let mut token = VotesToken::new(1000);
+
+            set_caller::<E>(acc.bob);
+            assert!(token.delegate(acc.charlie).is_ok());
+            assert_eq!(token.get_votes(acc.bob), 0);
+            assert_eq!(token.get_votes(acc.charlie), 0);
+
+            set_caller::<E>(acc.alice);
+            assert!(token.transfer(acc.bob, 100, vec![]).is_ok());
+            assert_eq!(token.get_votes(acc.bob), 0);
+            assert_eq!(token.get_votes(acc.charlie), 100);
+        }
+    }
+}
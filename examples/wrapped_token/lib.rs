@@ -0,0 +1,804 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+pub use self::wrapped_token::{WrappedToken, WrappedTokenRef};
+
+// An example of a PSP22 token wrapping another PSP22 token 1:1 with the PSP22Wrapper extension.
+//
+// The address of the underlying token is kept in PSP22WrapperData, together with the gas limit
+// of the calls to it (set by the owner, no limit by default). Underlying tokens are moved
+// by PSP22Data wrapper methods (`deposit`, `withdraw`, `recover_underlying`), which call
+// the underlying token contract.
+//
+// The contract always holds at least as many underlying tokens as the total supply of
+// wrapped tokens. Underlying tokens sent to it directly (bypassing `deposit_for`) are
+// a surplus, which the owner can either send away with `recover_underlying` or mint
+// as wrapped tokens with `recover`.
+//
+// Messages calling the underlying token are guarded by ReentrancyGuardData with
+// the `psp22::non_reentrant!` macro, so that a malicious underlying token cannot call
+// back into them before the wrapper state is settled.
+#[ink::contract]
+mod wrapped_token {
+    use ink::prelude::vec::Vec;
+    use psp22::{
+        EmitsPSP22Events, OwnableError, PSP22Data, PSP22Error, PSP22Wrapper, PSP22WrapperData,
+        ReentrancyGuardData, PSP22,
+    };
+
+    #[ink(storage)]
+    pub struct WrappedToken {
+        data: PSP22Data,
+        wrapper: PSP22WrapperData,
+        owner: AccountId,
+        guard: ReentrancyGuardData,
+    }
+
+    impl WrappedToken {
+        #[ink(constructor)]
+        pub fn new(underlying: AccountId) -> Self {
+            Self {
+                data: Default::default(),
+                wrapper: PSP22WrapperData::new(underlying),
+                owner: Self::env().caller(),
+                guard: Default::default(),
+            }
+        }
+
+        /// Mints the surplus of underlying tokens held by the contract to `account`,
+        /// so that it backs new wrapped tokens. Returns the minted amount.
+        /// Only the owner can recover the surplus.
+        #[ink(message)]
+        pub fn recover(&mut self, account: AccountId) -> Result<u128, PSP22Error> {
+            if self.env().caller() != self.owner {
+                return Err(OwnableError::CallerIsNotOwner.into());
+            }
+            psp22::non_reentrant!(self.guard, {
+                let (surplus, events) = self.wrapper.recover(&mut self.data, account)?;
+                self.emit_events(events);
+                Ok(surplus)
+            })
+        }
+
+        /// Returns the gas limit of the calls to the underlying token, `0` for no limit.
+        #[ink(message)]
+        pub fn call_gas_limit(&self) -> u64 {
+            self.wrapper.call_gas_limit()
+        }
+
+        /// Sets the gas limit of the calls to the underlying token, `0` for no limit.
+        /// Only the owner can set the limit.
+        #[ink(message)]
+        pub fn set_call_gas_limit(&mut self, limit: u64) -> Result<(), PSP22Error> {
+            if self.env().caller() != self.owner {
+                return Err(OwnableError::CallerIsNotOwner.into());
+            }
+            let events = self.wrapper.set_call_gas_limit(limit);
+            self.emit_events(events);
+            Ok(())
+        }
+    }
+
+    impl EmitsPSP22Events for WrappedToken {}
+
+    impl PSP22 for WrappedToken {
+        #[ink(message)]
+        fn total_supply(&self) -> u128 {
+            self.data.total_supply()
+        }
+
+        #[ink(message)]
+        fn balance_of(&self, owner: AccountId) -> u128 {
+            self.data.balance_of(owner)
+        }
+
+        #[ink(message)]
+        fn allowance(&self, owner: AccountId, spender: AccountId) -> u128 {
+            self.data.allowance(owner, spender)
+        }
+
+        #[ink(message)]
+        fn transfer(
+            &mut self,
+            to: AccountId,
+            value: u128,
+            data: Vec<u8>,
+        ) -> Result<(), PSP22Error> {
+            let events = self
+                .data
+                .transfer_with_data(self.env().caller(), to, value, data)?;
+            self.emit_events(events);
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn transfer_from(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            value: u128,
+            data: Vec<u8>,
+        ) -> Result<(), PSP22Error> {
+            let events =
+                self.data
+                    .transfer_from_with_data(self.env().caller(), from, to, value, data)?;
+            self.emit_events(events);
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn approve(&mut self, spender: AccountId, value: u128) -> Result<(), PSP22Error> {
+            let events = self.data.approve(self.env().caller(), spender, value)?;
+            self.emit_events(events);
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn increase_allowance(
+            &mut self,
+            spender: AccountId,
+            delta_value: u128,
+        ) -> Result<(), PSP22Error> {
+            let events = self
+                .data
+                .increase_allowance(self.env().caller(), spender, delta_value)?;
+            self.emit_events(events);
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn decrease_allowance(
+            &mut self,
+            spender: AccountId,
+            delta_value: u128,
+        ) -> Result<(), PSP22Error> {
+            let events = self
+                .data
+                .decrease_allowance(self.env().caller(), spender, delta_value)?;
+            self.emit_events(events);
+            Ok(())
+        }
+    }
+
+    impl PSP22Wrapper for WrappedToken {
+        #[ink(message)]
+        fn deposit_for(&mut self, account: AccountId, value: u128) -> Result<(), PSP22Error> {
+            psp22::non_reentrant!(self.guard, {
+                let events =
+                    self.wrapper
+                        .deposit(&mut self.data, self.env().caller(), account, value)?;
+                self.emit_events(events);
+                Ok(())
+            })
+        }
+
+        #[ink(message)]
+        fn withdraw_to(&mut self, account: AccountId, value: u128) -> Result<(), PSP22Error> {
+            psp22::non_reentrant!(self.guard, {
+                let events =
+                    self.wrapper
+                        .withdraw(&mut self.data, self.env().caller(), account, value)?;
+                self.emit_events(events);
+                Ok(())
+            })
+        }
+
+        #[ink(message)]
+        fn recover_underlying(&mut self, to: AccountId) -> Result<u128, PSP22Error> {
+            if self.env().caller() != self.owner {
+                return Err(OwnableError::CallerIsNotOwner.into());
+            }
+            psp22::non_reentrant!(self.guard, {
+                self.wrapper.recover_underlying(&self.data, to)
+            })
+        }
+
+        #[ink(message)]
+        fn underlying(&self) -> AccountId {
+            self.wrapper.underlying()
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink::env::{test::*, DefaultEnvironment as E};
+
+        #[ink::test]
+        fn underlying_is_the_configured_token() {
+            let acc = default_accounts::<E>();
+            let wrapper = WrappedToken::new(acc.django);
+
+            assert_eq!(wrapper.underlying(), acc.django);
+        }
+
+        #[ink::test]
+        fn recover_by_non_owner_fails() {
+            let acc = default_accounts::<E>();
+            set_caller::<E>(acc.alice);
+            let mut wrapper = WrappedToken::new(acc.django);
+
+            set_caller::<E>(acc.bob);
+            assert_eq!(
+                wrapper.recover_underlying(acc.bob),
+                Err(OwnableError::CallerIsNotOwner.into())
+            );
+            assert_eq!(
+                wrapper.recover(acc.bob),
+                Err(OwnableError::CallerIsNotOwner.into())
+            );
+        }
+
+        #[ink::test]
+        fn call_gas_limit_is_set_by_owner() {
+            let acc = default_accounts::<E>();
+            set_caller::<E>(acc.alice);
+            let mut wrapper = WrappedToken::new(acc.django);
+            assert_eq!(wrapper.call_gas_limit(), 0);
+
+            set_caller::<E>(acc.bob);
+            assert_eq!(
+                wrapper.set_call_gas_limit(1),
+                Err(OwnableError::CallerIsNotOwner.into())
+            );
+            set_caller::<E>(acc.alice);
+            assert!(wrapper.set_call_gas_limit(1_000_000).is_ok());
+            assert_eq!(wrapper.call_gas_limit(), 1_000_000);
+        }
+
+        #[ink::test]
+        fn guarded_messages_reject_nested_calls() {
+            let acc = default_accounts::<E>();
+            let mut wrapper = WrappedToken::new(acc.django);
+
+            // As if called back by the underlying token in the middle of a withdrawal
+            wrapper.guard.enter().unwrap();
+            let detected = Err(PSP22Error::custom("ReentrancyDetected"));
+            assert_eq!(wrapper.deposit_for(acc.alice, 100), detected);
+            assert_eq!(wrapper.withdraw_to(acc.alice, 100), detected);
+            assert_eq!(
+                wrapper.recover_underlying(acc.alice),
+                Err(PSP22Error::custom("ReentrancyDetected"))
+            );
+            assert_eq!(wrapper.recover(acc.alice), detected);
+        }
+    }
+
+    #[cfg(all(test, feature = "e2e-tests"))]
+    mod e2e_tests {
+        use super::*;
+        use ink_e2e::{account_id, AccountKeyring, ContractsBackend};
+        use mock_token::{MockToken, MockTokenRef};
+
+        type E2EResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+        // Evaluates to (underlying tokens held by the wrapper, total supply of the wrapper),
+        // checking that the former backs the latter.
+        macro_rules! backing {
+            ($client:ident, $token_call:ident, $wrapper:ident, $wrapper_call:ident) => {{
+                let held = $client
+                    .call(
+                        &ink_e2e::alice(),
+                        &$token_call.balance_of($wrapper.account_id),
+                    )
+                    .dry_run()
+                    .await?
+                    .return_value();
+                let supply = $client
+                    .call(&ink_e2e::alice(), &$wrapper_call.total_supply())
+                    .dry_run()
+                    .await?
+                    .return_value();
+                assert!(
+                    held >= supply,
+                    "{held} underlying tokens back {supply} tokens"
+                );
+                (held, supply)
+            }};
+        }
+
+        #[ink_e2e::test]
+        async fn deposits_and_withdrawals_keep_backing<Client: E2EBackend>(
+            mut client: Client,
+        ) -> E2EResult<()> {
+            let bob = account_id(AccountKeyring::Bob);
+
+            let token = client
+                .instantiate(
+                    "mock_token",
+                    &ink_e2e::alice(),
+                    &mut MockTokenRef::new(1000),
+                )
+                .submit()
+                .await
+                .expect("token instantiation failed");
+            let mut token_call = token.call_builder::<MockToken>();
+            let wrapper = client
+                .instantiate(
+                    "wrapped_token",
+                    &ink_e2e::alice(),
+                    &mut WrappedTokenRef::new(token.account_id),
+                )
+                .submit()
+                .await
+                .expect("wrapper instantiation failed");
+            let mut wrapper_call = wrapper.call_builder::<WrappedToken>();
+
+            client
+                .call(
+                    &ink_e2e::alice(),
+                    &token_call.approve(wrapper.account_id, 500),
+                )
+                .submit()
+                .await
+                .expect("approve failed");
+            client
+                .call(&ink_e2e::alice(), &wrapper_call.deposit_for(bob, 500))
+                .submit()
+                .await
+                .expect("deposit failed");
+            assert_eq!(
+                backing!(client, token_call, wrapper, wrapper_call),
+                (500, 500)
+            );
+
+            // Withdrawing more than the wrapped balance fails
+            let result = client
+                .call(&ink_e2e::bob(), &wrapper_call.withdraw_to(bob, 501))
+                .dry_run()
+                .await?;
+            assert_eq!(result.return_value(), Err(PSP22Error::InsufficientBalance));
+            client
+                .call(&ink_e2e::bob(), &wrapper_call.withdraw_to(bob, 200))
+                .submit()
+                .await
+                .expect("withdraw failed");
+            assert_eq!(
+                backing!(client, token_call, wrapper, wrapper_call),
+                (300, 300)
+            );
+            let bob_underlying = client
+                .call(&ink_e2e::alice(), &token_call.balance_of(bob))
+                .dry_run()
+                .await?
+                .return_value();
+            assert_eq!(bob_underlying, 200);
+            Ok(())
+        }
+
+        #[ink_e2e::test]
+        async fn deposit_and_withdraw_round_trip<Client: E2EBackend>(
+            mut client: Client,
+        ) -> E2EResult<()> {
+            let alice = account_id(AccountKeyring::Alice);
+
+            let token = client
+                .instantiate(
+                    "mock_token",
+                    &ink_e2e::alice(),
+                    &mut MockTokenRef::new(1000),
+                )
+                .submit()
+                .await
+                .expect("token instantiation failed");
+            let mut token_call = token.call_builder::<MockToken>();
+            let wrapper = client
+                .instantiate(
+                    "wrapped_token",
+                    &ink_e2e::alice(),
+                    &mut WrappedTokenRef::new(token.account_id),
+                )
+                .submit()
+                .await
+                .expect("wrapper instantiation failed");
+            let mut wrapper_call = wrapper.call_builder::<WrappedToken>();
+
+            client
+                .call(
+                    &ink_e2e::alice(),
+                    &token_call.approve(wrapper.account_id, 400),
+                )
+                .submit()
+                .await
+                .expect("approve failed");
+            client
+                .call(&ink_e2e::alice(), &wrapper_call.deposit_for(alice, 400))
+                .submit()
+                .await
+                .expect("deposit failed");
+            // The whole allowance is used by `transfer_from`
+            let allowance = client
+                .call(
+                    &ink_e2e::alice(),
+                    &token_call.allowance(alice, wrapper.account_id),
+                )
+                .dry_run()
+                .await?
+                .return_value();
+            assert_eq!(allowance, 0);
+            client
+                .call(&ink_e2e::alice(), &wrapper_call.withdraw_to(alice, 400))
+                .submit()
+                .await
+                .expect("withdraw failed");
+
+            assert_eq!(backing!(client, token_call, wrapper, wrapper_call), (0, 0));
+            let alice_underlying = client
+                .call(&ink_e2e::alice(), &token_call.balance_of(alice))
+                .dry_run()
+                .await?
+                .return_value();
+            assert_eq!(alice_underlying, 1000);
+            Ok(())
+        }
+
+        #[ink_e2e::test]
+        async fn deposit_fails_with_too_low_call_gas_limit<Client: E2EBackend>(
+            mut client: Client,
+        ) -> E2EResult<()> {
+            let alice = account_id(AccountKeyring::Alice);
+
+            let token = client
+                .instantiate(
+                    "mock_token",
+                    &ink_e2e::alice(),
+                    &mut MockTokenRef::new(1000),
+                )
+                .submit()
+                .await
+                .expect("token instantiation failed");
+            let mut token_call = token.call_builder::<MockToken>();
+            let wrapper = client
+                .instantiate(
+                    "wrapped_token",
+                    &ink_e2e::alice(),
+                    &mut WrappedTokenRef::new(token.account_id),
+                )
+                .submit()
+                .await
+                .expect("wrapper instantiation failed");
+            let mut wrapper_call = wrapper.call_builder::<WrappedToken>();
+            client
+                .call(
+                    &ink_e2e::alice(),
+                    &token_call.approve(wrapper.account_id, 100),
+                )
+                .submit()
+                .await
+                .expect("approve failed");
+
+            // Far too little for `transfer_from` of the underlying token
+            client
+                .call(&ink_e2e::alice(), &wrapper_call.set_call_gas_limit(1))
+                .submit()
+                .await
+                .expect("setting gas limit failed");
+            let result = client
+                .call(&ink_e2e::alice(), &wrapper_call.deposit_for(alice, 100))
+                .dry_run()
+                .await?;
+            assert_eq!(result.return_value(), Err(PSP22Error::UnderlyingCallFailed));
+
+            client
+                .call(
+                    &ink_e2e::alice(),
+                    &wrapper_call.set_call_gas_limit(50_000_000_000),
+                )
+                .submit()
+                .await
+                .expect("setting gas limit failed");
+            client
+                .call(&ink_e2e::alice(), &wrapper_call.deposit_for(alice, 100))
+                .submit()
+                .await
+                .expect("deposit failed");
+            assert_eq!(
+                backing!(client, token_call, wrapper, wrapper_call),
+                (100, 100)
+            );
+            Ok(())
+        }
+
+        #[ink_e2e::test]
+        async fn donated_surplus_is_minted_by_recover<Client: E2EBackend>(
+            mut client: Client,
+        ) -> E2EResult<()> {
+            let bob = account_id(AccountKeyring::Bob);
+            let charlie = account_id(AccountKeyring::Charlie);
+
+            let token = client
+                .instantiate(
+                    "mock_token",
+                    &ink_e2e::alice(),
+                    &mut MockTokenRef::new(1000),
+                )
+                .submit()
+                .await
+                .expect("token instantiation failed");
+            let mut token_call = token.call_builder::<MockToken>();
+            let wrapper = client
+                .instantiate(
+                    "wrapped_token",
+                    &ink_e2e::alice(),
+                    &mut WrappedTokenRef::new(token.account_id),
+                )
+                .submit()
+                .await
+                .expect("wrapper instantiation failed");
+            let mut wrapper_call = wrapper.call_builder::<WrappedToken>();
+
+            client
+                .call(
+                    &ink_e2e::alice(),
+                    &token_call.approve(wrapper.account_id, 300),
+                )
+                .submit()
+                .await
+                .expect("approve failed");
+            client
+                .call(&ink_e2e::alice(), &wrapper_call.deposit_for(charlie, 300))
+                .submit()
+                .await
+                .expect("deposit failed");
+            // Underlying tokens sent directly, bypassing `deposit_for`
+            client
+                .call(
+                    &ink_e2e::alice(),
+                    &token_call.transfer(wrapper.account_id, 50, vec![]),
+                )
+                .submit()
+                .await
+                .expect("transfer failed");
+            assert_eq!(
+                backing!(client, token_call, wrapper, wrapper_call),
+                (350, 300)
+            );
+
+            let result = client
+                .call(&ink_e2e::bob(), &wrapper_call.recover(bob))
+                .dry_run()
+                .await?;
+            assert!(result.return_value().is_err());
+            let recovered = client
+                .call(&ink_e2e::alice(), &wrapper_call.recover(bob))
+                .submit()
+                .await
+                .expect("recover failed")
+                .return_value();
+            assert_eq!(recovered, Ok(50));
+            assert_eq!(
+                backing!(client, token_call, wrapper, wrapper_call),
+                (350, 350)
+            );
+            let recovered = client
+                .call(&ink_e2e::alice(), &wrapper_call.recover(bob))
+                .dry_run()
+                .await?
+                .return_value();
+            assert_eq!(recovered, Ok(0));
+
+            // The recovered tokens are fully backed
+            client
+                .call(&ink_e2e::bob(), &wrapper_call.withdraw_to(bob, 50))
+                .submit()
+                .await
+                .expect("withdraw failed");
+            assert_eq!(
+                backing!(client, token_call, wrapper, wrapper_call),
+                (300, 300)
+            );
+            let bob_underlying = client
+                .call(&ink_e2e::alice(), &token_call.balance_of(bob))
+                .dry_run()
+                .await?
+                .return_value();
+            assert_eq!(bob_underlying, 50);
+            Ok(())
+        }
+
+        #[ink_e2e::test]
+        async fn surplus_is_recovered_without_breaking_backing<Client: E2EBackend>(
+            mut client: Client,
+        ) -> E2EResult<()> {
+            let bob = account_id(AccountKeyring::Bob);
+            let charlie = account_id(AccountKeyring::Charlie);
+
+            let token = client
+                .instantiate(
+                    "mock_token",
+                    &ink_e2e::alice(),
+                    &mut MockTokenRef::new(1000),
+                )
+                .submit()
+                .await
+                .expect("token instantiation failed");
+            let mut token_call = token.call_builder::<MockToken>();
+            let wrapper = client
+                .instantiate(
+                    "wrapped_token",
+                    &ink_e2e::alice(),
+                    &mut WrappedTokenRef::new(token.account_id),
+                )
+                .submit()
+                .await
+                .expect("wrapper instantiation failed");
+            let mut wrapper_call = wrapper.call_builder::<WrappedToken>();
+
+            client
+                .call(
+                    &ink_e2e::alice(),
+                    &token_call.approve(wrapper.account_id, 300),
+                )
+                .submit()
+                .await
+                .expect("approve failed");
+            client
+                .call(&ink_e2e::alice(), &wrapper_call.deposit_for(charlie, 300))
+                .submit()
+                .await
+                .expect("deposit failed");
+            // Underlying tokens sent directly, bypassing `deposit_for`
+            client
+                .call(
+                    &ink_e2e::alice(),
+                    &token_call.transfer(wrapper.account_id, 50, vec![]),
+                )
+                .submit()
+                .await
+                .expect("transfer failed");
+
+            let recovered = client
+                .call(&ink_e2e::bob(), &wrapper_call.recover_underlying(bob))
+                .dry_run()
+                .await?
+                .return_value();
+            assert!(recovered.is_err());
+            let recovered = client
+                .call(&ink_e2e::alice(), &wrapper_call.recover_underlying(bob))
+                .submit()
+                .await
+                .expect("recover failed")
+                .return_value();
+            assert_eq!(recovered, Ok(50));
+            let recovered = client
+                .call(&ink_e2e::alice(), &wrapper_call.recover_underlying(bob))
+                .dry_run()
+                .await?
+                .return_value();
+            assert_eq!(recovered, Ok(0));
+
+            let held = client
+                .call(
+                    &ink_e2e::alice(),
+                    &token_call.balance_of(wrapper.account_id),
+                )
+                .dry_run()
+                .await?
+                .return_value();
+            let supply = client
+                .call(&ink_e2e::alice(), &wrapper_call.total_supply())
+                .dry_run()
+                .await?
+                .return_value();
+            let bob_balance = client
+                .call(&ink_e2e::alice(), &token_call.balance_of(bob))
+                .dry_run()
+                .await?
+                .return_value();
+            assert_eq!((held, supply, bob_balance), (300, 300, 50));
+
+            client
+                .call(&ink_e2e::charlie(), &wrapper_call.withdraw_to(charlie, 100))
+                .submit()
+                .await
+                .expect("withdraw failed");
+            let held = client
+                .call(
+                    &ink_e2e::alice(),
+                    &token_call.balance_of(wrapper.account_id),
+                )
+                .dry_run()
+                .await?
+                .return_value();
+            let charlie_balance = client
+                .call(&ink_e2e::alice(), &token_call.balance_of(charlie))
+                .dry_run()
+                .await?
+                .return_value();
+            assert_eq!((held, charlie_balance), (200, 100));
+            Ok(())
+        }
+
+        #[ink_e2e::test]
+        async fn reentrant_withdrawal_is_blocked<Client: E2EBackend>(
+            mut client: Client,
+        ) -> E2EResult<()> {
+            let charlie = account_id(AccountKeyring::Charlie);
+
+            let token = client
+                .instantiate(
+                    "mock_token",
+                    &ink_e2e::alice(),
+                    &mut MockTokenRef::new(1000),
+                )
+                .submit()
+                .await
+                .expect("token instantiation failed");
+            let mut token_call = token.call_builder::<MockToken>();
+            let wrapper = client
+                .instantiate(
+                    "wrapped_token",
+                    &ink_e2e::alice(),
+                    &mut WrappedTokenRef::new(token.account_id),
+                )
+                .submit()
+                .await
+                .expect("wrapper instantiation failed");
+            let mut wrapper_call = wrapper.call_builder::<WrappedToken>();
+
+            client
+                .call(
+                    &ink_e2e::alice(),
+                    &token_call.approve(wrapper.account_id, 400),
+                )
+                .submit()
+                .await
+                .expect("approve failed");
+            // The malicious token holds wrapped tokens it will try to withdraw
+            // in the middle of Charlie's withdrawal
+            for (account, value) in [(charlie, 300), (token.account_id, 100)] {
+                client
+                    .call(&ink_e2e::alice(), &wrapper_call.deposit_for(account, value))
+                    .submit()
+                    .await
+                    .expect("deposit failed");
+            }
+            client
+                .call(
+                    &ink_e2e::alice(),
+                    &token_call.set_reentry_target(Some(wrapper.account_id)),
+                )
+                .submit()
+                .await
+                .expect("setting reentry target failed");
+
+            client
+                .call(&ink_e2e::charlie(), &wrapper_call.withdraw_to(charlie, 100))
+                .submit()
+                .await
+                .expect("withdraw failed");
+
+            // Only Charlie's withdrawal went through
+            let held = client
+                .call(
+                    &ink_e2e::alice(),
+                    &token_call.balance_of(wrapper.account_id),
+                )
+                .dry_run()
+                .await?
+                .return_value();
+            let supply = client
+                .call(&ink_e2e::alice(), &wrapper_call.total_supply())
+                .dry_run()
+                .await?
+                .return_value();
+            let attacker_balance = client
+                .call(
+                    &ink_e2e::alice(),
+                    &wrapper_call.balance_of(token.account_id),
+                )
+                .dry_run()
+                .await?
+                .return_value();
+            let charlie_balance = client
+                .call(&ink_e2e::alice(), &token_call.balance_of(charlie))
+                .dry_run()
+                .await?
+                .return_value();
+            assert_eq!(
+                (held, supply, attacker_balance, charlie_balance),
+                (300, 300, 100, 100)
+            );
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,86 @@
+//! Identifiers of PSP22 extensions, reported by `PSP22Introspection::supports_extension`.
+//!
+//! Each identifier is the first 4 bytes of `blake2b_256` of the extension name,
+//! e.g. `blake2b_256("PSP22Metadata")` for `METADATA`.
+
+/// Identifier of the `PSP22Metadata` extension.
+pub const METADATA: [u8; 4] = [0x09, 0x75, 0xa9, 0x59];
+/// Identifier of the `PSP22Mintable` extension.
+pub const MINTABLE: [u8; 4] = [0x4c, 0xbb, 0xd0, 0x8b];
+/// Identifier of the `PSP22Burnable` extension.
+pub const BURNABLE: [u8; 4] = [0x25, 0xf7, 0x67, 0xa1];
+/// Identifier of the `PSP22Pausable` extension.
+pub const PAUSABLE: [u8; 4] = [0x72, 0x6b, 0x09, 0x37];
+/// Identifier of the `PSP22Wrapper` extension.
+pub const WRAPPER: [u8; 4] = [0x17, 0xf3, 0xe4, 0x06];
+/// Identifier of the `PSP22Vesting` extension.
+pub const VESTING: [u8; 4] = [0xb1, 0x52, 0x1c, 0xe0];
+/// Identifier of the `PSP22Airdrop` extension.
+pub const AIRDROP: [u8; 4] = [0x14, 0xc1, 0xff, 0x75];
+/// Identifier of the `PSP22Timelock` extension.
+pub const TIMELOCK: [u8; 4] = [0xa9, 0x06, 0x78, 0xf7];
+/// Identifier of the `PSP22Whitelist` extension.
+pub const WHITELIST: [u8; 4] = [0xb1, 0x60, 0xc2, 0xf6];
+/// Identifier of the `PSP22Bridgeable` extension.
+pub const BRIDGEABLE: [u8; 4] = [0x74, 0xaa, 0xd1, 0xa3];
+/// Identifier of the `PSP22Blacklist` extension.
+pub const BLACKLIST: [u8; 4] = [0x4c, 0xd0, 0x03, 0x3c];
+/// Identifier of the `PSP22AllowanceEnumerable` extension.
+pub const ALLOWANCE_ENUMERABLE: [u8; 4] = [0xb0, 0xf4, 0x69, 0x95];
+/// Identifier of the `PSP22Capped` extension.
+pub const CAPPED: [u8; 4] = [0xf1, 0x02, 0x80, 0x54];
+/// Identifier of the `PSP22MetaTx` extension.
+pub const META_TX: [u8; 4] = [0xaf, 0xd6, 0x8a, 0x83];
+/// Identifier of the `PSP22Domain` extension.
+pub const DOMAIN: [u8; 4] = [0xa3, 0x1b, 0x08, 0x61];
+/// Identifier of the `PSP22Multicall` extension.
+pub const MULTICALL: [u8; 4] = [0x5c, 0x5c, 0xb2, 0xa8];
+/// Identifier of the `PSP22Restrictable` extension.
+pub const RESTRICTABLE: [u8; 4] = [0xe4, 0x9b, 0xe5, 0x9c];
+/// Identifier of the `PSP22BatchApprove` extension.
+pub const BATCH_APPROVE: [u8; 4] = [0xe9, 0x07, 0x78, 0xb7];
+/// Identifier of the `Ownable` extension (a single owner allowed to call privileged messages).
+pub const OWNABLE: [u8; 4] = [0xcd, 0x90, 0xd2, 0xa3];
+/// Identifier of the `AccessControl` extension (roles allowed to call privileged messages).
+pub const ACCESS_CONTROL: [u8; 4] = [0x40, 0x9e, 0xf1, 0x10];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink::env::hash::{Blake2x256, HashOutput};
+
+    fn blake2b_prefix(input: &[u8]) -> [u8; 4] {
+        let mut output = <Blake2x256 as HashOutput>::Type::default();
+        ink::env::hash_bytes::<Blake2x256>(input, &mut output);
+        [output[0], output[1], output[2], output[3]]
+    }
+
+    #[test]
+    fn ids_match_hashes() {
+        let cases = [
+            ("PSP22Metadata", METADATA),
+            ("PSP22Mintable", MINTABLE),
+            ("PSP22Burnable", BURNABLE),
+            ("PSP22Pausable", PAUSABLE),
+            ("PSP22Wrapper", WRAPPER),
+            ("PSP22Vesting", VESTING),
+            ("PSP22Airdrop", AIRDROP),
+            ("PSP22Timelock", TIMELOCK),
+            ("PSP22Whitelist", WHITELIST),
+            ("PSP22Bridgeable", BRIDGEABLE),
+            ("PSP22Blacklist", BLACKLIST),
+            ("PSP22AllowanceEnumerable", ALLOWANCE_ENUMERABLE),
+            ("PSP22Capped", CAPPED),
+            ("PSP22MetaTx", META_TX),
+            ("PSP22Domain", DOMAIN),
+            ("PSP22Multicall", MULTICALL),
+            ("PSP22Restrictable", RESTRICTABLE),
+            ("PSP22BatchApprove", BATCH_APPROVE),
+            ("Ownable", OWNABLE),
+            ("AccessControl", ACCESS_CONTROL),
+        ];
+        for (name, id) in cases {
+            assert_eq!(blake2b_prefix(name.as_bytes()), id, "{name}");
+        }
+    }
+}
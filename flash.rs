@@ -0,0 +1,59 @@
+use crate::errors::PSP22Error;
+use ink::{prelude::vec::Vec, primitives::AccountId};
+
+/// Value which must be returned by `PSP22FlashBorrower::on_flash_loan` for the loan to succeed:
+/// first 4 bytes of `blake2b_256("PSP22FlashBorrower.onFlashLoan")`.
+pub const CALLBACK_SUCCESS: [u8; 4] = [0x40, 0x7d, 0x3f, 0xda];
+
+// Selector of `PSP22FlashBorrower::on_flash_loan`, called by the lender.
+pub(crate) const ON_FLASH_LOAN_SELECTOR: [u8; 4] = [0x80, 0x61, 0xa7, 0x9e];
+
+/// Receiver of flash loans made by contracts implementing `PSP22FlashLender`, following ERC-3156.
+#[ink::trait_definition]
+pub trait PSP22FlashBorrower {
+    /// Receives a flash loan of `amount` of `token`, initiated by `initiator`.
+    ///
+    /// Called by the lender after the loaned tokens are transferred to the borrower.
+    /// Before returning, the borrower must approve the lender to spend `amount + fee`
+    /// of `token`, which is taken back right after the call.
+    ///
+    /// `data` is passed unchanged from the `PSP22FlashLender::flash_loan` call.
+    ///
+    /// The selector for this message is `0x8061a79e` (first 4 bytes of `blake2b_256("PSP22FlashBorrower::on_flash_loan")`).
+    ///
+    /// Must return `CALLBACK_SUCCESS`, otherwise the whole loan is reverted
+    /// with `PSP22Error::FlashLoanFailed`.
+    #[ink(message)]
+    fn on_flash_loan(
+        &mut self,
+        initiator: AccountId,
+        token: AccountId,
+        amount: u128,
+        fee: u128,
+        data: Vec<u8>,
+    ) -> Result<[u8; 4], PSP22Error>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink::env::hash::{Blake2x256, HashOutput};
+
+    fn blake2b_prefix(input: &[u8]) -> [u8; 4] {
+        let mut output = <Blake2x256 as HashOutput>::Type::default();
+        ink::env::hash_bytes::<Blake2x256>(input, &mut output);
+        [output[0], output[1], output[2], output[3]]
+    }
+
+    #[test]
+    fn constants_match_hashes() {
+        assert_eq!(
+            blake2b_prefix(b"PSP22FlashBorrower.onFlashLoan"),
+            CALLBACK_SUCCESS
+        );
+        assert_eq!(
+            blake2b_prefix(b"PSP22FlashBorrower::on_flash_loan"),
+            ON_FLASH_LOAN_SELECTOR
+        );
+    }
+}
@@ -0,0 +1,271 @@
+//! Generic implementations of PSP22 messages for contracts holding `PSP22Data`.
+//!
+//! A contract implementing `HasPSP22Data` and `EmitsPSP22Events` can implement
+//! each PSP22 message with a one-line call to the corresponding function, e.g.:
+//!
+//! ```ignore
+//! #[ink(message)]
+//! fn transfer(&mut self, to: AccountId, value: u128, data: Vec<u8>) -> Result<(), PSP22Error> {
+//!     psp22::impls::transfer(self, to, value, data)
+//! }
+//! ```
+//!
+//! The caller of the message is obtained from the environment.
+
+use crate::data::{EventSink, PSP22Data, PSP22Event};
+use crate::errors::PSP22Error;
+use crate::events::Transfer;
+use crate::traits::PSP22Restrictable;
+use ink::{
+    env::{ContractEnv, DefaultEnvironment, Environment},
+    prelude::vec::Vec,
+    primitives::AccountId,
+};
+
+/// Gives access to the `PSP22Data` stored in a contract.
+pub trait HasPSP22Data {
+    fn data(&self) -> &PSP22Data;
+    fn data_mut(&mut self) -> &mut PSP22Data;
+}
+
+/// Emits events resulting from operations on `PSP22Data`.
+///
+/// The default implementation emits each event with `emit_event` in the environment
+/// of the contract, which is sufficient for most contracts:
+/// `impl EmitsPSP22Events for Contract {}`.
+pub trait EmitsPSP22Events: ContractEnv {
+    fn emit_events(&self, events: Vec<PSP22Event>) {
+        for event in events {
+            emit_event::<Self::Env>(event);
+        }
+    }
+}
+
+/// Emits a single event resulting from an operation on `PSP22Data` with `ink::env::emit_event`
+/// in the environment `E`, mapping `Minted` and `Burned` to standard `Transfer` events.
+///
+/// Used by `EmitsPSP22Events` as well as by the `emit_events` helpers generated
+/// by `impl_psp22!` and `#[psp22::implementation]`.
+pub fn emit_event<E: Environment>(event: PSP22Event) {
+    match event {
+        PSP22Event::Transfer(e) => emit::<E, _>(e),
+        PSP22Event::Approval(e) => emit::<E, _>(e),
+        PSP22Event::PermitUsed(e) => emit::<E, _>(e),
+        PSP22Event::TransferWithData(e) => emit::<E, _>(e),
+        PSP22Event::Minted { to, value } => emit::<E, _>(Transfer {
+            from: None,
+            to: Some(to),
+            value,
+        }),
+        PSP22Event::Burned { from, value } => emit::<E, _>(Transfer {
+            from: Some(from),
+            to: None,
+            value,
+        }),
+        PSP22Event::SnapshotCreated(e) => emit::<E, _>(e),
+        PSP22Event::DelegateChanged(e) => emit::<E, _>(e),
+        PSP22Event::DelegateVotesChanged(e) => emit::<E, _>(e),
+        PSP22Event::FlashLoan(e) => emit::<E, _>(e),
+        PSP22Event::VestingScheduleCreated(e) => emit::<E, _>(e),
+        PSP22Event::TokensReleased(e) => emit::<E, _>(e),
+        PSP22Event::AirdropClaimed(e) => emit::<E, _>(e),
+        PSP22Event::MintQueued(e) => emit::<E, _>(e),
+        PSP22Event::MintCancelled(e) => emit::<E, _>(e),
+        PSP22Event::AddedToWhitelist(e) => emit::<E, _>(e),
+        PSP22Event::RemovedFromWhitelist(e) => emit::<E, _>(e),
+        PSP22Event::BridgedIn(e) => emit::<E, _>(e),
+        PSP22Event::BridgedOut(e) => emit::<E, _>(e),
+        PSP22Event::AddedToBlacklist(e) => emit::<E, _>(e),
+        PSP22Event::RemovedFromBlacklist(e) => emit::<E, _>(e),
+        PSP22Event::OwnershipTransferred(e) => emit::<E, _>(e),
+        PSP22Event::RoleGranted(e) => emit::<E, _>(e),
+        PSP22Event::RoleRevoked(e) => emit::<E, _>(e),
+        PSP22Event::CallGasLimitUpdated(e) => emit::<E, _>(e),
+        PSP22Event::StorageVersion(e) => emit::<E, _>(e),
+    }
+}
+
+/// An `EventSink` emitting each event right away with `emit_event`, for contracts
+/// using `DefaultEnvironment`.
+///
+/// Passing `&mut EnvEventSink` to the `_with_sink` methods of `PSP22Data` emits events
+/// directly, without collecting them in a vector first:
+///
+/// ```ignore
+/// let caller = self.env().caller();
+/// self.data.transfer_with_sink(caller, to, value, data, &mut psp22::impls::EnvEventSink)?;
+/// ```
+pub struct EnvEventSink;
+
+impl EventSink for EnvEventSink {
+    fn emit(&mut self, event: PSP22Event) {
+        emit_event::<DefaultEnvironment>(event);
+    }
+}
+
+fn emit<E: Environment, Ev: ink::env::Event>(event: Ev) {
+    ink::env::emit_event::<E, Ev>(event)
+}
+
+fn caller() -> AccountId {
+    ink::env::caller::<DefaultEnvironment>()
+}
+
+// Emits the events of a successful operation and drops them from the result.
+fn emitting<T: EmitsPSP22Events>(
+    contract: &T,
+    result: Result<Vec<PSP22Event>, PSP22Error>,
+) -> Result<(), PSP22Error> {
+    contract.emit_events(result?);
+    Ok(())
+}
+
+/// Implements `PSP22::total_supply`.
+pub fn total_supply<T: HasPSP22Data>(contract: &T) -> u128 {
+    contract.data().total_supply()
+}
+
+/// Implements `PSP22::balance_of`.
+pub fn balance_of<T: HasPSP22Data>(contract: &T, owner: AccountId) -> u128 {
+    contract.data().balance_of(owner)
+}
+
+/// Implements `PSP22::allowance`.
+pub fn allowance<T: HasPSP22Data>(contract: &T, owner: AccountId, spender: AccountId) -> u128 {
+    contract.data().allowance(owner, spender)
+}
+
+/// Implements `PSP22::transfer`.
+pub fn transfer<T: HasPSP22Data + EmitsPSP22Events>(
+    contract: &mut T,
+    to: AccountId,
+    value: u128,
+    data: Vec<u8>,
+) -> Result<(), PSP22Error> {
+    let result = contract
+        .data_mut()
+        .transfer_with_data(caller(), to, value, data);
+    emitting(contract, result)
+}
+
+/// Implements `PSP22::transfer_from`.
+pub fn transfer_from<T: HasPSP22Data + EmitsPSP22Events>(
+    contract: &mut T,
+    from: AccountId,
+    to: AccountId,
+    value: u128,
+    data: Vec<u8>,
+) -> Result<(), PSP22Error> {
+    let result = contract
+        .data_mut()
+        .transfer_from_with_data(caller(), from, to, value, data);
+    emitting(contract, result)
+}
+
+/// Implements `PSP22::approve`.
+pub fn approve<T: HasPSP22Data + EmitsPSP22Events>(
+    contract: &mut T,
+    spender: AccountId,
+    value: u128,
+) -> Result<(), PSP22Error> {
+    let result = contract.data_mut().approve(caller(), spender, value);
+    emitting(contract, result)
+}
+
+/// Implements `PSP22::increase_allowance`.
+pub fn increase_allowance<T: HasPSP22Data + EmitsPSP22Events>(
+    contract: &mut T,
+    spender: AccountId,
+    delta_value: u128,
+) -> Result<(), PSP22Error> {
+    let result = contract
+        .data_mut()
+        .increase_allowance(caller(), spender, delta_value);
+    emitting(contract, result)
+}
+
+/// Implements `PSP22::decrease_allowance`.
+pub fn decrease_allowance<T: HasPSP22Data + EmitsPSP22Events>(
+    contract: &mut T,
+    spender: AccountId,
+    delta_value: u128,
+) -> Result<(), PSP22Error> {
+    let result = contract
+        .data_mut()
+        .decrease_allowance(caller(), spender, delta_value);
+    emitting(contract, result)
+}
+
+/// Implements `PSP22BatchApprove::batch_approve`.
+pub fn batch_approve<T: HasPSP22Data + EmitsPSP22Events>(
+    contract: &mut T,
+    approvals: Vec<(AccountId, u128)>,
+) -> Result<(), PSP22Error> {
+    let result = contract.data_mut().batch_approve(caller(), approvals);
+    emitting(contract, result)
+}
+
+/// Implements `PSP22AllowanceEnumerable::revoke_all`.
+pub fn revoke_all<T: HasPSP22Data + EmitsPSP22Events>(contract: &mut T) -> Result<(), PSP22Error> {
+    let result = contract.data_mut().revoke_all(caller());
+    emitting(contract, result)
+}
+
+/// Implements `PSP22Multicall::multicall`.
+///
+/// The calls are executed directly on `PSP22Data`, so contracts doing more than that
+/// in their `PSP22` messages (e.g. updating snapshots or votes, or checking a blacklist,
+/// a whitelist or locked balances) should not use it.
+pub fn multicall<T: HasPSP22Data + EmitsPSP22Events>(
+    contract: &mut T,
+    calls: Vec<Vec<u8>>,
+) -> Result<Vec<Vec<u8>>, PSP22Error> {
+    let (outputs, events) = contract.data_mut().multicall(caller(), calls)?;
+    contract.emit_events(events);
+    Ok(outputs)
+}
+
+/// Restriction code of a transfer which is not restricted, see `PSP22Restrictable`.
+pub const NO_RESTRICTION: u8 = 0;
+
+/// Checks `PSP22Restrictable::detect_transfer_restriction` of a transfer of `value` tokens
+/// from `from` to `to` by `caller`. To be called by the contract before every transfer,
+/// with the spender as `caller` in `transfer_from`.
+///
+/// Returns `Custom` error with the restriction code and its message (see
+/// `PSP22Error::restriction`) if the transfer is restricted.
+pub fn check_restriction<T: PSP22Restrictable>(
+    contract: &T,
+    caller: AccountId,
+    from: AccountId,
+    to: AccountId,
+    value: u128,
+) -> Result<(), PSP22Error> {
+    let code = contract.detect_transfer_restriction(caller, from, to, value);
+    if code == NO_RESTRICTION {
+        return Ok(());
+    }
+    Err(PSP22Error::restriction(
+        code,
+        &contract.message_for_restriction(code),
+    ))
+}
+
+/// Mints `value` tokens to `to`. Access control is up to the contract.
+pub fn mint<T: HasPSP22Data + EmitsPSP22Events>(
+    contract: &mut T,
+    to: AccountId,
+    value: u128,
+) -> Result<(), PSP22Error> {
+    let result = contract.data_mut().mint(to, value);
+    emitting(contract, result)
+}
+
+/// Burns `value` tokens of the caller.
+pub fn burn<T: HasPSP22Data + EmitsPSP22Events>(
+    contract: &mut T,
+    value: u128,
+) -> Result<(), PSP22Error> {
+    let result = contract.data_mut().burn(caller(), value);
+    emitting(contract, result)
+}
@@ -1,15 +1,95 @@
 #![cfg_attr(not(feature = "std"), no_std, no_main)]
 
+mod access_control;
+mod callers;
+mod clock;
+pub mod compat;
 mod data;
+mod data_airdrop;
+mod data_blacklist;
+mod data_bridge;
+mod data_flash;
+mod data_lockable;
+mod data_metatx;
+mod data_permit;
+mod data_snapshot;
+mod data_timelock;
+mod data_vesting;
+mod data_votes;
+mod data_whitelist;
+mod data_wrapper;
+mod domain;
 mod errors;
 mod events;
+pub mod extensions;
+mod flash;
+pub mod impls;
+mod macros;
+mod migration;
+mod multicall;
+mod nonces;
+mod ownable;
+mod reentrancy;
+pub mod selectors;
 mod testing;
 mod traits;
 
-pub use data::{PSP22Data, PSP22Event};
-pub use errors::PSP22Error;
-pub use events::{Approval, Transfer};
-pub use traits::{PSP22Burnable, PSP22Metadata, PSP22Mintable, PSP22};
+pub use access_control::{AccessControlData, RoleType, DEFAULT_ADMIN_ROLE};
+pub use callers::{
+    PSP22BurnableRef, PSP22Call, PSP22Contract, PSP22MetadataRef, PSP22MintableRef, PSP22Ref,
+};
+pub use clock::Clock;
+pub use data::{
+    ApprovalOutcome, EventSink, PSP22Data, PSP22Event, SupplyOutcome, TransferOutcome,
+    ALLOWANCES_KEY, ALLOWANCE_INDEX_KEY, BALANCES_KEY, MAX_ALLOWANCE_KEY, MAX_REVOKED_PER_CALL,
+    MAX_SPENDERS, MIN_SUPPLY_KEY, SELF_TRANSFER_EVENTS_KEY, SPENDERS_KEY, STORAGE_VERSION,
+    STORAGE_VERSION_KEY, TOTAL_BURNED_KEY, TOTAL_MINTED_KEY, TOTAL_SUPPLY_KEY,
+};
+pub use data_airdrop::PSP22AirdropData;
+pub use data_blacklist::PSP22BlacklistData;
+pub use data_bridge::{PSP22BridgeData, BRIDGE_ROLE};
+pub use data_flash::PSP22FlashFeeData;
+pub use data_lockable::PSP22LockableData;
+pub use data_metatx::PSP22MetaTxData;
+pub use data_permit::PSP22PermitData;
+pub use data_snapshot::PSP22SnapshotData;
+pub use data_timelock::{PSP22TimelockData, QueuedMint};
+pub use data_vesting::{PSP22VestingData, VestingSchedule};
+pub use data_votes::PSP22VotesData;
+pub use data_whitelist::PSP22WhitelistData;
+pub use data_wrapper::PSP22WrapperData;
+pub use domain::DomainData;
+pub use errors::{AccessControlError, OwnableError, PSP22Error, PSP22ErrorV2};
+pub use events::{
+    AddedToBlacklist, AddedToWhitelist, AirdropClaimed, Approval, BridgedIn, BridgedOut,
+    CallGasLimitUpdated, DelegateChanged, DelegateVotesChanged, FlashLoan, MintCancelled,
+    MintQueued, OwnershipTransferred, PermitUsed, RemovedFromBlacklist, RemovedFromWhitelist,
+    RoleGranted, RoleRevoked, SnapshotCreated, StorageVersion, TokensReleased, Transfer,
+    TransferWithData, VestingScheduleCreated, APPROVAL_SIGNATURE_TOPIC, TRANSFER_SIGNATURE_TOPIC,
+};
+pub use flash::{PSP22FlashBorrower, CALLBACK_SUCCESS};
+pub use impls::{EmitsPSP22Events, EnvEventSink, HasPSP22Data, NO_RESTRICTION};
+pub use migration::{migrate_v0_to_v1, PSP22DataV0};
+pub use multicall::{encode_call, MAX_MULTICALL_CALLS};
+pub use nonces::NoncesData;
+pub use ownable::OwnableData;
+pub use psp22_macros::implementation;
+pub use reentrancy::ReentrancyGuardData;
+pub use traits::{
+    AccessControl, Ownable, PSP22Airdrop, PSP22AllowanceEnumerable, PSP22BatchApprove,
+    PSP22Blacklist, PSP22Bridgeable, PSP22Burnable, PSP22Capped, PSP22Domain, PSP22FlashLender,
+    PSP22Introspection, PSP22MetaTx, PSP22Metadata, PSP22Mintable, PSP22Multicall, PSP22Permit,
+    PSP22Restrictable, PSP22Snapshot, PSP22Timelock, PSP22Vesting, PSP22Votes, PSP22Whitelist,
+    PSP22Wrapper, PSP22,
+};
+
+/// Returns the scaling factor of a token with `decimals` (see `PSP22Metadata::token_decimals`),
+/// i.e. `10^decimals`, saturating at `u128::MAX` for more than 38 decimals.
+///
+/// `PSP22Metadata` cannot provide it as a default method, as ink! traits do not support them.
+pub fn decimals_factor(decimals: u8) -> u128 {
+    10u128.checked_pow(decimals.into()).unwrap_or(u128::MAX)
+}
 
 // An example code of a smart contract using PSP22Data struct to implement
 // the functionality of PSP22 fungible token.
@@ -22,17 +102,47 @@ pub use traits::{PSP22Burnable, PSP22Metadata, PSP22Mintable, PSP22};
 //
 // It is a good practice to also implement the optional PSP22Metadata extension (5)
 // and include unit tests (6).
+//
+// This example passes `EnvEventSink` to the `_with_sink` methods of PSP22Data, so that
+// events are emitted as they happen. Alternatively, the methods without the suffix
+// return the resulting events in a vector, to be emitted by the contract.
+//
+// Implementing `HasPSP22Data` and `EmitsPSP22Events` makes each PSP22 message
+// a one-line call to a generic function from the `impls` module, see `impls.rs`
+// and `examples/minimal_token`. The whole implementation can also be generated with
+// the `psp22::implementation` attribute (see the `psp22-macros` crate in `macros/`)
+// or the `impl_psp22!` declarative macro (see `macros.rs`).
+//
+// Contracts using extensions (e.g. `examples/snapshot_token`) implement PSP22 by hand,
+// so that extension hooks can be called before each operation on PSP22Data.
+//
+// The metadata is kept in a separate `Lazy` storage cell, so that it is not loaded
+// by messages other than the PSP22Metadata queries. Likewise PSP22Data keeps the total
+// supply in its own cell, so transfers load only the balances they change.
 #[ink::contract]
 mod token {
-    use crate::{PSP22Data, PSP22Error, PSP22Event, PSP22Metadata, PSP22};
-    use ink::prelude::{string::String, vec::Vec};
+    use crate::{
+        extensions, EnvEventSink, PSP22Data, PSP22Error, PSP22Introspection, PSP22Metadata, PSP22,
+    };
+    use ink::{
+        prelude::{string::String, vec::Vec},
+        storage::Lazy,
+    };
 
-    #[ink(storage)]
-    pub struct Token {
-        data: PSP22Data, // (1)
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[derive(Debug, Default)]
+    pub struct Metadata {
         name: Option<String>,
         symbol: Option<String>,
         decimals: u8,
+        uri: Option<String>,
+    }
+
+    #[ink(storage)]
+    pub struct Token {
+        data: PSP22Data, // (1)
+        metadata: Lazy<Metadata>,
     }
 
     impl Token {
@@ -42,31 +152,61 @@ mod token {
             name: Option<String>,
             symbol: Option<String>,
             decimals: u8,
+            uri: Option<String>,
         ) -> Self {
-            let (data, events) = PSP22Data::new(supply, Self::env().caller()); // (2)
-            let contract = Self {
-                data,
+            let mut metadata = Lazy::new();
+            metadata.set(&Metadata {
                 name,
                 symbol,
                 decimals,
-            };
-            contract.emit_events(events);
-            contract
-        }
-
-        // A helper function emitting events contained in a vector of PSP22Events.
-        // (4)
-        fn emit_events(&self, events: Vec<PSP22Event>) {
-            for event in events {
-                match event {
-                    PSP22Event::Transfer(e) => self.env().emit_event(e),
-                    PSP22Event::Approval(e) => self.env().emit_event(e),
-                }
+                uri,
+            });
+            Self {
+                // (2), (4)
+                data: PSP22Data::new_with_sink(supply, Self::env().caller(), &mut EnvEventSink),
+                metadata,
             }
         }
+
+        fn metadata(&self) -> Metadata {
+            self.metadata.get().unwrap_or_default()
+        }
+
+        /// Transfers the whole balance of the caller to `to` with additional `data`
+        /// and returns the amount transferred. Useful for "send max" in wallets.
+        ///
+        /// It is a no-op returning `0` if the balance is zero or `to` is the caller.
+        #[ink(message)]
+        pub fn transfer_all(&mut self, to: AccountId, data: Vec<u8>) -> Result<u128, PSP22Error> {
+            let caller = self.env().caller();
+            self.data
+                .transfer_all_with_sink(caller, to, data, &mut EnvEventSink)
+        }
+
+        /// Sets the allowance granted by the caller to `spender` to `new_value`, but only if
+        /// it is currently equal to `expected_current`. Otherwise fails with `AllowanceMismatch`.
+        ///
+        /// Unlike `approve`, it cannot be front-run by the spender to spend both allowances.
+        #[ink(message)]
+        pub fn approve_checked(
+            &mut self,
+            spender: AccountId,
+            expected_current: u128,
+            new_value: u128,
+        ) -> Result<(), PSP22Error> {
+            let owner = self.env().caller();
+            self.data.approve_checked_with_sink(
+                owner,
+                spender,
+                expected_current,
+                new_value,
+                &mut EnvEventSink,
+            )?;
+            Ok(())
+        }
     }
 
-    // (3)
+    // (3), (4)
     impl PSP22 for Token {
         #[ink(message)]
         fn total_supply(&self) -> u128 {
@@ -88,10 +228,11 @@ mod token {
             &mut self,
             to: AccountId,
             value: u128,
-            _data: Vec<u8>,
+            data: Vec<u8>,
         ) -> Result<(), PSP22Error> {
-            let events = self.data.transfer(self.env().caller(), to, value)?;
-            self.emit_events(events);
+            let caller = self.env().caller();
+            self.data
+                .transfer_with_sink(caller, to, value, data, &mut EnvEventSink)?;
             Ok(())
         }
 
@@ -101,19 +242,19 @@ mod token {
             from: AccountId,
             to: AccountId,
             value: u128,
-            _data: Vec<u8>,
+            data: Vec<u8>,
         ) -> Result<(), PSP22Error> {
-            let events = self
-                .data
-                .transfer_from(self.env().caller(), from, to, value)?;
-            self.emit_events(events);
+            let caller = self.env().caller();
+            self.data
+                .transfer_from_with_sink(caller, from, to, value, data, &mut EnvEventSink)?;
             Ok(())
         }
 
         #[ink(message)]
         fn approve(&mut self, spender: AccountId, value: u128) -> Result<(), PSP22Error> {
-            let events = self.data.approve(self.env().caller(), spender, value)?;
-            self.emit_events(events);
+            let owner = self.env().caller();
+            self.data
+                .approve_with_sink(owner, spender, value, &mut EnvEventSink)?;
             Ok(())
         }
 
@@ -123,10 +264,13 @@ mod token {
             spender: AccountId,
             delta_value: u128,
         ) -> Result<(), PSP22Error> {
-            let events = self
-                .data
-                .increase_allowance(self.env().caller(), spender, delta_value)?;
-            self.emit_events(events);
+            let owner = self.env().caller();
+            self.data.increase_allowance_with_sink(
+                owner,
+                spender,
+                delta_value,
+                &mut EnvEventSink,
+            )?;
             Ok(())
         }
 
@@ -136,10 +280,13 @@ mod token {
             spender: AccountId,
             delta_value: u128,
         ) -> Result<(), PSP22Error> {
-            let events = self
-                .data
-                .decrease_allowance(self.env().caller(), spender, delta_value)?;
-            self.emit_events(events);
+            let owner = self.env().caller();
+            self.data.decrease_allowance_with_sink(
+                owner,
+                spender,
+                delta_value,
+                &mut EnvEventSink,
+            )?;
             Ok(())
         }
     }
@@ -148,22 +295,74 @@ mod token {
     impl PSP22Metadata for Token {
         #[ink(message)]
         fn token_name(&self) -> Option<String> {
-            self.name.clone()
+            self.metadata().name
         }
+
         #[ink(message)]
         fn token_symbol(&self) -> Option<String> {
-            self.symbol.clone()
+            self.metadata().symbol
         }
+
         #[ink(message)]
         fn token_decimals(&self) -> u8 {
-            self.decimals
+            self.metadata().decimals
+        }
+
+        #[ink(message)]
+        fn token_uri(&self) -> Option<String> {
+            self.metadata().uri
+        }
+    }
+
+    impl PSP22Introspection for Token {
+        #[ink(message)]
+        fn supports_extension(&self, id: [u8; 4]) -> bool {
+            id == extensions::METADATA
         }
     }
 
     // (6)
     #[cfg(test)]
     mod tests {
-        use super::Token;
-        crate::tests!(Token, (|supply| Token::new(supply, None, None, 0)));
+        use super::*;
+        crate::tests!(Token, (|supply| Token::new(supply, None, None, 0, None)));
+        crate::metadata_tests!(Token, (|uri| Token::new(1000, None, None, 0, uri)));
+
+        #[ink::test]
+        fn supports_metadata_only() {
+            let token = Token::new(1000, None, None, 0, None);
+            assert_eq!(token.token_uri(), None);
+            assert!(token.supports_extension(extensions::METADATA));
+            assert!(!token.supports_extension(extensions::PAUSABLE));
+            assert!(!token.supports_extension(extensions::MINTABLE));
+        }
+
+        #[ink::test]
+        fn metadata_is_stored() {
+            let token = Token::new(
+                1000,
+                Some(String::from("Token")),
+                Some(String::from("TKN")),
+                12,
+                Some(String::from("ipfs://token.json")),
+            );
+            assert_eq!(token.token_name(), Some(String::from("Token")));
+            assert_eq!(token.token_symbol(), Some(String::from("TKN")));
+            assert_eq!(token.token_decimals(), 12);
+            assert_eq!(token.token_uri(), Some(String::from("ipfs://token.json")));
+            assert_eq!(
+                crate::decimals_factor(token.token_decimals()),
+                10u128.pow(12)
+            );
+        }
+
+        #[test]
+        fn decimals_factor_saturates() {
+            assert_eq!(crate::decimals_factor(0), 1);
+            assert_eq!(crate::decimals_factor(18), 1_000_000_000_000_000_000u128);
+            assert_eq!(crate::decimals_factor(38), 10u128.pow(38));
+            assert_eq!(crate::decimals_factor(39), u128::MAX);
+            assert_eq!(crate::decimals_factor(u8::MAX), u128::MAX);
+        }
     }
 }
@@ -0,0 +1,189 @@
+/// Implements the `PSP22` trait for a contract storing its token state in a `PSP22Data` field.
+///
+/// `ink::contract` expands the contract module before any macro inside of it, so messages
+/// generated by a macro invoked inside the module would not be recognized by ink!.
+/// Because of that, this macro wraps the whole contract module and appends to it:
+/// - the `impl PSP22 for $contract` block forwarding all messages to `self.$data`,
+/// - an `emit_events` helper method emitting a vector of `PSP22Event`s,
+/// - optionally (with `metadata(name, symbol, decimals)`) the `impl PSP22Metadata for $contract`
+///   block returning the values of the given fields. The field holding the token URI
+///   can be given as the fourth one, `metadata(name, symbol, decimals, uri)`,
+///   otherwise `token_uri` returns `None`.
+///
+/// The `emit_events` method is available to the rest of the contract code, e.g. the constructor.
+///
+/// ```ignore
+/// psp22::impl_psp22! {
+///     Token, data, metadata(name, symbol, decimals);
+///
+///     #[ink::contract]
+///     mod token {
+///         #[ink(storage)]
+///         pub struct Token {
+///             data: PSP22Data,
+///             name: Option<String>,
+///             symbol: Option<String>,
+///             decimals: u8,
+///         }
+///         // constructors and other messages
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! impl_psp22 {
+    (
+        $contract:ident, $data:ident, metadata($name:ident, $symbol:ident, $decimals:ident $(, $uri:ident)?);
+        $(#[$attr:meta])*
+        $vis:vis mod $module:ident { $($body:tt)* }
+    ) => {
+        $crate::impl_psp22! {
+            @module [
+                impl $crate::PSP22Metadata for $contract {
+                    #[ink(message)]
+                    fn token_name(&self) -> Option<::ink::prelude::string::String> {
+                        self.$name.clone()
+                    }
+
+                    #[ink(message)]
+                    fn token_symbol(&self) -> Option<::ink::prelude::string::String> {
+                        self.$symbol.clone()
+                    }
+
+                    #[ink(message)]
+                    fn token_decimals(&self) -> u8 {
+                        self.$decimals
+                    }
+
+                    #[ink(message)]
+                    fn token_uri(&self) -> Option<::ink::prelude::string::String> {
+                        None $(.or_else(|| self.$uri.clone()))?
+                    }
+                }
+            ]
+            $contract, $data;
+            $(#[$attr])*
+            $vis mod $module { $($body)* }
+        }
+    };
+    (
+        $contract:ident, $data:ident;
+        $(#[$attr:meta])*
+        $vis:vis mod $module:ident { $($body:tt)* }
+    ) => {
+        $crate::impl_psp22! {
+            @module []
+            $contract, $data;
+            $(#[$attr])*
+            $vis mod $module { $($body)* }
+        }
+    };
+    (
+        @module [$($extra:tt)*]
+        $contract:ident, $data:ident;
+        $(#[$attr:meta])*
+        $vis:vis mod $module:ident { $($body:tt)* }
+    ) => {
+        $(#[$attr])*
+        $vis mod $module {
+            $($body)*
+
+            impl $contract {
+                // A helper function emitting events contained in a vector of PSP22Events.
+                fn emit_events(&self, events: ::ink::prelude::vec::Vec<$crate::PSP22Event>) {
+                    for event in events {
+                        $crate::impls::emit_event::<<Self as ::ink::env::ContractEnv>::Env>(event);
+                    }
+                }
+            }
+
+            impl $crate::PSP22 for $contract {
+                #[ink(message)]
+                fn total_supply(&self) -> u128 {
+                    self.$data.total_supply()
+                }
+
+                #[ink(message)]
+                fn balance_of(&self, owner: AccountId) -> u128 {
+                    self.$data.balance_of(owner)
+                }
+
+                #[ink(message)]
+                fn allowance(&self, owner: AccountId, spender: AccountId) -> u128 {
+                    self.$data.allowance(owner, spender)
+                }
+
+                #[ink(message)]
+                fn transfer(
+                    &mut self,
+                    to: AccountId,
+                    value: u128,
+                    data: ::ink::prelude::vec::Vec<u8>,
+                ) -> Result<(), $crate::PSP22Error> {
+                    let events = self
+                        .$data
+                        .transfer_with_data(self.env().caller(), to, value, data)?;
+                    self.emit_events(events);
+                    Ok(())
+                }
+
+                #[ink(message)]
+                fn transfer_from(
+                    &mut self,
+                    from: AccountId,
+                    to: AccountId,
+                    value: u128,
+                    data: ::ink::prelude::vec::Vec<u8>,
+                ) -> Result<(), $crate::PSP22Error> {
+                    let events = self.$data.transfer_from_with_data(
+                        self.env().caller(),
+                        from,
+                        to,
+                        value,
+                        data,
+                    )?;
+                    self.emit_events(events);
+                    Ok(())
+                }
+
+                #[ink(message)]
+                fn approve(
+                    &mut self,
+                    spender: AccountId,
+                    value: u128,
+                ) -> Result<(), $crate::PSP22Error> {
+                    let events = self.$data.approve(self.env().caller(), spender, value)?;
+                    self.emit_events(events);
+                    Ok(())
+                }
+
+                #[ink(message)]
+                fn increase_allowance(
+                    &mut self,
+                    spender: AccountId,
+                    delta_value: u128,
+                ) -> Result<(), $crate::PSP22Error> {
+                    let events =
+                        self.$data
+                            .increase_allowance(self.env().caller(), spender, delta_value)?;
+                    self.emit_events(events);
+                    Ok(())
+                }
+
+                #[ink(message)]
+                fn decrease_allowance(
+                    &mut self,
+                    spender: AccountId,
+                    delta_value: u128,
+                ) -> Result<(), $crate::PSP22Error> {
+                    let events =
+                        self.$data
+                            .decrease_allowance(self.env().caller(), spender, delta_value)?;
+                    self.emit_events(events);
+                    Ok(())
+                }
+            }
+
+            $($extra)*
+        }
+    };
+}
@@ -0,0 +1,542 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+    parse::Parser, punctuated::Punctuated, Attribute, Ident, ImplItem, Item, ItemImpl, ItemMod,
+    Token, Type,
+};
+
+/// Generates implementations of PSP22 traits for an ink! contract storing its token
+/// state in a `PSP22Data` field.
+///
+/// The attribute must be placed on the contract module, above `#[ink::contract]`.
+/// The `PSP22Data` field of the storage struct is marked with `#[psp22(data)]`.
+/// The macro generates:
+/// - `impl PSP22 for Contract` forwarding all messages to the data field,
+/// - an `emit_events` helper method emitting a vector of `PSP22Event`s,
+/// - `impl PSP22Metadata` with the `metadata` flag, returning the fields marked with
+///   `#[psp22(name)]`, `#[psp22(symbol)]`, `#[psp22(decimals)]` and optionally
+///   `#[psp22(uri)]` (`token_uri` returns `None` without it),
+/// - `impl PSP22Mintable` with the `mintable` flag (minting to the caller, unrestricted),
+/// - `impl PSP22Burnable` with the `burnable` flag (burning from the caller).
+///
+/// Any of the messages (or `emit_events`) can be overridden by implementing it manually
+/// in the module: if an impl block of a trait already exists, only the missing messages
+/// are added to it.
+///
+/// ```ignore
+/// #[psp22::implementation(metadata)]
+/// #[ink::contract]
+/// mod token {
+///     #[ink(storage)]
+///     pub struct Token {
+///         #[psp22(data)]
+///         data: PSP22Data,
+///         #[psp22(name)]
+///         name: Option<String>,
+///         #[psp22(symbol)]
+///         symbol: Option<String>,
+///         #[psp22(decimals)]
+///         decimals: u8,
+///     }
+///     // constructors and other messages
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn implementation(
+    attr: proc_macro::TokenStream,
+    item: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    expand(attr.into(), item.into())
+        .unwrap_or_else(|error| error.to_compile_error())
+        .into()
+}
+
+// Fields of the storage struct marked with `#[psp22(...)]` attributes.
+#[derive(Default)]
+struct Fields {
+    data: Option<Ident>,
+    name: Option<Ident>,
+    symbol: Option<Ident>,
+    decimals: Option<Ident>,
+    uri: Option<Ident>,
+}
+
+fn expand(attr: TokenStream, item: TokenStream) -> syn::Result<TokenStream> {
+    let flags = Punctuated::<Ident, Token![,]>::parse_terminated.parse2(attr)?;
+    for flag in &flags {
+        if !["metadata", "mintable", "burnable"].contains(&flag.to_string().as_str()) {
+            return Err(syn::Error::new(flag.span(), "unknown psp22 flag"));
+        }
+    }
+    let has_flag = |name: &str| flags.iter().any(|flag| flag == name);
+
+    let mut module: ItemMod = syn::parse2(item)?;
+    let span = module.ident.span();
+    let (_, items) = module
+        .content
+        .as_mut()
+        .ok_or_else(|| syn::Error::new(span, "the contract module must be inline"))?;
+
+    let (contract, fields) = storage_struct(items)?;
+    let data = fields
+        .data
+        .ok_or_else(|| syn::Error::new(span, "no field is marked with #[psp22(data)]"))?;
+
+    let mut generated = vec![(format_ident!("PSP22"), psp22_messages(&data))];
+    if has_flag("metadata") {
+        let missing =
+            |field| syn::Error::new(span, format!("no field is marked with #[psp22({field})]"));
+        let name = fields.name.ok_or_else(|| missing("name"))?;
+        let symbol = fields.symbol.ok_or_else(|| missing("symbol"))?;
+        let decimals = fields.decimals.ok_or_else(|| missing("decimals"))?;
+        generated.push((
+            format_ident!("PSP22Metadata"),
+            metadata_messages(&name, &symbol, &decimals, fields.uri.as_ref()),
+        ));
+    }
+    if has_flag("mintable") {
+        generated.push((format_ident!("PSP22Mintable"), mintable_messages(&data)));
+    }
+    if has_flag("burnable") {
+        generated.push((format_ident!("PSP22Burnable"), burnable_messages(&data)));
+    }
+
+    for (trait_name, messages) in generated {
+        let trait_path = quote!(::psp22::#trait_name);
+        merge_impl(
+            items,
+            &contract,
+            Some(&trait_name),
+            messages,
+            || syn::parse_quote!(impl #trait_path for #contract {}),
+        );
+    }
+    merge_impl(
+        items,
+        &contract,
+        None,
+        vec![emit_events()],
+        || syn::parse_quote!(impl #contract {}),
+    );
+
+    Ok(quote!(#module))
+}
+
+// Finds the `#[ink(storage)]` struct, strips `#[psp22(...)]` attributes from its fields
+// and returns the name of the struct with the marked fields.
+fn storage_struct(items: &mut [Item]) -> syn::Result<(Ident, Fields)> {
+    for item in items.iter_mut() {
+        let Item::Struct(storage) = item else {
+            continue;
+        };
+        if !storage.attrs.iter().any(is_ink_storage) {
+            continue;
+        }
+        let mut fields = Fields::default();
+        for field in storage.fields.iter_mut() {
+            let mut markers = Vec::new();
+            field.attrs.retain(|attr| {
+                let is_marker = attr.path().is_ident("psp22");
+                if is_marker {
+                    markers.push(attr.clone());
+                }
+                !is_marker
+            });
+            for marker in markers {
+                let kind: Ident = marker.parse_args()?;
+                let ident = field.ident.clone().ok_or_else(|| {
+                    syn::Error::new_spanned(&marker, "#[psp22] requires a named field")
+                })?;
+                let slot = match kind.to_string().as_str() {
+                    "data" => &mut fields.data,
+                    "name" => &mut fields.name,
+                    "symbol" => &mut fields.symbol,
+                    "decimals" => &mut fields.decimals,
+                    "uri" => &mut fields.uri,
+                    _ => return Err(syn::Error::new(kind.span(), "unknown psp22 field marker")),
+                };
+                if slot.replace(ident).is_some() {
+                    return Err(syn::Error::new(
+                        kind.span(),
+                        "duplicated psp22 field marker",
+                    ));
+                }
+            }
+        }
+        return Ok((storage.ident.clone(), fields));
+    }
+    Err(syn::Error::new(
+        proc_macro2::Span::call_site(),
+        "no #[ink(storage)] struct found in the module",
+    ))
+}
+
+fn is_ink_storage(attr: &Attribute) -> bool {
+    attr.path().is_ident("ink")
+        && attr
+            .parse_args::<Ident>()
+            .map(|arg| arg == "storage")
+            .unwrap_or(false)
+}
+
+// Returns the name of the type an impl block is written for, if it is a plain identifier.
+fn self_ident(block: &ItemImpl) -> Option<&Ident> {
+    match &*block.self_ty {
+        Type::Path(path) => path.path.get_ident(),
+        _ => None,
+    }
+}
+
+// Adds `methods` to the impl block of `trait_name` (or the inherent impl block if `None`)
+// for `contract`, skipping methods already implemented there. Creates the block with
+// `new_block` if it does not exist.
+fn merge_impl(
+    items: &mut Vec<Item>,
+    contract: &Ident,
+    trait_name: Option<&Ident>,
+    methods: Vec<ImplItem>,
+    new_block: impl FnOnce() -> ItemImpl,
+) {
+    let position = items.iter().position(|item| match item {
+        Item::Impl(block) if self_ident(block) == Some(contract) => {
+            match (&block.trait_, trait_name) {
+                (Some((_, path, _)), Some(name)) => {
+                    path.segments.last().map(|segment| &segment.ident) == Some(name)
+                }
+                (None, None) => block
+                    .items
+                    .iter()
+                    .any(|item| matches!(item, ImplItem::Fn(_))),
+                _ => false,
+            }
+        }
+        _ => false,
+    });
+    let block = match position {
+        Some(position) => match &mut items[position] {
+            Item::Impl(block) => block,
+            _ => unreachable!(),
+        },
+        None => {
+            items.push(Item::Impl(new_block()));
+            match items.last_mut() {
+                Some(Item::Impl(block)) => block,
+                _ => unreachable!(),
+            }
+        }
+    };
+    let existing: Vec<Ident> = block
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            ImplItem::Fn(method) => Some(method.sig.ident.clone()),
+            _ => None,
+        })
+        .collect();
+    for method in methods {
+        if let ImplItem::Fn(generated) = &method {
+            if existing.contains(&generated.sig.ident) {
+                continue;
+            }
+        }
+        block.items.push(method);
+    }
+}
+
+fn emit_events() -> ImplItem {
+    syn::parse_quote! {
+        // A helper function emitting events contained in a vector of PSP22Events.
+        fn emit_events(&self, events: ::ink::prelude::vec::Vec<::psp22::PSP22Event>) {
+            for event in events {
+                ::psp22::impls::emit_event::<<Self as ::ink::env::ContractEnv>::Env>(event);
+            }
+        }
+    }
+}
+
+fn psp22_messages(data: &Ident) -> Vec<ImplItem> {
+    vec![
+        syn::parse_quote! {
+            #[ink(message)]
+            fn total_supply(&self) -> u128 {
+                self.#data.total_supply()
+            }
+        },
+        syn::parse_quote! {
+            #[ink(message)]
+            fn balance_of(&self, owner: AccountId) -> u128 {
+                self.#data.balance_of(owner)
+            }
+        },
+        syn::parse_quote! {
+            #[ink(message)]
+            fn allowance(&self, owner: AccountId, spender: AccountId) -> u128 {
+                self.#data.allowance(owner, spender)
+            }
+        },
+        syn::parse_quote! {
+            #[ink(message)]
+            fn transfer(
+                &mut self,
+                to: AccountId,
+                value: u128,
+                data: ::ink::prelude::vec::Vec<u8>,
+            ) -> Result<(), ::psp22::PSP22Error> {
+                let events = self
+                    .#data
+                    .transfer_with_data(self.env().caller(), to, value, data)?;
+                self.emit_events(events);
+                Ok(())
+            }
+        },
+        syn::parse_quote! {
+            #[ink(message)]
+            fn transfer_from(
+                &mut self,
+                from: AccountId,
+                to: AccountId,
+                value: u128,
+                data: ::ink::prelude::vec::Vec<u8>,
+            ) -> Result<(), ::psp22::PSP22Error> {
+                let events = self.#data.transfer_from_with_data(
+                    self.env().caller(),
+                    from,
+                    to,
+                    value,
+                    data,
+                )?;
+                self.emit_events(events);
+                Ok(())
+            }
+        },
+        syn::parse_quote! {
+            #[ink(message)]
+            fn approve(&mut self, spender: AccountId, value: u128) -> Result<(), ::psp22::PSP22Error> {
+                let events = self.#data.approve(self.env().caller(), spender, value)?;
+                self.emit_events(events);
+                Ok(())
+            }
+        },
+        syn::parse_quote! {
+            #[ink(message)]
+            fn increase_allowance(
+                &mut self,
+                spender: AccountId,
+                delta_value: u128,
+            ) -> Result<(), ::psp22::PSP22Error> {
+                let events = self
+                    .#data
+                    .increase_allowance(self.env().caller(), spender, delta_value)?;
+                self.emit_events(events);
+                Ok(())
+            }
+        },
+        syn::parse_quote! {
+            #[ink(message)]
+            fn decrease_allowance(
+                &mut self,
+                spender: AccountId,
+                delta_value: u128,
+            ) -> Result<(), ::psp22::PSP22Error> {
+                let events = self
+                    .#data
+                    .decrease_allowance(self.env().caller(), spender, delta_value)?;
+                self.emit_events(events);
+                Ok(())
+            }
+        },
+    ]
+}
+
+fn metadata_messages(
+    name: &Ident,
+    symbol: &Ident,
+    decimals: &Ident,
+    uri: Option<&Ident>,
+) -> Vec<ImplItem> {
+    let uri = match uri {
+        Some(uri) => quote!(self.#uri.clone()),
+        None => quote!(None),
+    };
+    vec![
+        syn::parse_quote! {
+            #[ink(message)]
+            fn token_name(&self) -> Option<::ink::prelude::string::String> {
+                self.#name.clone()
+            }
+        },
+        syn::parse_quote! {
+            #[ink(message)]
+            fn token_symbol(&self) -> Option<::ink::prelude::string::String> {
+                self.#symbol.clone()
+            }
+        },
+        syn::parse_quote! {
+            #[ink(message)]
+            fn token_decimals(&self) -> u8 {
+                self.#decimals
+            }
+        },
+        syn::parse_quote! {
+            #[ink(message)]
+            fn token_uri(&self) -> Option<::ink::prelude::string::String> {
+                #uri
+            }
+        },
+    ]
+}
+
+fn mintable_messages(data: &Ident) -> Vec<ImplItem> {
+    vec![syn::parse_quote! {
+        #[ink(message)]
+        fn mint(&mut self, value: u128) -> Result<(), ::psp22::PSP22Error> {
+            let events = self.#data.mint(self.env().caller(), value)?;
+            self.emit_events(events);
+            Ok(())
+        }
+    }]
+}
+
+fn burnable_messages(data: &Ident) -> Vec<ImplItem> {
+    vec![syn::parse_quote! {
+        #[ink(message)]
+        fn burn(&mut self, value: u128) -> Result<(), ::psp22::PSP22Error> {
+            let events = self.#data.burn(self.env().caller(), value)?;
+            self.emit_events(events);
+            Ok(())
+        }
+    }]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contract() -> TokenStream {
+        quote! {
+            #[ink::contract]
+            mod token {
+                #[ink(storage)]
+                pub struct Token {
+                    #[psp22(data)]
+                    data: PSP22Data,
+                    #[psp22(name)]
+                    name: Option<String>,
+                    #[psp22(symbol)]
+                    symbol: Option<String>,
+                    #[psp22(decimals)]
+                    decimals: u8,
+                }
+
+                impl Token {
+                    #[ink(constructor)]
+                    pub fn new() -> Self {
+                        unimplemented!()
+                    }
+                }
+
+                impl PSP22 for Token {
+                    #[ink(message)]
+                    fn total_supply(&self) -> u128 {
+                        42
+                    }
+                }
+            }
+        }
+    }
+
+    fn methods(module: &ItemMod, trait_name: Option<&str>) -> Vec<String> {
+        let (_, items) = module.content.as_ref().unwrap();
+        items
+            .iter()
+            .filter_map(|item| match item {
+                Item::Impl(block) => Some(block),
+                _ => None,
+            })
+            .filter(|block| {
+                let name = block
+                    .trait_
+                    .as_ref()
+                    .map(|(_, path, _)| path.segments.last().unwrap().ident.to_string());
+                name.as_deref() == trait_name
+            })
+            .flat_map(|block| block.items.iter())
+            .filter_map(|item| match item {
+                ImplItem::Fn(method) => Some(method.sig.ident.to_string()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn generates_missing_messages_and_keeps_overrides() {
+        let output = expand(quote!(metadata, burnable), contract()).unwrap();
+        let module: ItemMod = syn::parse2(output.clone()).unwrap();
+
+        let psp22 = methods(&module, Some("PSP22"));
+        assert_eq!(psp22.len(), 8);
+        assert_eq!(psp22.iter().filter(|m| *m == "total_supply").count(), 1);
+        assert!(output.to_string().contains("42"));
+        assert_eq!(
+            methods(&module, Some("PSP22Metadata")),
+            ["token_name", "token_symbol", "token_decimals", "token_uri"]
+        );
+        assert_eq!(methods(&module, Some("PSP22Burnable")), ["burn"]);
+        assert!(methods(&module, Some("PSP22Mintable")).is_empty());
+        assert_eq!(methods(&module, None), ["new", "emit_events"]);
+        assert!(!output.to_string().contains("psp22 ("));
+    }
+
+    #[test]
+    fn reports_missing_markers_and_unknown_flags() {
+        let error = expand(
+            quote!(),
+            quote!(
+                mod token {
+                    #[ink(storage)]
+                    pub struct Token {}
+                }
+            ),
+        );
+        assert!(error.is_err());
+        assert!(expand(quote!(pausable), contract()).is_err());
+        let no_metadata = quote! {
+            mod token {
+                #[ink(storage)]
+                pub struct Token {
+                    #[psp22(data)]
+                    data: PSP22Data,
+                }
+            }
+        };
+        assert!(expand(quote!(), no_metadata.clone()).is_ok());
+        assert!(expand(quote!(metadata), no_metadata).is_err());
+    }
+
+    #[test]
+    fn token_uri_is_returned_if_marked() {
+        let without_uri = expand(quote!(metadata), contract()).unwrap().to_string();
+        assert!(without_uri.contains("fn token_uri"));
+        assert!(!without_uri.contains("self . uri"));
+
+        let with_uri = quote! {
+            mod token {
+                #[ink(storage)]
+                pub struct Token {
+                    #[psp22(data)]
+                    data: PSP22Data,
+                    #[psp22(name)]
+                    name: Option<String>,
+                    #[psp22(symbol)]
+                    symbol: Option<String>,
+                    #[psp22(decimals)]
+                    decimals: u8,
+                    #[psp22(uri)]
+                    uri: Option<String>,
+                }
+            }
+        };
+        let with_uri = expand(quote!(metadata), with_uri).unwrap().to_string();
+        assert!(with_uri.contains("self . uri . clone ()"));
+    }
+}
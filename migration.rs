@@ -0,0 +1,207 @@
+use crate::data::{PSP22Data, PSP22Event, STORAGE_VERSION};
+use crate::errors::PSP22Error;
+use crate::events::StorageVersion;
+use ink::{
+    env::hash::{Blake2x256, HashOutput},
+    prelude::{vec, vec::Vec},
+    primitives::AccountId,
+};
+
+/// The storage of `PSP22Data` version 0, written by the versions of this crate before
+/// the fixed storage keys.
+///
+/// In version 0 the total supply was stored inline, in the storage cell of the contract,
+/// and the balances and allowances in `Mapping`s with automatic keys, which ink! derives
+/// from the names of the contract storage struct and its `PSP22Data` field.
+/// The contract decodes the total supply from its old storage cell itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PSP22DataV0 {
+    total_supply: u128,
+    balances_key: u32,
+    allowances_key: u32,
+}
+
+impl PSP22DataV0 {
+    /// Describes `PSP22Data` version 0 stored in the `field` of the contract storage
+    /// struct named `contract` (e.g. `("Token", "data")`), with the given `total_supply`.
+    pub fn new(contract: &str, field: &str, total_supply: u128) -> PSP22DataV0 {
+        let parent = storage_key(&[contract, "::", field].concat());
+        PSP22DataV0 {
+            total_supply,
+            balances_key: concat_keys(storage_key("PSP22Data::balances"), parent),
+            allowances_key: concat_keys(storage_key("PSP22Data::allowances"), parent),
+        }
+    }
+
+    /// Returns the root key of the balances mapping.
+    pub fn balances_key(&self) -> u32 {
+        self.balances_key
+    }
+
+    /// Returns the root key of the allowances mapping.
+    pub fn allowances_key(&self) -> u32 {
+        self.allowances_key
+    }
+
+    fn balance_of(&self, account: AccountId) -> u128 {
+        ink::env::get_contract_storage::<(u32, AccountId), u128>(&(self.balances_key, account))
+            .ok()
+            .flatten()
+            .unwrap_or_default()
+    }
+
+    fn allowance(&self, owner: AccountId, spender: AccountId) -> u128 {
+        ink::env::get_contract_storage::<(u32, (AccountId, AccountId)), u128>(&(
+            self.allowances_key,
+            (owner, spender),
+        ))
+        .ok()
+        .flatten()
+        .unwrap_or_default()
+    }
+}
+
+/// Moves the balances of `accounts` and the allowances of `approvals` from `PSP22Data`
+/// version 0 described by `v0` to `data` in the current layout, removing them from
+/// the old storage, and stores the current storage version.
+///
+/// Mappings cannot be enumerated on-chain, so `accounts` must list all holders
+/// (e.g. collected from `Transfer` events) and `approvals` all non-zero allowances
+/// to be kept. Fails with `Custom (Incomplete migration.)` if the migrated balances
+/// do not add up to the total supply of `v0`, and with `Custom (Storage already migrated.)`
+/// if `data` already has the current layout (e.g. the migration has already run).
+///
+/// Returns a `StorageVersion` event. No other events are returned, as no tokens are moved
+/// from the point of view of the holders. The state is not rolled back on error,
+/// it is up to the contract to revert the transaction.
+pub fn migrate_v0_to_v1(
+    data: &mut PSP22Data,
+    v0: &PSP22DataV0,
+    accounts: &[AccountId],
+    approvals: &[(AccountId, AccountId)],
+) -> Result<Vec<PSP22Event>, PSP22Error> {
+    if data.is_storage_version_marked() || data.total_supply() != 0 {
+        return Err(PSP22Error::custom("Storage already migrated."));
+    }
+    for &account in accounts {
+        let balance = v0.balance_of(account);
+        if balance > 0 {
+            data.mint(account, balance)?;
+            ink::env::clear_contract_storage(&(v0.balances_key, account));
+        }
+    }
+    if data.total_supply() != v0.total_supply {
+        return Err(PSP22Error::custom("Incomplete migration."));
+    }
+    for &(owner, spender) in approvals {
+        let amount = v0.allowance(owner, spender);
+        if amount > 0 {
+            data.approve(owner, spender, amount)?;
+            ink::env::clear_contract_storage(&(v0.allowances_key, (owner, spender)));
+        }
+    }
+    data.mark_storage_version();
+    Ok(vec![PSP22Event::StorageVersion(StorageVersion {
+        version: STORAGE_VERSION,
+    })])
+}
+
+// Automatic storage key of a field, computed by ink! from `Struct::field`.
+fn storage_key(path: &str) -> u32 {
+    let mut output = <Blake2x256 as HashOutput>::Type::default();
+    ink::env::hash_bytes::<Blake2x256>(path.as_bytes(), &mut output);
+    u32::from_be_bytes([output[0], output[1], output[2], output[3]])
+}
+
+// Combines the key of a field with the key of its parent, like ink!'s `KeyComposer::concat`.
+fn concat_keys(key: u32, parent: u32) -> u32 {
+    match (key, parent) {
+        (0, _) => parent,
+        (_, 0) => key,
+        _ => key ^ parent,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink::env::{test::default_accounts, DefaultEnvironment as E};
+
+    fn setup_v0(balances: &[(AccountId, u128)]) -> PSP22DataV0 {
+        let total_supply = balances.iter().map(|(_, balance)| balance).sum();
+        let v0 = PSP22DataV0::new("Token", "data", total_supply);
+        for (account, balance) in balances {
+            ink::env::set_contract_storage(&(v0.balances_key(), *account), balance);
+        }
+        v0
+    }
+
+    #[test]
+    fn v0_keys_are_derived_from_field_path() {
+        let v0 = PSP22DataV0::new("Token", "data", 0);
+        // blake2b_256("Token::data") ^ blake2b_256("PSP22Data::balances") etc.
+        assert_eq!(v0.balances_key(), 0xa3a28c6c);
+        assert_eq!(v0.allowances_key(), 0xe262bbdf);
+        assert_ne!(PSP22DataV0::new("Token", "psp22", 0), v0);
+    }
+
+    #[ink::test]
+    fn balances_and_allowances_are_migrated() {
+        let acc = default_accounts::<E>();
+        let v0 = setup_v0(&[(acc.alice, 600), (acc.bob, 400)]);
+        ink::env::set_contract_storage(&(v0.allowances_key(), (acc.alice, acc.charlie)), &50u128);
+        let mut data = PSP22Data::default();
+        assert_eq!(data.storage_version(), 0);
+
+        let events = migrate_v0_to_v1(
+            &mut data,
+            &v0,
+            &[acc.alice, acc.bob, acc.charlie],
+            &[(acc.alice, acc.charlie)],
+        )
+        .unwrap();
+        assert!(matches!(
+            &events[..],
+            [PSP22Event::StorageVersion(e)] if e.version == 1
+        ));
+        assert_eq!(data.total_supply(), 1000);
+        assert_eq!(data.balance_of(acc.alice), 600);
+        assert_eq!(data.balance_of(acc.bob), 400);
+        assert_eq!(data.allowance(acc.alice, acc.charlie), 50);
+        assert_eq!(data.storage_version(), STORAGE_VERSION);
+        // The old storage is cleared
+        assert_eq!(v0.balance_of(acc.alice), 0);
+        assert_eq!(v0.allowance(acc.alice, acc.charlie), 0);
+    }
+
+    #[ink::test]
+    fn migration_runs_once() {
+        let acc = default_accounts::<E>();
+        let v0 = setup_v0(&[(acc.alice, 600)]);
+        let mut data = PSP22Data::default();
+        assert!(migrate_v0_to_v1(&mut data, &v0, &[acc.alice], &[]).is_ok());
+
+        assert_eq!(
+            migrate_v0_to_v1(&mut data, &v0, &[acc.alice], &[]).err(),
+            Some(PSP22Error::custom("Storage already migrated."))
+        );
+        // Tokens created in the current layout need no migration
+        let (mut data, _) = PSP22Data::new(1000, acc.alice);
+        assert_eq!(
+            migrate_v0_to_v1(&mut data, &v0, &[acc.alice], &[]).err(),
+            Some(PSP22Error::custom("Storage already migrated."))
+        );
+    }
+
+    #[ink::test]
+    fn missing_holders_fail_migration() {
+        let acc = default_accounts::<E>();
+        let v0 = setup_v0(&[(acc.alice, 600), (acc.bob, 400)]);
+        let mut data = PSP22Data::default();
+
+        assert_eq!(
+            migrate_v0_to_v1(&mut data, &v0, &[acc.alice], &[]).err(),
+            Some(PSP22Error::custom("Incomplete migration."))
+        );
+    }
+}
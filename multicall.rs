@@ -0,0 +1,210 @@
+use crate::data::{PSP22Data, PSP22Event};
+use crate::errors::PSP22Error;
+use crate::selectors;
+use ink::{
+    prelude::{vec, vec::Vec},
+    primitives::AccountId,
+    scale::{Decode, Encode},
+};
+
+/// Maximal number of calls batched in a single `multicall`.
+pub const MAX_MULTICALL_CALLS: usize = 16;
+
+impl PSP22Data {
+    /// Executes the PSP22 messages encoded in `calls` one by one on behalf of `caller`,
+    /// as if `caller` sent them in separate transactions, see `PSP22Multicall`.
+    ///
+    /// Each call is a selector from `selectors` followed by the SCALE-encoded arguments,
+    /// like the input of a contract call. Only the messages of `PSP22` are supported.
+    /// They are executed directly on `self` rather than by calling the contract, so the caller
+    /// of each of them is `caller` and no other contract gets control in the meantime.
+    ///
+    /// Returns the SCALE-encoded return value of each message alongside the events
+    /// of all of them. Stops at the first failing message and returns its error.
+    /// Returns `Custom (too many calls)` error if there are more than `MAX_MULTICALL_CALLS`
+    /// calls and `Custom (invalid call)` error if a call is not a supported message
+    /// with correctly encoded arguments.
+    ///
+    /// The state is not rolled back on error, it is up to the contract to revert
+    /// the transaction (which ink! does when a message returns an error).
+    ///
+    /// Checks done by the contract outside of `PSP22Data`, like a blacklist, a whitelist
+    /// or locked balances, are not applied to the batched messages.
+    pub fn multicall(
+        &mut self,
+        caller: AccountId,
+        calls: Vec<Vec<u8>>,
+    ) -> Result<(Vec<Vec<u8>>, Vec<PSP22Event>), PSP22Error> {
+        if calls.len() > MAX_MULTICALL_CALLS {
+            return Err(PSP22Error::custom("too many calls"));
+        }
+        let mut outputs = Vec::with_capacity(calls.len());
+        let mut events = Vec::new();
+        for call in calls {
+            outputs.push(self.dispatch(caller, &call, &mut events)?);
+        }
+        Ok((outputs, events))
+    }
+
+    // Executes a single call of `multicall`, returning the encoded return value of the message.
+    fn dispatch(
+        &mut self,
+        caller: AccountId,
+        call: &[u8],
+        events: &mut Vec<PSP22Event>,
+    ) -> Result<Vec<u8>, PSP22Error> {
+        if call.len() < 4 {
+            return Err(PSP22Error::custom("invalid call"));
+        }
+        let (selector, mut input) = call.split_at(4);
+        let selector = [selector[0], selector[1], selector[2], selector[3]];
+        let input = &mut input;
+        let output = match selector {
+            selectors::TOTAL_SUPPLY => {
+                decode_args::<()>(input)?;
+                self.total_supply().encode()
+            }
+            selectors::BALANCE_OF => {
+                let owner = decode_args(input)?;
+                self.balance_of(owner).encode()
+            }
+            selectors::ALLOWANCE => {
+                let (owner, spender) = decode_args(input)?;
+                self.allowance(owner, spender).encode()
+            }
+            selectors::TRANSFER => {
+                let (to, value, data) = decode_args(input)?;
+                events.extend(self.transfer_with_data(caller, to, value, data)?);
+                success()
+            }
+            selectors::TRANSFER_FROM => {
+                let (from, to, value, data) = decode_args(input)?;
+                events.extend(self.transfer_from_with_data(caller, from, to, value, data)?);
+                success()
+            }
+            selectors::APPROVE => {
+                let (spender, value) = decode_args(input)?;
+                events.extend(self.approve(caller, spender, value)?);
+                success()
+            }
+            selectors::INCREASE_ALLOWANCE => {
+                let (spender, delta_value) = decode_args(input)?;
+                events.extend(self.increase_allowance(caller, spender, delta_value)?);
+                success()
+            }
+            selectors::DECREASE_ALLOWANCE => {
+                let (spender, delta_value) = decode_args(input)?;
+                events.extend(self.decrease_allowance(caller, spender, delta_value)?);
+                success()
+            }
+            _ => return Err(PSP22Error::custom("invalid call")),
+        };
+        Ok(output)
+    }
+}
+
+// Decodes the arguments of a call, which must take the whole input.
+fn decode_args<T: Decode>(input: &mut &[u8]) -> Result<T, PSP22Error> {
+    let args = T::decode(input).map_err(|_| PSP22Error::custom("invalid call"))?;
+    if !input.is_empty() {
+        return Err(PSP22Error::custom("invalid call"));
+    }
+    Ok(args)
+}
+
+// The encoded return value of a successful state-changing message.
+fn success() -> Vec<u8> {
+    Ok::<(), PSP22Error>(()).encode()
+}
+
+/// Encodes a call of the `selector` message with `args` for `multicall`
+/// (e.g. `encode_call(selectors::APPROVE, (spender, value))`).
+pub fn encode_call<Args: Encode>(selector: [u8; 4], args: Args) -> Vec<u8> {
+    let mut call = vec![];
+    call.extend_from_slice(&selector);
+    args.encode_to(&mut call);
+    call
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink::env::{test::default_accounts, DefaultEnvironment as E};
+
+    #[ink::test]
+    fn calls_are_executed_in_order() {
+        let acc = default_accounts::<E>();
+        let (mut data, _) = PSP22Data::new(1000, acc.alice);
+        assert!(data.approve(acc.alice, acc.bob, 100).is_ok());
+
+        let calls = vec![
+            encode_call(
+                selectors::TRANSFER_FROM,
+                (acc.alice, acc.bob, 60u128, Vec::<u8>::new()),
+            ),
+            encode_call(selectors::APPROVE, (acc.charlie, 60u128)),
+            encode_call(selectors::BALANCE_OF, acc.bob),
+            encode_call(selectors::TOTAL_SUPPLY, ()),
+        ];
+        let (outputs, events) = data.multicall(acc.bob, calls).unwrap();
+
+        assert_eq!(
+            outputs,
+            vec![vec![0], vec![0], 60u128.encode(), 1000u128.encode()]
+        );
+        assert!(matches!(
+            &events[..],
+            [PSP22Event::Approval(_), PSP22Event::Transfer(_), PSP22Event::Approval(e)]
+                if e.owner == acc.bob && e.spender == acc.charlie && e.amount == 60
+        ));
+        assert_eq!(data.balance_of(acc.bob), 60);
+        assert_eq!(data.allowance(acc.alice, acc.bob), 40);
+        assert_eq!(data.allowance(acc.bob, acc.charlie), 60);
+    }
+
+    #[ink::test]
+    fn failing_call_stops_multicall() {
+        let acc = default_accounts::<E>();
+        let (mut data, _) = PSP22Data::new(1000, acc.alice);
+
+        let calls = vec![
+            encode_call(selectors::APPROVE, (acc.bob, 10u128)),
+            encode_call(selectors::TRANSFER, (acc.bob, 1001u128, Vec::<u8>::new())),
+            encode_call(selectors::APPROVE, (acc.charlie, 10u128)),
+        ];
+        assert_eq!(
+            data.multicall(acc.alice, calls).err(),
+            Some(PSP22Error::InsufficientBalance)
+        );
+        assert_eq!(data.allowance(acc.alice, acc.charlie), 0);
+    }
+
+    #[ink::test]
+    fn invalid_calls_are_rejected() {
+        let acc = default_accounts::<E>();
+        let (mut data, _) = PSP22Data::new(1000, acc.alice);
+        let approve = encode_call(selectors::APPROVE, (acc.bob, 10u128));
+
+        let mut trailing = approve.clone();
+        trailing.push(0);
+        for call in [
+            vec![],
+            approve[..3].to_vec(),
+            approve[..10].to_vec(),
+            trailing,
+            encode_call(selectors::MINT, 10u128),
+            encode_call(selectors::TRANSFER, (acc.bob, 10u128)),
+        ] {
+            assert_eq!(
+                data.multicall(acc.alice, vec![call]).err(),
+                Some(PSP22Error::custom("invalid call"))
+            );
+        }
+        assert_eq!(
+            data.multicall(acc.alice, vec![approve; MAX_MULTICALL_CALLS + 1])
+                .err(),
+            Some(PSP22Error::custom("too many calls"))
+        );
+        assert_eq!(data.allowance(acc.alice, acc.bob), 0);
+    }
+}
@@ -0,0 +1,129 @@
+use crate::errors::PSP22Error;
+use ink::{primitives::AccountId, storage::Mapping};
+
+/// Per-account nonces protecting signed messages (permits, meta-transactions, bridge
+/// messages etc.) from being replayed: each message carries the nonce of its signer,
+/// which is consumed when the message is executed.
+///
+/// Nonces start at `0` and only move forward. The last nonce, `u64::MAX`, is never
+/// consumed: an account whose nonce reaches it (only possible with `invalidate_nonces`)
+/// cannot execute signed messages anymore, instead of having the last nonce reused.
+#[ink::storage_item]
+#[derive(Debug, Default)]
+pub struct NoncesData {
+    nonces: Mapping<AccountId, u64>,
+}
+
+impl NoncesData {
+    /// Returns the nonce to be used by the next message of `account`.
+    pub fn nonce_of(&self, account: AccountId) -> u64 {
+        self.nonces.get(account).unwrap_or_default()
+    }
+
+    /// Consumes the nonce of `account` and returns it.
+    ///
+    /// Panics if the nonces of `account` are exhausted, reverting the transaction.
+    pub fn use_nonce(&mut self, account: AccountId) -> u64 {
+        let current = self.nonce_of(account);
+        let next = current.checked_add(1).expect("nonces exhausted");
+        self.nonces.insert(account, &next);
+        current
+    }
+
+    /// Consumes the nonce of `account`, provided that it is equal to `expected`.
+    ///
+    /// Returns `NonceMismatch` error if `expected` is not the current nonce of `account`
+    /// and `Custom (nonces exhausted)` error if the current nonce is `u64::MAX`.
+    pub fn use_checked_nonce(
+        &mut self,
+        account: AccountId,
+        expected: u64,
+    ) -> Result<(), PSP22Error> {
+        let current = self.nonce_of(account);
+        if expected != current {
+            return Err(PSP22Error::NonceMismatch);
+        }
+        let next = current
+            .checked_add(1)
+            .ok_or(PSP22Error::custom("nonces exhausted"))?;
+        self.nonces.insert(account, &next);
+        Ok(())
+    }
+
+    /// Advances the nonce of `account` to `new_nonce`, invalidating all messages
+    /// signed by `account` but not executed yet (e.g. after a key compromise).
+    ///
+    /// Returns `Custom` error if `new_nonce` is not greater than the current nonce.
+    pub fn invalidate_nonces(
+        &mut self,
+        account: AccountId,
+        new_nonce: u64,
+    ) -> Result<(), PSP22Error> {
+        if new_nonce <= self.nonce_of(account) {
+            return Err(PSP22Error::custom("nonce can only move forward"));
+        }
+        self.nonces.insert(account, &new_nonce);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink::env::{test::default_accounts, DefaultEnvironment as E};
+
+    #[ink::test]
+    fn use_nonce_returns_and_increments() {
+        let acc = default_accounts::<E>();
+        let mut nonces = NoncesData::default();
+
+        assert_eq!(nonces.nonce_of(acc.alice), 0);
+        assert_eq!(nonces.use_nonce(acc.alice), 0);
+        assert_eq!(nonces.use_nonce(acc.alice), 1);
+        assert_eq!(nonces.nonce_of(acc.alice), 2);
+        // Nonces are per account
+        assert_eq!(nonces.nonce_of(acc.bob), 0);
+    }
+
+    #[ink::test]
+    fn use_checked_nonce_rejects_wrong_nonce() {
+        let acc = default_accounts::<E>();
+        let mut nonces = NoncesData::default();
+
+        assert_eq!(
+            nonces.use_checked_nonce(acc.alice, 1),
+            Err(PSP22Error::NonceMismatch)
+        );
+        assert_eq!(nonces.use_checked_nonce(acc.alice, 0), Ok(()));
+        assert_eq!(
+            nonces.use_checked_nonce(acc.alice, 0),
+            Err(PSP22Error::NonceMismatch)
+        );
+        assert_eq!(nonces.nonce_of(acc.alice), 1);
+    }
+
+    #[ink::test]
+    fn last_nonce_is_never_used() {
+        let acc = default_accounts::<E>();
+        let mut nonces = NoncesData::default();
+        assert_eq!(nonces.invalidate_nonces(acc.alice, u64::MAX - 1), Ok(()));
+
+        assert_eq!(nonces.use_nonce(acc.alice), u64::MAX - 1);
+        assert_eq!(
+            nonces.use_checked_nonce(acc.alice, u64::MAX),
+            Err(PSP22Error::custom("nonces exhausted"))
+        );
+        assert_eq!(nonces.nonce_of(acc.alice), u64::MAX);
+        assert!(nonces.invalidate_nonces(acc.alice, u64::MAX).is_err());
+    }
+
+    #[ink::test]
+    #[should_panic(expected = "nonces exhausted")]
+    fn use_nonce_panics_when_exhausted() {
+        let acc = default_accounts::<E>();
+        let mut nonces = NoncesData::default();
+        assert_eq!(nonces.invalidate_nonces(acc.alice, u64::MAX), Ok(()));
+
+        nonces.use_nonce(acc.alice);
+    }
+}
@@ -0,0 +1,234 @@
+use crate::data::PSP22Event;
+use crate::errors::OwnableError;
+use crate::events::OwnershipTransferred;
+use ink::{
+    prelude::{vec, vec::Vec},
+    primitives::AccountId,
+};
+
+/// A class implementing the internal logic of the `Ownable` trait: a single owner allowed
+/// to call privileged messages, like `PSP22Data` implements the logic of `PSP22`.
+///
+/// The contract calls `ensure_owner(caller)` at the start of each privileged message.
+/// After `renounce_ownership` there is no owner and privileged messages cannot be called
+/// by anyone anymore.
+///
+/// Next to the immediate `transfer_ownership`, ownership can be transferred in two steps:
+/// the owner nominates a pending owner with `begin_ownership_transfer`, who becomes
+/// the owner only after calling `accept_ownership`. This protects against transferring
+/// ownership to a mistyped address.
+#[ink::storage_item]
+#[derive(Debug, Default)]
+pub struct OwnableData {
+    owner: Option<AccountId>,
+    pending_owner: Option<AccountId>,
+}
+
+impl OwnableData {
+    /// Creates a new ownable state with `owner` as the owner.
+    pub fn new(owner: AccountId) -> OwnableData {
+        OwnableData {
+            owner: Some(owner),
+            pending_owner: None,
+        }
+    }
+
+    /// Returns the current owner, `None` if ownership has been renounced.
+    pub fn owner(&self) -> Option<AccountId> {
+        self.owner
+    }
+
+    /// Returns the account nominated with `begin_ownership_transfer`, if any.
+    pub fn pending_owner(&self) -> Option<AccountId> {
+        self.pending_owner
+    }
+
+    /// Returns `CallerIsNotOwner` error if `caller` is not the owner.
+    pub fn ensure_owner(&self, caller: AccountId) -> Result<(), OwnableError> {
+        if self.owner != Some(caller) {
+            return Err(OwnableError::CallerIsNotOwner);
+        }
+        Ok(())
+    }
+
+    /// Makes `new_owner` the owner, on behalf of `caller`.
+    ///
+    /// Returns `CallerIsNotOwner` error if `caller` is not the owner and `NewOwnerIsZero`
+    /// error if `new_owner` is the zero address (use `renounce_ownership` instead).
+    pub fn transfer_ownership(
+        &mut self,
+        caller: AccountId,
+        new_owner: AccountId,
+    ) -> Result<Vec<PSP22Event>, OwnableError> {
+        self.ensure_owner(caller)?;
+        if new_owner == AccountId::from([0; 32]) {
+            return Err(OwnableError::NewOwnerIsZero);
+        }
+        Ok(self.set_owner(Some(new_owner)))
+    }
+
+    /// Nominates `new_owner` as the pending owner, on behalf of `caller`, replacing
+    /// the previous nomination. The owner does not change until `accept_ownership`.
+    ///
+    /// Returns `CallerIsNotOwner` error if `caller` is not the owner and `NewOwnerIsZero`
+    /// error if `new_owner` is the zero address.
+    pub fn begin_ownership_transfer(
+        &mut self,
+        caller: AccountId,
+        new_owner: AccountId,
+    ) -> Result<(), OwnableError> {
+        self.ensure_owner(caller)?;
+        if new_owner == AccountId::from([0; 32]) {
+            return Err(OwnableError::NewOwnerIsZero);
+        }
+        self.pending_owner = Some(new_owner);
+        Ok(())
+    }
+
+    /// Makes `caller` the owner, provided that it is the pending owner.
+    ///
+    /// Returns `NotPendingOwner` error otherwise.
+    pub fn accept_ownership(&mut self, caller: AccountId) -> Result<Vec<PSP22Event>, OwnableError> {
+        if self.pending_owner != Some(caller) {
+            return Err(OwnableError::NotPendingOwner);
+        }
+        Ok(self.set_owner(Some(caller)))
+    }
+
+    /// Leaves the contract without an owner, on behalf of `caller`.
+    ///
+    /// Returns `CallerIsNotOwner` error if `caller` is not the owner.
+    pub fn renounce_ownership(
+        &mut self,
+        caller: AccountId,
+    ) -> Result<Vec<PSP22Event>, OwnableError> {
+        self.ensure_owner(caller)?;
+        Ok(self.set_owner(None))
+    }
+
+    fn set_owner(&mut self, new_owner: Option<AccountId>) -> Vec<PSP22Event> {
+        let previous_owner = self.owner;
+        self.owner = new_owner;
+        self.pending_owner = None;
+        vec![PSP22Event::OwnershipTransferred(OwnershipTransferred {
+            previous_owner,
+            new_owner,
+        })]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink::env::{test::default_accounts, DefaultEnvironment as E};
+
+    #[ink::test]
+    fn only_owner_passes_the_guard() {
+        let acc = default_accounts::<E>();
+        let ownable = OwnableData::new(acc.alice);
+
+        assert_eq!(ownable.owner(), Some(acc.alice));
+        assert!(ownable.ensure_owner(acc.alice).is_ok());
+        assert_eq!(
+            ownable.ensure_owner(acc.bob),
+            Err(OwnableError::CallerIsNotOwner)
+        );
+        assert_eq!(
+            OwnableData::default().ensure_owner(acc.alice),
+            Err(OwnableError::CallerIsNotOwner)
+        );
+    }
+
+    #[ink::test]
+    fn ownership_can_be_transferred_by_owner() {
+        let acc = default_accounts::<E>();
+        let mut ownable = OwnableData::new(acc.alice);
+
+        assert_eq!(
+            ownable.transfer_ownership(acc.bob, acc.bob).err(),
+            Some(OwnableError::CallerIsNotOwner)
+        );
+        assert_eq!(
+            ownable
+                .transfer_ownership(acc.alice, AccountId::from([0; 32]))
+                .err(),
+            Some(OwnableError::NewOwnerIsZero)
+        );
+        let events = ownable.transfer_ownership(acc.alice, acc.bob).unwrap();
+        assert!(matches!(
+            &events[..],
+            [PSP22Event::OwnershipTransferred(e)]
+                if e.previous_owner == Some(acc.alice) && e.new_owner == Some(acc.bob)
+        ));
+        assert_eq!(ownable.owner(), Some(acc.bob));
+        assert!(ownable.ensure_owner(acc.alice).is_err());
+        assert!(ownable.ensure_owner(acc.bob).is_ok());
+    }
+
+    #[ink::test]
+    fn two_step_transfer_needs_acceptance_by_pending_owner() {
+        let acc = default_accounts::<E>();
+        let mut ownable = OwnableData::new(acc.alice);
+
+        assert_eq!(
+            ownable.begin_ownership_transfer(acc.bob, acc.bob),
+            Err(OwnableError::CallerIsNotOwner)
+        );
+        assert_eq!(
+            ownable.begin_ownership_transfer(acc.alice, AccountId::from([0; 32])),
+            Err(OwnableError::NewOwnerIsZero)
+        );
+        assert_eq!(
+            ownable.accept_ownership(acc.bob).err(),
+            Some(OwnableError::NotPendingOwner)
+        );
+        assert!(ownable.begin_ownership_transfer(acc.alice, acc.bob).is_ok());
+        assert_eq!(ownable.pending_owner(), Some(acc.bob));
+        assert_eq!(ownable.owner(), Some(acc.alice));
+        assert_eq!(
+            ownable.accept_ownership(acc.charlie).err(),
+            Some(OwnableError::NotPendingOwner)
+        );
+
+        let events = ownable.accept_ownership(acc.bob).unwrap();
+        assert!(matches!(
+            &events[..],
+            [PSP22Event::OwnershipTransferred(e)]
+                if e.previous_owner == Some(acc.alice) && e.new_owner == Some(acc.bob)
+        ));
+        assert_eq!(ownable.owner(), Some(acc.bob));
+        assert_eq!(ownable.pending_owner(), None);
+        assert_eq!(
+            ownable.accept_ownership(acc.bob).err(),
+            Some(OwnableError::NotPendingOwner)
+        );
+    }
+
+    #[ink::test]
+    fn renounced_ownership_cannot_be_regained() {
+        let acc = default_accounts::<E>();
+        let mut ownable = OwnableData::new(acc.alice);
+
+        assert_eq!(
+            ownable.renounce_ownership(acc.bob).err(),
+            Some(OwnableError::CallerIsNotOwner)
+        );
+        assert!(ownable.begin_ownership_transfer(acc.alice, acc.bob).is_ok());
+        let events = ownable.renounce_ownership(acc.alice).unwrap();
+        assert!(matches!(
+            &events[..],
+            [PSP22Event::OwnershipTransferred(e)]
+                if e.previous_owner == Some(acc.alice) && e.new_owner.is_none()
+        ));
+        assert_eq!(ownable.owner(), None);
+        assert_eq!(
+            ownable.transfer_ownership(acc.alice, acc.alice).err(),
+            Some(OwnableError::CallerIsNotOwner)
+        );
+        // Renouncing cancels the pending transfer
+        assert_eq!(
+            ownable.accept_ownership(acc.bob).err(),
+            Some(OwnableError::NotPendingOwner)
+        );
+    }
+}
@@ -0,0 +1,126 @@
+use crate::errors::PSP22Error;
+use ink::storage::Lazy;
+
+/// A reentrancy guard for contracts making cross-contract calls while their state is
+/// being modified, e.g. `PSP22WrapperData::deposit` and `PSP22WrapperData::withdraw`.
+///
+/// The flag is kept in its own storage cell (`Lazy`), so that it is written to storage
+/// immediately on `enter`. A flag kept directly in the contract struct would be written
+/// only at the end of the message, after any nested call had already read the old value.
+///
+/// Note that contracts are not reentrant by default: a cross-contract call made without
+/// the `allow_reentry` flag cannot call back into the caller. The guard protects also
+/// messages of contracts which do allow reentry.
+#[ink::storage_item]
+#[derive(Debug, Default)]
+pub struct ReentrancyGuardData {
+    entered: Lazy<bool>,
+}
+
+impl ReentrancyGuardData {
+    /// Marks the start of a guarded section.
+    ///
+    /// Returns `Custom (ReentrancyDetected)` error if the guarded section has already been
+    /// entered and not exited, i.e. the current call is nested in a guarded one.
+    pub fn enter(&mut self) -> Result<(), PSP22Error> {
+        if self.is_entered() {
+            return Err(PSP22Error::custom("ReentrancyDetected"));
+        }
+        self.entered.set(&true);
+        Ok(())
+    }
+
+    /// Marks the end of a guarded section.
+    pub fn exit(&mut self) {
+        self.entered.set(&false);
+    }
+
+    /// Returns `true` inside a guarded section.
+    pub fn is_entered(&self) -> bool {
+        self.entered.get().unwrap_or_default()
+    }
+}
+
+/// Runs `$body` as a section guarded by the `ReentrancyGuardData` at `$guard`, e.g.:
+///
+/// ```ignore
+/// #[ink(message)]
+/// fn withdraw_to(&mut self, account: AccountId, value: u128) -> Result<(), PSP22Error> {
+///     psp22::non_reentrant!(self.guard, {
+///         let events = self.wrapper.withdraw(&mut self.data, self.env().caller(), account, value)?;
+///         self.emit_events(events);
+///         Ok(())
+///     })
+/// }
+/// ```
+///
+/// `$body` must evaluate to `Result<_, PSP22Error>`. The guard is exited also when `$body`
+/// returns early with an error (`?` and `return` leave only `$body`, not the whole message).
+/// If the section is already entered, returns `Custom (ReentrancyDetected)` error without
+/// running `$body`.
+#[macro_export]
+macro_rules! non_reentrant {
+    ($guard:expr, $body:block) => {{
+        $guard.enter()?;
+        #[allow(clippy::redundant_closure_call)]
+        let result = (|| -> ::core::result::Result<_, $crate::PSP22Error> { $body })();
+        $guard.exit();
+        result
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[ink::test]
+    fn nested_entry_fails() {
+        let mut guard = ReentrancyGuardData::default();
+
+        assert!(!guard.is_entered());
+        assert!(guard.enter().is_ok());
+        assert_eq!(guard.enter(), Err(PSP22Error::custom("ReentrancyDetected")));
+        guard.exit();
+        assert!(guard.enter().is_ok());
+    }
+
+    struct Contract {
+        guard: ReentrancyGuardData,
+        calls: u32,
+    }
+
+    impl Contract {
+        fn guarded(&mut self, fail: bool) -> Result<u32, PSP22Error> {
+            non_reentrant!(self.guard, {
+                if fail {
+                    return Err(PSP22Error::InsufficientBalance);
+                }
+                self.calls += 1;
+                Ok(self.calls)
+            })
+        }
+
+        fn nested(&mut self) -> Result<u32, PSP22Error> {
+            non_reentrant!(self.guard, { self.guarded(false) })
+        }
+    }
+
+    #[ink::test]
+    fn macro_exits_guard_on_success_and_error() {
+        let mut contract = Contract {
+            guard: Default::default(),
+            calls: 0,
+        };
+
+        assert_eq!(contract.guarded(false), Ok(1));
+        assert_eq!(contract.guarded(true), Err(PSP22Error::InsufficientBalance));
+        assert!(!contract.guard.is_entered());
+        assert_eq!(contract.guarded(false), Ok(2));
+
+        assert_eq!(
+            contract.nested(),
+            Err(PSP22Error::custom("ReentrancyDetected"))
+        );
+        assert_eq!(contract.calls, 2);
+    }
+}
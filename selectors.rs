@@ -0,0 +1,91 @@
+//! Selectors of the messages of `PSP22`, `PSP22Metadata`, `PSP22Mintable` and `PSP22Burnable`,
+//! for calling PSP22 tokens with `build_call` (e.g. by the callers in `callers.rs`).
+//!
+//! Each selector is the first 4 bytes of `blake2b_256` of the trait and message name,
+//! e.g. `blake2b_256("PSP22::transfer")` for `TRANSFER`, computed at compile time
+//! with `ink::selector_bytes!` like the selectors of the messages themselves.
+//! The values are also pinned below, as changing any of them breaks compatibility
+//! with deployed tokens.
+
+/// Selector of `PSP22::total_supply`.
+pub const TOTAL_SUPPLY: [u8; 4] = ink::selector_bytes!("PSP22::total_supply");
+/// Selector of `PSP22::balance_of`.
+pub const BALANCE_OF: [u8; 4] = ink::selector_bytes!("PSP22::balance_of");
+/// Selector of `PSP22::allowance`.
+pub const ALLOWANCE: [u8; 4] = ink::selector_bytes!("PSP22::allowance");
+/// Selector of `PSP22::transfer`.
+pub const TRANSFER: [u8; 4] = ink::selector_bytes!("PSP22::transfer");
+/// Selector of `PSP22::transfer_from`.
+pub const TRANSFER_FROM: [u8; 4] = ink::selector_bytes!("PSP22::transfer_from");
+/// Selector of `PSP22::approve`.
+pub const APPROVE: [u8; 4] = ink::selector_bytes!("PSP22::approve");
+/// Selector of `PSP22::increase_allowance`.
+pub const INCREASE_ALLOWANCE: [u8; 4] = ink::selector_bytes!("PSP22::increase_allowance");
+/// Selector of `PSP22::decrease_allowance`.
+pub const DECREASE_ALLOWANCE: [u8; 4] = ink::selector_bytes!("PSP22::decrease_allowance");
+/// Selector of `PSP22Metadata::token_name`.
+pub const TOKEN_NAME: [u8; 4] = ink::selector_bytes!("PSP22Metadata::token_name");
+/// Selector of `PSP22Metadata::token_symbol`.
+pub const TOKEN_SYMBOL: [u8; 4] = ink::selector_bytes!("PSP22Metadata::token_symbol");
+/// Selector of `PSP22Metadata::token_decimals`.
+pub const TOKEN_DECIMALS: [u8; 4] = ink::selector_bytes!("PSP22Metadata::token_decimals");
+/// Selector of `PSP22Metadata::token_uri`.
+pub const TOKEN_URI: [u8; 4] = ink::selector_bytes!("PSP22Metadata::token_uri");
+/// Selector of `PSP22Mintable::mint`.
+pub const MINT: [u8; 4] = ink::selector_bytes!("PSP22Mintable::mint");
+/// Selector of `PSP22Burnable::burn`.
+pub const BURN: [u8; 4] = ink::selector_bytes!("PSP22Burnable::burn");
+
+// Arrays cannot be compared in constants, so the selectors are compared as `u32`.
+// Fails to compile if any of them changes.
+const _: () = {
+    assert!(u32::from_be_bytes(TOTAL_SUPPLY) == 0x162df8c2);
+    assert!(u32::from_be_bytes(BALANCE_OF) == 0x6568382f);
+    assert!(u32::from_be_bytes(ALLOWANCE) == 0x4d47d921);
+    assert!(u32::from_be_bytes(TRANSFER) == 0xdb20f9f5);
+    assert!(u32::from_be_bytes(TRANSFER_FROM) == 0x54b3c76e);
+    assert!(u32::from_be_bytes(APPROVE) == 0xb20f1bbd);
+    assert!(u32::from_be_bytes(INCREASE_ALLOWANCE) == 0x96d6b57a);
+    assert!(u32::from_be_bytes(DECREASE_ALLOWANCE) == 0xfecb57d5);
+    assert!(u32::from_be_bytes(TOKEN_NAME) == 0x3d261bd4);
+    assert!(u32::from_be_bytes(TOKEN_SYMBOL) == 0x34205be5);
+    assert!(u32::from_be_bytes(TOKEN_DECIMALS) == 0x7271b782);
+    assert!(u32::from_be_bytes(TOKEN_URI) == 0xd71a2793);
+    assert!(u32::from_be_bytes(MINT) == 0xfc3c75d4);
+    assert!(u32::from_be_bytes(BURN) == 0x7a9da510);
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink::env::hash::{Blake2x256, HashOutput};
+
+    fn blake2b_prefix(input: &[u8]) -> [u8; 4] {
+        let mut output = <Blake2x256 as HashOutput>::Type::default();
+        ink::env::hash_bytes::<Blake2x256>(input, &mut output);
+        [output[0], output[1], output[2], output[3]]
+    }
+
+    #[test]
+    fn selectors_match_hashes() {
+        let cases = [
+            ("PSP22::total_supply", TOTAL_SUPPLY),
+            ("PSP22::balance_of", BALANCE_OF),
+            ("PSP22::allowance", ALLOWANCE),
+            ("PSP22::transfer", TRANSFER),
+            ("PSP22::transfer_from", TRANSFER_FROM),
+            ("PSP22::approve", APPROVE),
+            ("PSP22::increase_allowance", INCREASE_ALLOWANCE),
+            ("PSP22::decrease_allowance", DECREASE_ALLOWANCE),
+            ("PSP22Metadata::token_name", TOKEN_NAME),
+            ("PSP22Metadata::token_symbol", TOKEN_SYMBOL),
+            ("PSP22Metadata::token_decimals", TOKEN_DECIMALS),
+            ("PSP22Metadata::token_uri", TOKEN_URI),
+            ("PSP22Mintable::mint", MINT),
+            ("PSP22Burnable::burn", BURN),
+        ];
+        for (name, selector) in cases {
+            assert_eq!(blake2b_prefix(name.as_bytes()), selector, "{name}");
+        }
+    }
+}
@@ -12,16 +12,25 @@ macro_rules! tests {
             use ink::env::test::*;
             use ink::env::DefaultEnvironment as E;
             use ink::primitives::AccountId;
-            use $crate::{Approval, PSP22Error, Transfer, PSP22};
+            use $crate::{Approval, PSP22Error, Transfer, TransferWithData, PSP22};
 
             // Gathers all emitted events, skip `shift` first, and return as a vector.
             fn get_events(shift: usize) -> Vec<EmittedEvent> {
                 recorded_events().skip(shift).collect()
             }
 
+            // Checks if the given event is a `T` by its signature topic or, if `T` is anonymous
+            // (the `anonymous-events` feature of psp22), by decoding all of its data
+            fn is_event<T: ink::env::Event + ink::scale::Decode>(event: &EmittedEvent) -> bool {
+                match T::SIGNATURE_TOPIC {
+                    Some(topic) => event.topics.first().is_some_and(|first| first[..] == topic),
+                    None => <T as ink::scale::DecodeAll>::decode_all(&mut &event.data[..]).is_ok(),
+                }
+            }
+
             // Checks if the given event is a Transfer
             fn is_transfer(event: &EmittedEvent) -> bool {
-                <Transfer as ink::scale::Decode>::decode(&mut &event.data[..]).is_ok()
+                is_event::<Transfer>(event)
             }
 
             // Asserts if the given event is a Transfer with particular from_, to_ and value_
@@ -31,6 +40,7 @@ macro_rules! tests {
                 to: Option<AccountId>,
                 value: u128,
             ) {
+                assert!(is_transfer(event), "Event is not Transfer");
                 let e = <Transfer as ink::scale::Decode>::decode(&mut &event.data[..])
                     .expect("Event is not Transfer");
                 assert_eq!(e.from, from, "Transfer event: 'from' mismatch");
@@ -38,6 +48,22 @@ macro_rules! tests {
                 assert_eq!(e.value, value, "Transfer event: 'value' mismatch");
             }
 
+            // Asserts if the given event is a TransferWithData with particular from_, to_, value_ and data_
+            fn assert_transfer_with_data(
+                event: &EmittedEvent,
+                from: Option<AccountId>,
+                to: Option<AccountId>,
+                value: u128,
+                data: &[u8],
+            ) {
+                let e = <TransferWithData as ink::scale::Decode>::decode(&mut &event.data[..])
+                    .expect("Event is not TransferWithData");
+                assert_eq!(e.from, from, "TransferWithData event: 'from' mismatch");
+                assert_eq!(e.to, to, "TransferWithData event: 'to' mismatch");
+                assert_eq!(e.value, value, "TransferWithData event: 'value' mismatch");
+                assert_eq!(e.data, data, "TransferWithData event: 'data' mismatch");
+            }
+
             // Asserts if the given event is a Approval with particular owner_, spender_ and amount_
             fn assert_approval(
                 event: &EmittedEvent,
@@ -45,6 +71,7 @@ macro_rules! tests {
                 spender: AccountId,
                 amount: u128,
             ) {
+                assert!(is_event::<Approval>(event), "Event is not Approval");
                 let e = <Approval as ink::scale::Decode>::decode(&mut &event.data[..])
                     .expect("Event is not Approval");
                 assert_eq!(e.owner, owner, "Approval event: 'owner' mismatch");
@@ -149,6 +176,27 @@ macro_rules! tests {
                 assert_transfer(&events[0], Some(acc.alice), Some(acc.bob), value);
             }
 
+            #[ink::test]
+            fn transfer_with_data_emits_data_event() {
+                let acc = default_accounts::<E>();
+                set_caller::<E>(acc.alice);
+                let (supply, value) = (1000, 100);
+                let mut token = $constructor(supply);
+                let start = recorded_events().count();
+
+                assert!(token.transfer(acc.bob, value, vec![1, 2, 3]).is_ok());
+                let events = get_events(start);
+                assert_eq!(events.len(), 2);
+                assert_transfer(&events[0], Some(acc.alice), Some(acc.bob), value);
+                assert_transfer_with_data(
+                    &events[1],
+                    Some(acc.alice),
+                    Some(acc.bob),
+                    value,
+                    &[1, 2, 3],
+                );
+            }
+
             #[ink::test]
             fn constructor_emits_event() {
                 let acc = default_accounts::<E>();
@@ -524,6 +572,37 @@ macro_rules! tests {
                 }
             }
 
+            #[ink::test]
+            fn transfer_from_with_data_emits_data_event() {
+                let acc = default_accounts::<E>();
+                set_caller::<E>(acc.alice);
+                let (supply, value) = (1000, 100);
+                let mut token = $constructor(supply);
+
+                assert!(token.approve(acc.bob, value).is_ok());
+                let start = recorded_events().count();
+                set_caller::<E>(acc.bob);
+                assert!(token
+                    .transfer_from(acc.alice, acc.charlie, value, vec![4, 5])
+                    .is_ok());
+                let events = get_events(start);
+                assert_eq!(events.len(), 3);
+                if is_transfer(&events[0]) {
+                    assert_transfer(&events[0], Some(acc.alice), Some(acc.charlie), value);
+                    assert_approval(&events[1], acc.alice, acc.bob, 0);
+                } else {
+                    assert_approval(&events[0], acc.alice, acc.bob, 0);
+                    assert_transfer(&events[1], Some(acc.alice), Some(acc.charlie), value);
+                }
+                assert_transfer_with_data(
+                    &events[2],
+                    Some(acc.alice),
+                    Some(acc.charlie),
+                    value,
+                    &[4, 5],
+                );
+            }
+
             #[ink::test]
             fn transfer_from_fails_with_insufficient_allowance() {
                 let acc = default_accounts::<E>();
@@ -636,17 +715,237 @@ macro_rules! tests {
             fn transfer_from_to_the_same_address_is_no_op() {
                 let acc = default_accounts::<E>();
                 set_caller::<E>(acc.alice);
-                let supply = 1000;
+                let (supply, value) = (1000, 100);
                 let mut token = $constructor(supply);
+                assert!(token.approve(acc.bob, value).is_ok());
                 let start = recorded_events().count();
 
                 set_caller::<E>(acc.bob);
                 assert!(token
-                    .transfer_from(acc.alice, acc.alice, 2 * supply, vec![])
+                    .transfer_from(acc.alice, acc.alice, value, vec![])
                     .is_ok());
 
                 let events = get_events(start);
                 assert_eq!(events.len(), 0);
+                assert_eq!(token.balance_of(acc.alice), supply);
+                assert_eq!(token.allowance(acc.alice, acc.bob), value);
+            }
+
+            #[ink::test]
+            fn transfer_from_to_the_same_address_checks_balance() {
+                let acc = default_accounts::<E>();
+                set_caller::<E>(acc.alice);
+                let supply = 1000;
+                let mut token = $constructor(supply);
+                assert!(token.approve(acc.bob, 2 * supply).is_ok());
+                let start = recorded_events().count();
+
+                set_caller::<E>(acc.bob);
+                assert_eq!(
+                    token.transfer_from(acc.alice, acc.alice, supply + 1, vec![]),
+                    Err(PSP22Error::InsufficientBalance)
+                );
+                set_caller::<E>(acc.alice);
+                assert_eq!(
+                    token.transfer_from(acc.alice, acc.alice, supply + 1, vec![]),
+                    Err(PSP22Error::InsufficientBalance)
+                );
+                assert_eq!(
+                    token.transfer(acc.alice, supply + 1, vec![]),
+                    Err(PSP22Error::InsufficientBalance)
+                );
+
+                let events = get_events(start);
+                assert_eq!(events.len(), 0);
+                assert_eq!(token.allowance(acc.alice, acc.bob), 2 * supply);
+            }
+
+            #[ink::test]
+            fn transfer_from_to_the_same_address_checks_allowance() {
+                let acc = default_accounts::<E>();
+                set_caller::<E>(acc.alice);
+                let (supply, value) = (1000, 100);
+                let mut token = $constructor(supply);
+                assert!(token.approve(acc.bob, value).is_ok());
+                let start = recorded_events().count();
+
+                set_caller::<E>(acc.bob);
+                assert_eq!(
+                    token.transfer_from(acc.alice, acc.alice, value + 1, vec![]),
+                    Err(PSP22Error::InsufficientAllowance)
+                );
+
+                let events = get_events(start);
+                assert_eq!(events.len(), 0);
+                assert_eq!(token.allowance(acc.alice, acc.bob), value);
+            }
+
+            #[ink::test]
+            fn transfer_of_max_supply_works() {
+                let acc = default_accounts::<E>();
+                set_caller::<E>(acc.alice);
+                let supply = u128::MAX;
+                let mut token = $constructor(supply);
+
+                assert!(token.transfer(acc.bob, supply - 1, vec![]).is_ok());
+                assert!(token.transfer(acc.bob, 1, vec![]).is_ok());
+
+                assert_eq!(token.total_supply(), supply);
+                assert_eq!(token.balance_of(acc.alice), 0);
+                assert_eq!(token.balance_of(acc.bob), supply);
+            }
+
+            #[ink::test]
+            fn transfer_from_with_max_allowance_works() {
+                let acc = default_accounts::<E>();
+                set_caller::<E>(acc.alice);
+                let supply = u128::MAX;
+                let mut token = $constructor(supply);
+
+                assert!(token.approve(acc.bob, u128::MAX).is_ok());
+                set_caller::<E>(acc.bob);
+                assert!(token
+                    .transfer_from(acc.alice, acc.charlie, supply, vec![])
+                    .is_ok());
+
+                assert_eq!(token.allowance(acc.alice, acc.bob), 0);
+                assert_eq!(token.balance_of(acc.alice), 0);
+                assert_eq!(token.balance_of(acc.charlie), supply);
+            }
+
+            #[ink::test]
+            fn increase_allowance_overflow_fails() {
+                let acc = default_accounts::<E>();
+                set_caller::<E>(acc.alice);
+                let mut token = $constructor(1000);
+
+                assert!(token.approve(acc.bob, u128::MAX - 1).is_ok());
+                let start = recorded_events().count();
+                assert_eq!(
+                    token.increase_allowance(acc.bob, 10),
+                    Err(PSP22Error::AllowanceOverflow)
+                );
+                assert_eq!(token.allowance(acc.alice, acc.bob), u128::MAX - 1);
+                assert_eq!(get_events(start).len(), 0);
+
+                assert!(token.increase_allowance(acc.bob, 1).is_ok());
+                assert_eq!(token.allowance(acc.alice, acc.bob), u128::MAX);
+                let events = get_events(start);
+                assert_eq!(events.len(), 1);
+                assert_approval(&events[0], acc.alice, acc.bob, u128::MAX);
+            }
+
+            #[ink::test]
+            fn decrease_allowance_from_max_works() {
+                let acc = default_accounts::<E>();
+                set_caller::<E>(acc.alice);
+                let mut token = $constructor(1000);
+
+                assert!(token.approve(acc.bob, u128::MAX).is_ok());
+                assert!(token.decrease_allowance(acc.bob, u128::MAX).is_ok());
+                assert_eq!(token.allowance(acc.alice, acc.bob), 0);
+                assert_eq!(
+                    token.decrease_allowance(acc.bob, 1),
+                    Err(PSP22Error::InsufficientAllowance)
+                );
+            }
+        }
+    };
+}
+
+/// Inserts a suite of ink! unit tests intended for a contract implementing PSP22Metadata trait.
+/// `$contract` argument should be the name of the contract struct.
+/// `$constructor` argument should be the name of a function, which initializes `$contract`
+/// with the given `Option<String>` token URI.
+/// This macro should be invoked inside `#[ink::contract]` module.
+#[macro_export]
+macro_rules! metadata_tests {
+    ($contract:ident, $constructor:expr) => {
+        #[allow(clippy::redundant_closure_call)]
+        mod psp22_metadata_tests {
+            use super::*;
+            use ink::prelude::string::String;
+            use $crate::PSP22Metadata;
+
+            #[ink::test]
+            fn token_uri_matches_constructor_argument() {
+                let uri = String::from(
+                    "ipfs://bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi",
+                );
+                let token: $contract = $constructor(Some(uri.clone()));
+                assert_eq!(token.token_uri(), Some(uri));
+
+                let uri = String::from("https://example.com/token.json");
+                let token: $contract = $constructor(Some(uri.clone()));
+                assert_eq!(token.token_uri(), Some(uri));
+            }
+
+            #[ink::test]
+            fn token_uri_can_be_none() {
+                let token: $contract = $constructor(None);
+                assert_eq!(token.token_uri(), None);
+            }
+        }
+    };
+}
+
+/// Inserts ink! unit tests checking that the PSP22Capped and PSP22Snapshot extensions
+/// compose: capped mints must be recorded by the snapshots, and mints rejected by the cap
+/// must leave them intact.
+/// `$contract` argument should be the name of the contract struct, implementing `PSP22`,
+/// `PSP22Mintable`, `PSP22Capped` and `PSP22Snapshot`.
+/// `$constructor` argument should be the name of a function, which initializes `$contract`
+/// with the given total supply and cap. The caller must be allowed to mint.
+/// This macro should be invoked inside `#[ink::contract]` module.
+#[macro_export]
+macro_rules! combined_tests {
+    ($contract:ident, $constructor:expr) => {
+        #[allow(clippy::redundant_closure_call)]
+        mod combined_tests {
+            use super::*;
+            use ink::env::{test::*, DefaultEnvironment as E};
+            use $crate::{PSP22Capped, PSP22Error, PSP22Mintable, PSP22Snapshot, PSP22};
+
+            #[ink::test]
+            fn mint_respects_cap() {
+                let acc = default_accounts::<E>();
+                set_caller::<E>(acc.alice);
+                let mut token: $contract = $constructor(900, 1000);
+
+                assert_eq!(token.cap(), 1000);
+                assert!(token.mint(100).is_ok());
+                assert_eq!(token.total_supply(), 1000);
+                assert_eq!(token.mint(1), Err(PSP22Error::CapExceeded));
+                assert!(token.mint(0).is_ok());
+                assert_eq!(token.total_supply(), 1000);
+                assert_eq!(token.balance_of(acc.alice), 1000);
+            }
+
+            #[ink::test]
+            fn snapshots_record_capped_mints() {
+                let acc = default_accounts::<E>();
+                set_caller::<E>(acc.alice);
+                let mut token: $contract = $constructor(500, 1000);
+
+                let first = token.snapshot().unwrap();
+                let first_block = token.clock();
+                advance_block::<E>();
+                assert!(token.mint(300).is_ok());
+                let second = token.snapshot().unwrap();
+                let second_block = token.clock();
+                advance_block::<E>();
+                // Rejected by the cap, must not change the snapshots
+                assert!(token.mint(201).is_err());
+                assert!(token.mint(200).is_ok());
+                advance_block::<E>();
+
+                assert_eq!(token.total_supply_at(first), Ok(500));
+                assert_eq!(token.total_supply_at(second), Ok(800));
+                assert_eq!(token.balance_of_at(acc.alice, first), Ok(500));
+                assert_eq!(token.balance_of_at(acc.alice, second), Ok(800));
+                assert_eq!(token.get_past_total_supply(first_block), 500);
+                assert_eq!(token.get_past_total_supply(second_block), 800);
+                assert_eq!(token.total_supply(), 1000);
             }
         }
     };
@@ -3,7 +3,8 @@ use ink::{
     primitives::AccountId,
 };
 
-use crate::errors::PSP22Error;
+use crate::access_control::RoleType;
+use crate::errors::{AccessControlError, OwnableError, PSP22Error};
 
 #[ink::trait_definition]
 pub trait PSP22 {
@@ -30,12 +31,16 @@ pub trait PSP22 {
     ///
     /// On success a `Transfer` event is emitted.
     ///
-    /// No-op if the caller and `to` is the same address or `value` is zero, returns success
-    /// and no events are emitted.
+    /// No-op if `value` is zero, returns success and no events are emitted.
+    ///
+    /// If the caller and `to` is the same address, the balance is left unchanged and by default
+    /// no events are emitted (see `PSP22Data::enable_self_transfer_events`), but the transfer
+    /// still fails if `value` exceeds the caller's balance.
     ///
     /// # Errors
     ///
-    /// Reverts with `InsufficientBalance` if the `value` exceeds the caller's balance.
+    /// Reverts with `InsufficientBalance` if the `value` exceeds the caller's balance,
+    /// also when the caller and `to` is the same address.
     #[ink(message)]
     fn transfer(&mut self, to: AccountId, value: u128, data: Vec<u8>) -> Result<(), PSP22Error>;
 
@@ -49,8 +54,11 @@ pub trait PSP22 {
     ///
     /// On success a `Transfer` event is emitted.
     ///
-    /// No-op if `from` and `to` is the same address or `value` is zero, returns success
-    /// and no events are emitted.
+    /// No-op if `value` is zero, returns success and no events are emitted.
+    ///
+    /// If `from` and `to` is the same address, balances and the allowance are left unchanged
+    /// and by default no events are emitted, but the transfer still fails if `value` exceeds
+    /// the balance of `from` or the allowance, as below.
     ///
     /// If `from` and the caller are different addresses, a successful transfer results
     /// in decreased allowance by `from` to the caller and an `Approval` event with
@@ -95,6 +103,10 @@ pub trait PSP22 {
     ///
     /// No-op if the caller and `spender` is the same address or `delta-value` is zero, returns success
     /// and no events are emitted.
+    ///
+    /// # Errors
+    ///
+    /// Reverts with `AllowanceOverflow` if the allowance would exceed `u128::MAX`.
     #[ink(message)]
     fn increase_allowance(
         &mut self,
@@ -132,8 +144,26 @@ pub trait PSP22Metadata {
     #[ink(message)]
     fn token_symbol(&self) -> Option<String>;
     /// Returns the token decimals.
+    ///
+    /// The scaling factor of amounts, `10^decimals`, is returned by `psp22::decimals_factor`.
     #[ink(message)]
     fn token_decimals(&self) -> u8;
+    /// Returns the URI of a JSON document with the off-chain metadata of the token
+    /// (e.g. `ipfs://<CID>` or an HTTPS URL), or `None` if the token has no such document.
+    ///
+    /// The document is an object with the following fields, all optional:
+    /// - `name` (string) - the token name,
+    /// - `symbol` (string) - the token symbol,
+    /// - `decimals` (integer) - the token decimals,
+    /// - `description` (string) - a human-readable description of the token,
+    /// - `image` (string) - the URI of the token logo, preferably a square SVG or PNG,
+    /// - `external_url` (string) - the URL of the project website,
+    /// - `links` (object) - URLs of social profiles keyed by platform, e.g. `{"x": "https://x.com/..."}`.
+    ///
+    /// `name`, `symbol` and `decimals` should match the values returned by the other
+    /// messages of this trait, which take precedence in case of a mismatch.
+    #[ink(message)]
+    fn token_uri(&self) -> Option<String>;
 }
 
 #[ink::trait_definition]
@@ -174,3 +204,833 @@ pub trait PSP22Mintable {
     #[ink(message)]
     fn mint(&mut self, value: u128) -> Result<(), PSP22Error>;
 }
+
+/// A fixed limit of the total supply, enforced on minting with `PSP22Data::mint_capped`,
+/// which fails with `CapExceeded` above the cap.
+#[ink::trait_definition]
+pub trait PSP22Capped {
+    /// Returns the maximal total supply of the token.
+    #[ink(message)]
+    fn cap(&self) -> u128;
+}
+
+/// A PSP22 token wrapping another (underlying) PSP22 token 1:1.
+///
+/// Wrapped tokens are minted when underlying tokens are deposited and burned
+/// when they are withdrawn, so the underlying balance of the wrapper is never
+/// lower than its total supply.
+#[ink::trait_definition]
+pub trait PSP22Wrapper {
+    /// Transfers `value` underlying tokens from the caller to the wrapper
+    /// and mints `value` wrapped tokens to `account`.
+    ///
+    /// The caller must have approved the wrapper to spend at least `value` underlying tokens.
+    ///
+    /// The selector for this message is `0x4ce2ea83` (first 4 bytes of `blake2b_256("PSP22Wrapper::deposit_for")`).
+    ///
+    /// # Events
+    ///
+    /// On success a `Transfer` event is emitted with `None` sender.
+    ///
+    /// # Errors
+    ///
+    /// Reverts with the error returned by the underlying token if the transfer of underlying tokens fails.
+    ///
+    /// Reverts with `UnderlyingCallFailed` if the call to the underlying token fails without returning an error.
+    #[ink(message)]
+    fn deposit_for(&mut self, account: AccountId, value: u128) -> Result<(), PSP22Error>;
+
+    /// Burns `value` wrapped tokens of the caller and transfers `value` underlying tokens to `account`.
+    ///
+    /// The selector for this message is `0xbae15da6` (first 4 bytes of `blake2b_256("PSP22Wrapper::withdraw_to")`).
+    ///
+    /// # Events
+    ///
+    /// On success a `Transfer` event is emitted with `None` recipient.
+    ///
+    /// # Errors
+    ///
+    /// Reverts with `InsufficientBalance` if the `value` exceeds the caller's balance.
+    ///
+    /// Reverts with the error returned by the underlying token if the transfer of underlying tokens fails.
+    ///
+    /// Reverts with `UnderlyingCallFailed` if the call to the underlying token fails without returning an error.
+    #[ink(message)]
+    fn withdraw_to(&mut self, account: AccountId, value: u128) -> Result<(), PSP22Error>;
+
+    /// Transfers the surplus of underlying tokens (sent to the wrapper directly instead of
+    /// being deposited) to `to` and returns its amount. Restricted to the owner of the wrapper.
+    ///
+    /// The surplus is the underlying balance of the wrapper minus its total supply,
+    /// so the recovery never breaks the backing of wrapped tokens.
+    ///
+    /// The selector for this message is `0x138c2d73` (first 4 bytes of `blake2b_256("PSP22Wrapper::recover_underlying")`).
+    ///
+    /// # Errors
+    ///
+    /// Reverts with `Custom (caller is not the owner)` if the caller is not the owner.
+    ///
+    /// Reverts with the error returned by the underlying token if the transfer of underlying tokens fails.
+    ///
+    /// Reverts with `UnderlyingCallFailed` if the call to the underlying token fails without returning an error.
+    #[ink(message)]
+    fn recover_underlying(&mut self, to: AccountId) -> Result<u128, PSP22Error>;
+
+    /// Returns the address of the underlying PSP22 token.
+    ///
+    /// The selector for this message is `0xcd48a1dd` (first 4 bytes of `blake2b_256("PSP22Wrapper::underlying")`).
+    #[ink(message)]
+    fn underlying(&self) -> AccountId;
+}
+
+#[ink::trait_definition]
+pub trait PSP22Domain {
+    /// Returns the domain separator included in the payloads of all messages signed
+    /// for this contract (e.g. permits and meta-transactions).
+    ///
+    /// It is a hash of the name and the version of the contract, the chain id
+    /// and the address of the contract, so a signature is valid only for this deployment.
+    #[ink(message)]
+    fn domain_separator(&self) -> [u8; 32];
+}
+
+#[ink::trait_definition]
+pub trait PSP22Permit {
+    /// Sets the allowance granted by `owner` to `spender` to `value`, authorized by
+    /// an off-chain sr25519 `signature` of `owner` instead of a transaction sent by `owner`.
+    ///
+    /// The signed message is the payload returned by `PSP22PermitData::permit_payload`:
+    /// a hash binding the contract's domain separator (see `PSP22Domain`), `owner`, `spender`, `value`,
+    /// `nonce` and `deadline`.
+    ///
+    /// On success the nonce of `owner` is incremented, so each signature can be used only once.
+    ///
+    /// # Events
+    ///
+    /// On success an `Approval` event and a `PermitUsed` event are emitted.
+    ///
+    /// # Errors
+    ///
+    /// Reverts with `DeadlineExpired` if the current block timestamp is greater than `deadline`.
+    ///
+    /// Reverts with `NonceMismatch` if `nonce` is not the current nonce of `owner`,
+    /// e.g. if the permit has already been used or invalidated.
+    ///
+    /// Reverts with `InvalidSignature` if `signature` is not a valid signature of `owner`
+    /// for the expected payload.
+    #[ink(message)]
+    fn permit(
+        &mut self,
+        owner: AccountId,
+        spender: AccountId,
+        value: u128,
+        nonce: u64,
+        deadline: u64,
+        signature: Vec<u8>,
+    ) -> Result<(), PSP22Error>;
+
+    /// Returns the current permit nonce of `owner`.
+    ///
+    /// A permit signature is valid only for the nonce returned by this method.
+    #[ink(message)]
+    fn nonces(&self, owner: AccountId) -> u64;
+
+    /// Advances the nonce of the caller to `new_nonce`, invalidating all permits
+    /// signed by the caller but not submitted yet, e.g. after the key was compromised.
+    ///
+    /// # Errors
+    ///
+    /// Reverts with `Custom (nonce can only move forward)` if `new_nonce` is not greater
+    /// than the current nonce of the caller.
+    #[ink(message)]
+    fn invalidate_nonces(&mut self, new_nonce: u64) -> Result<(), PSP22Error>;
+}
+
+#[ink::trait_definition]
+pub trait PSP22MetaTx {
+    /// Transfers `value` tokens from `owner` to `to` on behalf of the caller (the relayer),
+    /// authorized by an off-chain sr25519 `signature` of `owner`. The caller pays for the gas
+    /// and receives `fee` tokens from `owner` in return.
+    ///
+    /// The signed message is the payload returned by `PSP22MetaTxData::transfer_payload`:
+    /// a hash binding the contract's domain separator (see `PSP22Domain`), `owner`, `to`, `value`, `fee`,
+    /// `nonce` and `deadline`.
+    ///
+    /// On success the nonce of `owner` is incremented, so each signature can be used only once.
+    ///
+    /// # Events
+    ///
+    /// On success a `Transfer` event is emitted for the transfer to `to` and another one
+    /// for the fee paid to the caller (unless the fee is `0`).
+    ///
+    /// # Errors
+    ///
+    /// Reverts with `DeadlineExpired` if the current block timestamp is greater than `deadline`.
+    ///
+    /// Reverts with `InvalidSignature` if `signature` is not a valid signature of `owner`
+    /// for the expected payload.
+    ///
+    /// Reverts with `NonceMismatch` if `nonce` is not the current meta-transaction nonce
+    /// of `owner`, in particular if the meta-transaction was already executed.
+    ///
+    /// Reverts with `InsufficientBalance` if `owner` holds less than `value + fee` tokens.
+    #[ink(message)]
+    #[allow(clippy::too_many_arguments)]
+    fn transfer_with_signature(
+        &mut self,
+        owner: AccountId,
+        to: AccountId,
+        value: u128,
+        fee: u128,
+        deadline: u64,
+        nonce: u64,
+        signature: Vec<u8>,
+    ) -> Result<(), PSP22Error>;
+
+    /// Returns the current meta-transaction nonce of `owner`.
+    ///
+    /// A meta-transaction signature is valid only for the nonce returned by this method.
+    #[ink(message)]
+    fn meta_tx_nonce(&self, owner: AccountId) -> u64;
+}
+
+#[ink::trait_definition]
+pub trait PSP22Snapshot {
+    /// Takes a snapshot of all balances and the total supply and returns its id.
+    ///
+    /// Snapshot ids are consecutive numbers starting from `1`.
+    ///
+    /// # Events
+    ///
+    /// On success a `SnapshotCreated` event is emitted.
+    #[ink(message)]
+    fn snapshot(&mut self) -> Result<u64, PSP22Error>;
+
+    /// Returns the balance of `account` at the time snapshot `snapshot_id` was taken.
+    ///
+    /// # Errors
+    ///
+    /// Reverts with `Custom (snapshot does not exist)` if no snapshot with `snapshot_id` was taken.
+    #[ink(message)]
+    fn balance_of_at(&self, account: AccountId, snapshot_id: u64) -> Result<u128, PSP22Error>;
+
+    /// Returns the total supply at the time snapshot `snapshot_id` was taken.
+    ///
+    /// # Errors
+    ///
+    /// Reverts with `Custom (snapshot does not exist)` if no snapshot with `snapshot_id` was taken.
+    #[ink(message)]
+    fn total_supply_at(&self, snapshot_id: u64) -> Result<u128, PSP22Error>;
+
+    /// Returns the total supply recorded by the most recent snapshot taken
+    /// not later than at `timepoint` (see `clock`).
+    ///
+    /// Returns `0` if no snapshot was taken until `timepoint`, and the current
+    /// total supply if `timepoint` is the current one or a future one.
+    #[ink(message)]
+    fn get_past_total_supply(&self, timepoint: u64) -> u128;
+
+    /// Returns the current timepoint of the clock used to record snapshots:
+    /// either the block number or the block timestamp.
+    #[ink(message)]
+    fn clock(&self) -> u64;
+
+    /// Returns the description of the clock, as in ERC-6372: `mode=blocknumber&from=default`
+    /// for block numbers or `mode=timestamp` for timestamps.
+    #[ink(message)]
+    fn clock_mode(&self) -> String;
+}
+
+/// Vote delegation and historical voting power, following the semantics of
+/// OpenZeppelin's ERC20Votes with one difference: an account which has never delegated
+/// is its own delegatee, so token holders have voting power without any action.
+///
+/// Timepoints are block numbers, unless the token uses a timestamp-based clock
+/// (see `Clock`).
+#[ink::trait_definition]
+pub trait PSP22Votes {
+    /// Delegates the voting power of the caller (equal to its balance) to `delegatee`.
+    ///
+    /// Any account can delegate, also one with zero balance: its future balance
+    /// will be counted as votes of `delegatee`.
+    ///
+    /// # Events
+    ///
+    /// On success a `DelegateChanged` event is emitted, followed by `DelegateVotesChanged`
+    /// events for the previous and the new delegatee if the voting power moved.
+    #[ink(message)]
+    fn delegate(&mut self, delegatee: AccountId) -> Result<(), PSP22Error>;
+
+    /// Returns the delegatee of `account`, which is `account` itself if it has never delegated.
+    #[ink(message)]
+    fn delegates(&self, account: AccountId) -> AccountId;
+
+    /// Returns the current voting power of `account`: the sum of balances of all
+    /// accounts delegating to it. An account with zero balance and no delegators has zero votes.
+    #[ink(message)]
+    fn get_votes(&self, account: AccountId) -> u128;
+
+    /// Returns the current voting power of each of `accounts`, in the same order,
+    /// e.g. for governance UIs listing many accounts.
+    #[ink(message)]
+    fn get_votes_batch(&self, accounts: Vec<AccountId>) -> Vec<u128>;
+
+    /// Returns the voting power of `account` at the end of the given past timepoint.
+    ///
+    /// # Errors
+    ///
+    /// Reverts with `Custom (block not yet mined)` if `timepoint` is the current
+    /// or a future one, as the voting power at its end is not known yet.
+    #[ink(message)]
+    fn get_past_votes(&self, account: AccountId, timepoint: u64) -> Result<u128, PSP22Error>;
+}
+
+/// Flash loans of the token itself, following ERC-3156.
+#[ink::trait_definition]
+pub trait PSP22FlashLender {
+    /// Returns the maximal amount of `token` available for a flash loan.
+    ///
+    /// Returns `0` if `token` is not this contract, otherwise the amount of tokens
+    /// which can still be minted (`u128::MAX - total_supply()`).
+    #[ink(message)]
+    fn max_flash_loan(&self, token: AccountId) -> u128;
+
+    /// Returns the fee charged for a flash loan of `amount` of `token`.
+    ///
+    /// # Errors
+    ///
+    /// Reverts with `Custom (unsupported token)` if `token` is not this contract.
+    #[ink(message)]
+    fn flash_fee(&self, token: AccountId, amount: u128) -> Result<u128, PSP22Error>;
+
+    /// Lends `amount` of `token` to `receiver`, which must implement `PSP22FlashBorrower`.
+    ///
+    /// The tokens are minted to `receiver`, after which its `on_flash_loan` is called with
+    /// the caller as `initiator` and `data`. Then `amount` plus the fee is burned from
+    /// `receiver`, which must approve this contract to spend it during the callback.
+    ///
+    /// The selector for this message is `0xe60fb06b` (first 4 bytes of `blake2b_256("PSP22FlashLender::flash_loan")`).
+    ///
+    /// # Events
+    ///
+    /// On success a `Transfer` event with `None` sender for the loan, an `Approval` and
+    /// a `Transfer` event with `None` recipient for the repayment, and a `FlashLoan` event
+    /// are emitted.
+    ///
+    /// # Errors
+    ///
+    /// Reverts with `Custom (unsupported token)` if `token` is not this contract.
+    ///
+    /// Reverts with `Custom` error if `amount` exceeds `max_flash_loan(token)`.
+    ///
+    /// Reverts with `FlashLoanFailed` if `on_flash_loan` fails or does not return
+    /// `CALLBACK_SUCCESS`, or if the loan is not repaid.
+    #[ink(message)]
+    fn flash_loan(
+        &mut self,
+        receiver: AccountId,
+        token: AccountId,
+        amount: u128,
+        data: Vec<u8>,
+    ) -> Result<(), PSP22Error>;
+}
+
+/// Linear vesting of newly minted tokens, with a cliff.
+///
+/// Each beneficiary has at most one vesting schedule. Its tokens are minted
+/// when released, so they are not a part of the total supply before.
+#[ink::trait_definition]
+pub trait PSP22Vesting {
+    /// Creates a schedule vesting `amount` tokens to `beneficiary` linearly between
+    /// the `start` timestamp and `start + duration`, with nothing vested before `start + cliff`.
+    ///
+    /// # Events
+    ///
+    /// On success a `VestingScheduleCreated` event is emitted.
+    ///
+    /// # Errors
+    ///
+    /// Reverts with `Custom` error if `beneficiary` already has a schedule or the schedule
+    /// is invalid (zero `duration` or `cliff` longer than `duration`).
+    #[ink(message)]
+    fn create_vesting_schedule(
+        &mut self,
+        beneficiary: AccountId,
+        start: u64,
+        cliff: u64,
+        duration: u64,
+        amount: u128,
+    ) -> Result<(), PSP22Error>;
+
+    /// Returns the amount of tokens vested for `beneficiary` so far, including the released ones.
+    #[ink(message)]
+    fn vested_amount(&self, beneficiary: AccountId) -> u128;
+
+    /// Mints the vested, but not yet released tokens to `beneficiary`.
+    /// Anyone can release tokens on behalf of the beneficiary.
+    ///
+    /// # Events
+    ///
+    /// On success a `Transfer` event with `None` sender and a `TokensReleased` event are emitted.
+    ///
+    /// # Errors
+    ///
+    /// Reverts with `ZeroAmount` if there are no such tokens.
+    #[ink(message)]
+    fn release(&mut self, beneficiary: AccountId) -> Result<(), PSP22Error>;
+}
+
+/// Airdrop of newly minted tokens to accounts proving their eligibility with a Merkle proof
+/// against a root set by the administrator, see `PSP22AirdropData` for the tree format.
+#[ink::trait_definition]
+pub trait PSP22Airdrop {
+    /// Sets the Merkle root of eligible accounts and amounts.
+    ///
+    /// # Errors
+    ///
+    /// Reverts with `Custom` error if the caller is not allowed to set the root.
+    #[ink(message)]
+    fn set_merkle_root(&mut self, root: [u8; 32]) -> Result<(), PSP22Error>;
+
+    /// Mints `amount` tokens to `account`, provided that `proof` proves `account`
+    /// is eligible for `amount` tokens. Anyone can claim on behalf of `account`.
+    ///
+    /// # Events
+    ///
+    /// On success a `Transfer` event with `None` sender and an `AirdropClaimed` event are emitted.
+    ///
+    /// # Errors
+    ///
+    /// Reverts with `AlreadyClaimed` if `account` has already claimed its tokens.
+    ///
+    /// Reverts with `InvalidProof` if `proof` does not match the Merkle root.
+    ///
+    /// Reverts with `ZeroAmount` if `amount` is zero.
+    #[ink(message)]
+    fn claim(
+        &mut self,
+        account: AccountId,
+        amount: u128,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<(), PSP22Error>;
+}
+
+/// Minting announced in advance: mints are queued by the administrator and can be executed
+/// by anyone only after a delay, during which holders can react to them.
+#[ink::trait_definition]
+pub trait PSP22Timelock {
+    /// Queues a mint of `value` tokens to `to`, executable once the block timestamp
+    /// reaches `execute_after`, and returns its id.
+    ///
+    /// # Events
+    ///
+    /// On success a `MintQueued` event is emitted.
+    ///
+    /// # Errors
+    ///
+    /// Reverts with `Custom` error if the caller is not allowed to queue mints.
+    ///
+    /// Reverts with `Custom (delay below min_delay)` if `execute_after` is earlier than
+    /// the minimal delay of the token after the current block timestamp.
+    #[ink(message)]
+    fn queue_mint(
+        &mut self,
+        to: AccountId,
+        value: u128,
+        execute_after: u64,
+    ) -> Result<u64, PSP22Error>;
+
+    /// Executes the queued mint with `id`.
+    ///
+    /// # Events
+    ///
+    /// On success a `Transfer` event with `None` sender is emitted.
+    ///
+    /// # Errors
+    ///
+    /// Reverts with `Custom (unknown queued mint)` if there is no such mint, also if it has
+    /// already been executed or cancelled.
+    ///
+    /// Reverts with `Custom (timelock not expired)` if the block timestamp is lower
+    /// than `execute_after` of the mint.
+    #[ink(message)]
+    fn execute_mint(&mut self, id: u64) -> Result<(), PSP22Error>;
+
+    /// Cancels the queued mint with `id`.
+    ///
+    /// # Events
+    ///
+    /// On success a `MintCancelled` event is emitted.
+    ///
+    /// # Errors
+    ///
+    /// Reverts with `Custom (unknown queued mint)` if there is no such mint.
+    ///
+    /// Reverts with `Custom` error if the caller is not allowed to cancel mints.
+    #[ink(message)]
+    fn cancel_mint(&mut self, id: u64) -> Result<(), PSP22Error>;
+}
+
+/// Restriction of transfers to whitelisted accounts, e.g. for regulated security tokens.
+#[ink::trait_definition]
+pub trait PSP22Whitelist {
+    /// Adds `account` to the whitelist.
+    ///
+    /// # Events
+    ///
+    /// An `AddedToWhitelist` event is emitted if `account` was not whitelisted before.
+    ///
+    /// # Errors
+    ///
+    /// Reverts with `Custom` error if the caller is not allowed to modify the whitelist.
+    #[ink(message)]
+    fn add_to_whitelist(&mut self, account: AccountId) -> Result<(), PSP22Error>;
+
+    /// Removes `account` from the whitelist.
+    ///
+    /// # Events
+    ///
+    /// A `RemovedFromWhitelist` event is emitted if `account` was whitelisted before.
+    ///
+    /// # Errors
+    ///
+    /// Reverts with `Custom` error if the caller is not allowed to modify the whitelist.
+    #[ink(message)]
+    fn remove_from_whitelist(&mut self, account: AccountId) -> Result<(), PSP22Error>;
+
+    /// Adds all `accounts` to the whitelist.
+    ///
+    /// # Events
+    ///
+    /// An `AddedToWhitelist` event is emitted for each account not whitelisted before.
+    ///
+    /// # Errors
+    ///
+    /// Reverts with `Custom` error if the caller is not allowed to modify the whitelist.
+    #[ink(message)]
+    fn add_to_whitelist_batch(&mut self, accounts: Vec<AccountId>) -> Result<(), PSP22Error>;
+
+    /// Removes all `accounts` from the whitelist.
+    ///
+    /// # Events
+    ///
+    /// A `RemovedFromWhitelist` event is emitted for each account whitelisted before.
+    ///
+    /// # Errors
+    ///
+    /// Reverts with `Custom` error if the caller is not allowed to modify the whitelist.
+    #[ink(message)]
+    fn remove_from_whitelist_batch(&mut self, accounts: Vec<AccountId>) -> Result<(), PSP22Error>;
+
+    /// Returns `true` if `account` is whitelisted.
+    #[ink(message)]
+    fn is_whitelisted(&self, account: AccountId) -> bool;
+
+    /// Enables (`enabled == true`) or disables the whitelist. While it is enabled, `transfer`
+    /// and `transfer_from` revert with `TransferRestricted (not whitelisted)` if the sender
+    /// or the recipient is not whitelisted, and so do mints to accounts not whitelisted.
+    /// The owner of the contract is exempt.
+    ///
+    /// # Errors
+    ///
+    /// Reverts with `Custom` error if the caller is not allowed to toggle the whitelist.
+    #[ink(message)]
+    fn set_whitelist_enabled(&mut self, enabled: bool) -> Result<(), PSP22Error>;
+}
+
+/// Minting and burning of tokens bridged from and to another chain by accounts with
+/// the bridge role (`BRIDGE_ROLE` of `AccessControl`), see `PSP22BridgeData`.
+#[ink::trait_definition]
+pub trait PSP22Bridgeable {
+    /// Mints `value` tokens to `to`, bridged in by the transaction `tx_ref` on the other chain.
+    ///
+    /// # Events
+    ///
+    /// On success a `Transfer` event with `None` sender and a `BridgedIn` event are emitted.
+    ///
+    /// # Errors
+    ///
+    /// Reverts with `Custom (caller is missing the role)` if the caller does not have `BRIDGE_ROLE`.
+    ///
+    /// Reverts with `Custom (tx_ref already processed)` if `tx_ref` has been used before.
+    #[ink(message)]
+    fn bridge_mint(
+        &mut self,
+        to: AccountId,
+        value: u128,
+        tx_ref: Vec<u8>,
+    ) -> Result<(), PSP22Error>;
+
+    /// Burns `value` tokens of `from`, bridged out to `dest` on the other chain.
+    /// Unless `from` is the caller, the tokens are spent from the allowance given by `from`
+    /// to the caller, like in `PSP22::transfer_from`.
+    ///
+    /// # Events
+    ///
+    /// On success a `Transfer` event with `None` recipient, an `Approval` event with
+    /// the decreased allowance (unless `from` is the caller) and a `BridgedOut` event are emitted.
+    ///
+    /// # Errors
+    ///
+    /// Reverts with `Custom (caller is missing the role)` if the caller does not have `BRIDGE_ROLE`.
+    ///
+    /// Reverts with `InsufficientAllowance` if the allowance of the caller is lower than `value`.
+    ///
+    /// Reverts with `InsufficientBalance` if `from` holds less than `value` tokens.
+    #[ink(message)]
+    fn bridge_burn(
+        &mut self,
+        from: AccountId,
+        value: u128,
+        dest: Vec<u8>,
+    ) -> Result<(), PSP22Error>;
+}
+
+/// Blocking of specific accounts from sending and receiving tokens, e.g. for compliance
+/// and anti-fraud measures. Independent of `PSP22Whitelist`.
+#[ink::trait_definition]
+pub trait PSP22Blacklist {
+    /// Adds `account` to the blacklist. While blacklisted, `account` can neither send nor
+    /// receive tokens: `transfer` and `transfer_from` revert with `Blacklisted`.
+    ///
+    /// # Events
+    ///
+    /// An `AddedToBlacklist` event is emitted if `account` was not blacklisted before.
+    ///
+    /// # Errors
+    ///
+    /// Reverts with `Custom` error if the caller is not allowed to modify the blacklist.
+    #[ink(message)]
+    fn add_to_blacklist(&mut self, account: AccountId) -> Result<(), PSP22Error>;
+
+    /// Removes `account` from the blacklist.
+    ///
+    /// # Events
+    ///
+    /// A `RemovedFromBlacklist` event is emitted if `account` was blacklisted before.
+    ///
+    /// # Errors
+    ///
+    /// Reverts with `Custom` error if the caller is not allowed to modify the blacklist.
+    #[ink(message)]
+    fn remove_from_blacklist(&mut self, account: AccountId) -> Result<(), PSP22Error>;
+
+    /// Returns `true` if `account` is blacklisted.
+    #[ink(message)]
+    fn is_blacklisted(&self, account: AccountId) -> bool;
+}
+
+/// A single owner allowed to call privileged messages of the contract, see `OwnableData`.
+#[ink::trait_definition]
+pub trait Ownable {
+    /// Returns the current owner, `None` if ownership has been renounced.
+    #[ink(message)]
+    fn owner(&self) -> Option<AccountId>;
+
+    /// Makes `new_owner` the owner of the contract.
+    ///
+    /// # Events
+    ///
+    /// On success an `OwnershipTransferred` event is emitted.
+    ///
+    /// # Errors
+    ///
+    /// Reverts with `CallerIsNotOwner` if the caller is not the owner.
+    ///
+    /// Reverts with `NewOwnerIsZero` if `new_owner` is the zero address.
+    #[ink(message)]
+    fn transfer_ownership(&mut self, new_owner: AccountId) -> Result<(), OwnableError>;
+
+    /// Leaves the contract without an owner, so privileged messages cannot be called anymore.
+    ///
+    /// # Events
+    ///
+    /// On success an `OwnershipTransferred` event with `None` new owner is emitted.
+    ///
+    /// # Errors
+    ///
+    /// Reverts with `CallerIsNotOwner` if the caller is not the owner.
+    #[ink(message)]
+    fn renounce_ownership(&mut self) -> Result<(), OwnableError>;
+}
+
+/// Roles allowed to call privileged messages of the contract, see `AccessControlData`.
+#[ink::trait_definition]
+pub trait AccessControl {
+    /// Returns `true` if `account` has `role`.
+    #[ink(message)]
+    fn has_role(&self, role: RoleType, account: AccountId) -> bool;
+
+    /// Grants `role` to `account`.
+    ///
+    /// # Events
+    ///
+    /// A `RoleGranted` event is emitted, unless `account` already has `role`.
+    ///
+    /// # Errors
+    ///
+    /// Reverts with `MissingRole` if the caller is not an admin.
+    #[ink(message)]
+    fn grant_role(&mut self, role: RoleType, account: AccountId) -> Result<(), AccessControlError>;
+
+    /// Revokes `role` from `account`.
+    ///
+    /// # Events
+    ///
+    /// A `RoleRevoked` event is emitted, unless `account` does not have `role`.
+    ///
+    /// # Errors
+    ///
+    /// Reverts with `MissingRole` if the caller is not an admin.
+    #[ink(message)]
+    fn revoke_role(&mut self, role: RoleType, account: AccountId)
+        -> Result<(), AccessControlError>;
+
+    /// Revokes `role` from the caller. `account` must be the caller, as a confirmation.
+    ///
+    /// # Events
+    ///
+    /// A `RoleRevoked` event is emitted, unless the caller does not have `role`.
+    ///
+    /// # Errors
+    ///
+    /// Reverts with `InvalidCaller` if `account` is not the caller.
+    #[ink(message)]
+    fn renounce_role(
+        &mut self,
+        role: RoleType,
+        account: AccountId,
+    ) -> Result<(), AccessControlError>;
+}
+
+/// Listing of the allowances granted by an owner, e.g. for wallets showing active approvals
+/// to be revoked. Backed by the allowance index of `PSP22Data`, see
+/// `PSP22Data::enable_allowance_index`.
+#[ink::trait_definition]
+pub trait PSP22AllowanceEnumerable {
+    /// Returns the spenders with a non-zero allowance granted by `owner`, in no particular order.
+    #[ink(message)]
+    fn spenders_of(&self, owner: AccountId) -> Vec<AccountId>;
+
+    /// Returns the spenders with a non-zero allowance granted by `owner` together with
+    /// the allowances, in no particular order.
+    #[ink(message)]
+    fn approvals_of(&self, owner: AccountId) -> Vec<(AccountId, u128)>;
+
+    /// Clears the allowances granted by the caller to all spenders, e.g. when a spender
+    /// is compromised. A single call clears a bounded number of allowances, see
+    /// `PSP22Data::revoke_all_approvals`. Returns the number of allowances left,
+    /// the message should be called until it returns `0`.
+    ///
+    /// # Events
+    ///
+    /// An `Approval` event with zero amount is emitted for each cleared allowance.
+    #[ink(message)]
+    fn revoke_all_approvals(&mut self) -> u32;
+
+    /// Clears all the allowances granted by the caller in a single call, see
+    /// `PSP22Data::revoke_all`.
+    ///
+    /// # Events
+    ///
+    /// An `Approval` event with zero amount is emitted for each cleared allowance.
+    ///
+    /// # Errors
+    ///
+    /// Reverts with `Custom (allowance index disabled)` if the allowance index is disabled.
+    #[ink(message)]
+    fn revoke_all(&mut self) -> Result<(), PSP22Error>;
+}
+
+/// Batching of several `PSP22` messages of the caller into one atomic transaction.
+///
+/// The messages are executed directly on `PSP22Data`, so contracts doing more than that
+/// in their `PSP22` messages should not implement it with `impls::multicall`. This includes
+/// updating snapshots or votes and checking transfers against a blacklist (`PSP22BlacklistData`),
+/// a whitelist (`PSP22WhitelistData`), locked balances (`PSP22LockableData`)
+/// or `PSP22Restrictable`: the batched transfers would bypass these checks.
+#[ink::trait_definition]
+pub trait PSP22Multicall {
+    /// Executes several PSP22 messages of the caller in a single transaction, e.g. approve
+    /// a spender and decrease the allowances of others. Either all of them succeed
+    /// or the whole transaction is reverted.
+    ///
+    /// Each element of `calls` is a message selector followed by its SCALE-encoded
+    /// arguments, like the input of a contract call (see `psp22::encode_call`).
+    /// Only the messages of `PSP22` are supported, at most `MAX_MULTICALL_CALLS` of them.
+    /// They are executed within this contract with the caller of `multicall` as their caller,
+    /// without calling any contract, so no contract can reenter the token in the meantime.
+    ///
+    /// Returns the SCALE-encoded return value of each message.
+    ///
+    /// # Events
+    ///
+    /// On success the events of all the messages are emitted, in order.
+    ///
+    /// # Errors
+    ///
+    /// Reverts with the error of the first failing message.
+    ///
+    /// Reverts with `Custom (too many calls)` if there are more than `MAX_MULTICALL_CALLS` calls.
+    ///
+    /// Reverts with `Custom (invalid call)` if a call is not a `PSP22` message
+    /// with correctly encoded arguments.
+    #[ink(message)]
+    fn multicall(&mut self, calls: Vec<Vec<u8>>) -> Result<Vec<Vec<u8>>, PSP22Error>;
+}
+
+/// Machine-readable restrictions of transfers, similar to ERC-1404, e.g. for compliance tokens.
+/// Wallets and indexers can check whether a transfer would be restricted, and why,
+/// without simulating it.
+#[ink::trait_definition]
+pub trait PSP22Restrictable {
+    /// Returns the code of the restriction preventing `caller` from transferring `value`
+    /// tokens from `from` to `to`, or `NO_RESTRICTION` (`0`) if the transfer is not restricted.
+    /// `caller` is `from` itself for `PSP22::transfer` and the spender for `PSP22::transfer_from`.
+    ///
+    /// The codes are defined by the token, `message_for_restriction` describes them.
+    /// A transfer not restricted can still fail, e.g. with `InsufficientBalance`.
+    #[ink(message)]
+    fn detect_transfer_restriction(
+        &self,
+        caller: AccountId,
+        from: AccountId,
+        to: AccountId,
+        value: u128,
+    ) -> u8;
+
+    /// Returns a human-readable description of the restriction `code`.
+    #[ink(message)]
+    fn message_for_restriction(&self, code: u8) -> String;
+}
+
+/// Setting several allowances of the caller in a single transaction, e.g. approving all
+/// the routers a protocol integrates with at deployment time.
+#[ink::trait_definition]
+pub trait PSP22BatchApprove {
+    /// Sets the allowance granted by the caller to each `(spender, value)` of `approvals`,
+    /// like `PSP22::approve` for each of them, in order. Approvals of the caller itself
+    /// are skipped.
+    ///
+    /// # Events
+    ///
+    /// An `Approval` event is emitted for each approval not skipped.
+    ///
+    /// # Errors
+    ///
+    /// Reverts with the error of the first failing approval, e.g. `AllowanceCapExceeded`.
+    #[ink(message)]
+    fn batch_approve(&mut self, approvals: Vec<(AccountId, u128)>) -> Result<(), PSP22Error>;
+}
+
+/// Runtime detection of the extensions implemented by a token, similar to ERC-165.
+#[ink::trait_definition]
+pub trait PSP22Introspection {
+    /// Returns `true` if the token implements the extension identified by `id`.
+    ///
+    /// Identifiers of the extensions defined in this crate are listed in the `extensions` module.
+    ///
+    /// The selector for this message is `0xc196e781` (first 4 bytes of `blake2b_256("PSP22Introspection::supports_extension")`).
+    #[ink(message)]
+    fn supports_extension(&self, id: [u8; 4]) -> bool;
+}